@@ -0,0 +1,232 @@
+use alloc::vec::Drain;
+
+use vibe_hft_core::{Instrument, Price, Quantity, Timestamp};
+use vibe_hft_sbe_messages::{ExchangeID, ExecutionReport, MarketDataUpdate, Side, Trade};
+use vibe_hft_strategy::{Strategy, StrategyContext};
+
+use crate::fills::Fills;
+
+/// Timer id of the slicing clock; child orders use their `cl_ord_id`s, which start at 1
+const TICK: u64 = 0;
+
+/// The order the algo works on behalf of its caller
+#[derive(Debug, Clone, Copy)]
+pub struct ParentOrder {
+    pub exchange_id: ExchangeID,
+    pub instrument: Instrument,
+    pub side: Side,
+    /// Total quantity (1e8); should be a whole number of lots
+    pub quantity: Quantity,
+    /// Never buy above / sell below this price
+    pub limit_price: Option<Price>,
+    /// Schedule window (local time, ns)
+    pub start: Timestamp,
+    pub end: Timestamp,
+}
+
+impl ParentOrder {
+    /// Fraction of the schedule window elapsed at `now`, in `[0, 1]`
+    pub fn elapsed(&self, now: Timestamp) -> f64 {
+        if now >= self.end {
+            return 1.0;
+        }
+        let duration = self.end.saturating_sub(self.start);
+        if duration == 0 {
+            return 1.0;
+        }
+        now.saturating_sub(self.start) as f64 / duration as f64
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Slicing {
+    /// How often the schedule is re-evaluated (ns)
+    pub interval: u64,
+    /// Shortfalls below this wait for the next slice; the final slice ignores it
+    pub min_child: Quantity,
+    pub max_child: Quantity,
+}
+
+/// How much of the parent should be done by a given time
+pub trait Pacing {
+    /// Cumulative quantity due by `now`, before clamping to the parent quantity
+    fn target(&self, parent: &ParentOrder, now: Timestamp) -> Quantity;
+
+    /// A print on the parent's instrument after the start of the schedule
+    fn on_trade(&mut self, _trade: &Trade) {}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Completion {
+    Filled,
+    /// The window closed with quantity left, e.g. because of the limit price
+    Expired,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlgoEvent {
+    ChildSent { cl_ord_id: u64, price: Price, quantity: Quantity },
+    /// A stop's trigger condition was met at this market price
+    Triggered { price: Price },
+    Progress { filled: Quantity, remaining: Quantity, avg_price: Price },
+    Completed { reason: Completion, filled: Quantity, avg_price: Price },
+}
+
+/// Works a parent order as a series of IOC child orders.
+///
+/// On every slice the pacing says how much should be done by now; the
+/// shortfall is sent as one marketable child at the touch, capped at the
+/// parent's limit price. A slice where the touch is through the limit sends
+/// nothing. One child is working at a time. After the window closes one
+/// final child is sent for everything left, then the algo completes.
+pub struct ExecutionAlgo<P: Pacing> {
+    parent: ParentOrder,
+    slicing: Slicing,
+    pacing: P,
+    started: bool,
+    child: Option<u64>,
+    fills: Fills,
+    final_slice_sent: bool,
+}
+
+impl<P: Pacing> ExecutionAlgo<P> {
+    pub fn new(parent: ParentOrder, slicing: Slicing, pacing: P) -> Self {
+        Self {
+            parent,
+            slicing,
+            pacing,
+            started: false,
+            child: None,
+            fills: Fills::new(parent.quantity),
+            final_slice_sent: false,
+        }
+    }
+
+    pub fn parent(&self) -> &ParentOrder {
+        &self.parent
+    }
+
+    pub fn pacing(&self) -> &P {
+        &self.pacing
+    }
+
+    pub fn filled(&self) -> Quantity {
+        self.fills.filled()
+    }
+
+    /// Volume-weighted fill price, 0 before the first fill
+    pub fn avg_price(&self) -> Price {
+        self.fills.avg_price()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.fills.is_complete()
+    }
+
+    /// Events since the last drain, oldest first
+    pub fn drain_events(&mut self) -> Drain<'_, AlgoEvent> {
+        self.fills.drain()
+    }
+
+    fn start(&mut self, ctx: &mut StrategyContext<'_>) {
+        if !self.started {
+            self.started = true;
+            ctx.schedule_timer(ctx.now().max(self.parent.start), TICK);
+        }
+    }
+
+    /// Marketable price for the next child, or `None` if the touch is through the limit
+    fn child_price(&self, ctx: &mut StrategyContext<'_>) -> Option<Price> {
+        let book = ctx.book(self.parent.exchange_id);
+        let touch = match self.parent.side {
+            Side::Buy => book.best_ask()?.price,
+            Side::Sell => book.best_bid()?.price,
+        };
+        match (self.parent.side, self.parent.limit_price) {
+            (Side::Buy, Some(limit)) if touch > limit => None,
+            (Side::Sell, Some(limit)) if touch < limit => None,
+            _ => Some(touch),
+        }
+    }
+
+    fn slice(&mut self, ctx: &mut StrategyContext<'_>) {
+        let now = ctx.now();
+        if self.fills.is_complete() || self.child.is_some() || now < self.parent.start {
+            return;
+        }
+        let quantity = self.parent.quantity;
+        let filled = self.fills.filled();
+        if filled >= quantity {
+            self.fills.complete(Completion::Filled);
+            return;
+        }
+
+        let past_end = now >= self.parent.end;
+        if past_end && self.final_slice_sent {
+            self.fills.complete(Completion::Expired);
+            return;
+        }
+        let target = if past_end { quantity } else { self.pacing.target(&self.parent, now).min(quantity) };
+        let shortfall = target.saturating_sub(filled).min(self.slicing.max_child);
+        let size = self.parent.instrument.floor_quantity(shortfall);
+        if size == 0 && past_end {
+            // Less than a lot left
+            self.fills.complete(Completion::Expired);
+            return;
+        }
+        if size == 0 || (size < self.slicing.min_child && !past_end) {
+            return;
+        }
+
+        self.final_slice_sent = past_end;
+        let Some(price) = self.child_price(ctx) else {
+            if past_end {
+                self.fills.complete(Completion::Expired);
+            }
+            return;
+        };
+        let cl_ord_id = ctx.submit_ioc(self.parent.exchange_id, self.parent.instrument.symbol_id, self.parent.side, price, size);
+        self.child = Some(cl_ord_id);
+        self.fills.push(AlgoEvent::ChildSent { cl_ord_id, price, quantity: size });
+    }
+
+    fn is_ours(&self, exchange_id: ExchangeID, symbol_id: u32) -> bool {
+        exchange_id == self.parent.exchange_id && symbol_id == self.parent.instrument.symbol_id
+    }
+}
+
+impl<P: Pacing> Strategy for ExecutionAlgo<P> {
+    fn on_book_update(&mut self, ctx: &mut StrategyContext<'_>, _update: &MarketDataUpdate) {
+        self.start(ctx);
+    }
+
+    fn on_trade(&mut self, ctx: &mut StrategyContext<'_>, trade: &Trade) {
+        self.start(ctx);
+        if self.fills.is_complete() || !self.is_ours(trade.exchange_id, trade.symbol_id) || ctx.now() < self.parent.start {
+            return;
+        }
+        self.pacing.on_trade(trade);
+        self.slice(ctx);
+    }
+
+    fn on_execution_report(&mut self, ctx: &mut StrategyContext<'_>, report: &ExecutionReport) {
+        if self.child != Some(report.cl_ord_id) {
+            return;
+        }
+        self.fills.on_report(report);
+        if !ctx.orders().get(report.cl_ord_id).is_some_and(|o| o.is_live()) {
+            self.child = None;
+            self.slice(ctx);
+        }
+    }
+
+    fn on_timer(&mut self, ctx: &mut StrategyContext<'_>, timer_id: u64) {
+        if timer_id != TICK || self.fills.is_complete() {
+            return;
+        }
+        self.slice(ctx);
+        if !self.fills.is_complete() {
+            ctx.schedule_timer(ctx.now() + self.slicing.interval.max(1), TICK);
+        }
+    }
+}
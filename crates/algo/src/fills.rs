@@ -0,0 +1,72 @@
+use alloc::vec::{Drain, Vec};
+
+use vibe_hft_core::{Price, Quantity};
+use vibe_hft_sbe_messages::ExecutionReport;
+
+use crate::execution::{AlgoEvent, Completion};
+
+/// Fill accounting and event log shared by the algos
+#[derive(Debug)]
+pub(crate) struct Fills {
+    quantity: Quantity,
+    filled: Quantity,
+    /// Sum of fill price times quantity, for the average price
+    notional: i128,
+    completed: bool,
+    events: Vec<AlgoEvent>,
+}
+
+impl Fills {
+    pub(crate) fn new(quantity: Quantity) -> Self {
+        Self { quantity, filled: 0, notional: 0, completed: false, events: Vec::with_capacity(64) }
+    }
+
+    pub(crate) fn filled(&self) -> Quantity {
+        self.filled
+    }
+
+    pub(crate) fn remaining(&self) -> Quantity {
+        self.quantity.saturating_sub(self.filled)
+    }
+
+    pub(crate) fn avg_price(&self) -> Price {
+        if self.filled == 0 {
+            0
+        } else {
+            (self.notional / self.filled as i128) as Price
+        }
+    }
+
+    pub(crate) fn is_complete(&self) -> bool {
+        self.completed
+    }
+
+    pub(crate) fn push(&mut self, event: AlgoEvent) {
+        self.events.push(event);
+    }
+
+    pub(crate) fn drain(&mut self) -> Drain<'_, AlgoEvent> {
+        self.events.drain(..)
+    }
+
+    /// Apply the fill carried by `report`, if any
+    pub(crate) fn on_report(&mut self, report: &ExecutionReport) {
+        if report.filled_quantity == 0 {
+            return;
+        }
+        self.filled += report.filled_quantity;
+        self.notional += report.filled_price as i128 * report.filled_quantity as i128;
+        self.events.push(AlgoEvent::Progress {
+            filled: self.filled,
+            remaining: self.remaining(),
+            avg_price: self.avg_price(),
+        });
+    }
+
+    pub(crate) fn complete(&mut self, reason: Completion) {
+        if !self.completed {
+            self.completed = true;
+            self.events.push(AlgoEvent::Completed { reason, filled: self.filled, avg_price: self.avg_price() });
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use alloc::vec::Drain;
+
+use vibe_hft_core::{Price, Quantity};
+use vibe_hft_sbe_messages::{ExecutionReport, MarketDataUpdate};
+use vibe_hft_strategy::{Strategy, StrategyContext};
+
+use crate::execution::{AlgoEvent, Completion, ParentOrder};
+use crate::fills::Fills;
+
+/// Timer id of the expiry; child orders use their `cl_ord_id`s, which start at 1
+const EXPIRY: u64 = 0;
+
+/// Rests a large limit order while only ever showing `display` of it.
+///
+/// Each visible clip is a plain limit order at the parent's limit price; when
+/// a clip is done the next one is posted, until the parent is filled or the
+/// window closes. Replenished clips join the back of the queue, as they would
+/// on a venue with native icebergs.
+pub struct Iceberg {
+    parent: ParentOrder,
+    display: Quantity,
+    started: bool,
+    clip: Option<u64>,
+    expiring: bool,
+    fills: Fills,
+}
+
+impl Iceberg {
+    /// `parent.limit_price` is the resting price and must be set
+    pub fn new(parent: ParentOrder, display: Quantity) -> Self {
+        Self {
+            parent,
+            display,
+            started: false,
+            clip: None,
+            expiring: false,
+            fills: Fills::new(parent.quantity),
+        }
+    }
+
+    pub fn filled(&self) -> Quantity {
+        self.fills.filled()
+    }
+
+    pub fn avg_price(&self) -> Price {
+        self.fills.avg_price()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.fills.is_complete()
+    }
+
+    pub fn drain_events(&mut self) -> Drain<'_, AlgoEvent> {
+        self.fills.drain()
+    }
+
+    fn post_clip(&mut self, ctx: &mut StrategyContext<'_>) {
+        let Some(price) = self.parent.limit_price else {
+            self.fills.complete(Completion::Expired);
+            return;
+        };
+        let size = self.parent.instrument.floor_quantity(self.display.min(self.fills.remaining()));
+        if size == 0 {
+            self.fills.complete(Completion::Expired);
+            return;
+        }
+        let cl_ord_id = ctx.submit_limit(self.parent.exchange_id, self.parent.instrument.symbol_id, self.parent.side, price, size);
+        self.clip = Some(cl_ord_id);
+        self.fills.push(AlgoEvent::ChildSent { cl_ord_id, price, quantity: size });
+    }
+}
+
+impl Strategy for Iceberg {
+    fn on_book_update(&mut self, ctx: &mut StrategyContext<'_>, _update: &MarketDataUpdate) {
+        if self.started || ctx.now() < self.parent.start {
+            return;
+        }
+        self.started = true;
+        ctx.schedule_timer(self.parent.end, EXPIRY);
+        self.post_clip(ctx);
+    }
+
+    fn on_execution_report(&mut self, ctx: &mut StrategyContext<'_>, report: &ExecutionReport) {
+        if self.clip != Some(report.cl_ord_id) {
+            return;
+        }
+        self.fills.on_report(report);
+        if ctx.orders().get(report.cl_ord_id).is_some_and(|o| o.is_live()) {
+            return;
+        }
+
+        self.clip = None;
+        if self.fills.remaining() == 0 {
+            self.fills.complete(Completion::Filled);
+        } else if self.expiring {
+            self.fills.complete(Completion::Expired);
+        } else {
+            self.post_clip(ctx);
+        }
+    }
+
+    fn on_timer(&mut self, ctx: &mut StrategyContext<'_>, timer_id: u64) {
+        if timer_id != EXPIRY || self.fills.is_complete() {
+            return;
+        }
+        self.expiring = true;
+        match self.clip {
+            Some(cl_ord_id) => {
+                let _ = ctx.cancel(cl_ord_id);
+            }
+            None => self.fills.complete(Completion::Expired),
+        }
+    }
+}
@@ -1,5 +1,22 @@
 #![no_std]
 
-pub fn init() {
-    // Algo initialization
-}
+extern crate alloc;
+
+pub mod execution;
+mod fills;
+pub mod iceberg;
+pub mod peg;
+pub mod pov;
+pub mod router;
+pub mod trailing_stop;
+pub mod twap;
+pub mod vwap;
+
+pub use execution::{AlgoEvent, Completion, ExecutionAlgo, Pacing, ParentOrder, Slicing};
+pub use iceberg::Iceberg;
+pub use peg::{peg_price, PegKind, PeggedOrder};
+pub use pov::Pov;
+pub use router::{plan_route, RouteRecord, RoutePlan, RouteSlice, RouterConfig, SmartOrderRouter, VenueRules};
+pub use trailing_stop::TrailingStop;
+pub use twap::Twap;
+pub use vwap::Vwap;
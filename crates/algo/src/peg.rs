@@ -0,0 +1,217 @@
+use alloc::vec::Drain;
+
+use vibe_hft_core::{Instrument, Price, Quantity};
+use vibe_hft_oms::OrderState;
+use vibe_hft_sbe_messages::{ExecutionReport, MarketDataUpdate, Side};
+use vibe_hft_strategy::{Strategy, StrategyContext};
+
+use crate::execution::{AlgoEvent, Completion, ParentOrder};
+use crate::fills::Fills;
+
+/// Timer id of the expiry; child orders use their `cl_ord_id`s, which start at 1
+const EXPIRY: u64 = 0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PegKind {
+    /// Same-side touch: the best bid for a buy, the best ask for a sell
+    Primary,
+    /// Midpoint, rounded away from the other side onto the tick grid
+    Mid,
+}
+
+/// Pegged price for the touch `best_bid` / `best_ask`.
+///
+/// `offset_ticks` moves the peg towards the other side (more aggressive) when
+/// positive. The result never crosses the touch and never goes through
+/// `limit`.
+pub fn peg_price(
+    kind: PegKind,
+    side: Side,
+    best_bid: Price,
+    best_ask: Price,
+    instrument: &Instrument,
+    offset_ticks: i64,
+    limit: Option<Price>,
+) -> Price {
+    let tick = instrument.tick_size;
+    let offset = offset_ticks * tick;
+    match side {
+        Side::Buy => {
+            let base = match kind {
+                PegKind::Primary => best_bid,
+                PegKind::Mid => instrument.floor_price((best_bid + best_ask) / 2),
+            };
+            let price = (base + offset).min(best_ask - tick);
+            limit.map_or(price, |l| price.min(l))
+        }
+        Side::Sell => {
+            let base = match kind {
+                PegKind::Primary => best_ask,
+                PegKind::Mid => instrument.ceil_price((best_bid + best_ask) / 2),
+            };
+            let price = (base - offset).max(best_bid + tick);
+            limit.map_or(price, |l| price.max(l))
+        }
+    }
+}
+
+/// Limit order that follows the touch or the mid, repricing with a
+/// cancel/replace whenever the pegged price moves.
+///
+/// While the child is unacknowledged or a replace is in flight further
+/// moves wait for it; the next book update after it settles catches up to
+/// the latest price.
+pub struct PeggedOrder {
+    parent: ParentOrder,
+    kind: PegKind,
+    offset_ticks: i64,
+    started: bool,
+    child: Option<u64>,
+    expiring: bool,
+    fills: Fills,
+}
+
+impl PeggedOrder {
+    /// `parent.limit_price`, if set, caps the peg
+    pub fn new(parent: ParentOrder, kind: PegKind, offset_ticks: i64) -> Self {
+        Self {
+            parent,
+            kind,
+            offset_ticks,
+            started: false,
+            child: None,
+            expiring: false,
+            fills: Fills::new(parent.quantity),
+        }
+    }
+
+    pub fn filled(&self) -> Quantity {
+        self.fills.filled()
+    }
+
+    pub fn avg_price(&self) -> Price {
+        self.fills.avg_price()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.fills.is_complete()
+    }
+
+    /// `cl_ord_id` of the working child, which changes on every reprice
+    pub fn working(&self) -> Option<u64> {
+        self.child
+    }
+
+    pub fn drain_events(&mut self) -> Drain<'_, AlgoEvent> {
+        self.fills.drain()
+    }
+
+    fn target(&self, ctx: &mut StrategyContext<'_>) -> Option<Price> {
+        let book = ctx.book(self.parent.exchange_id);
+        let (best_bid, best_ask) = (book.best_bid()?, book.best_ask()?);
+        Some(peg_price(
+            self.kind,
+            self.parent.side,
+            best_bid.price,
+            best_ask.price,
+            &self.parent.instrument,
+            self.offset_ticks,
+            self.parent.limit_price,
+        ))
+    }
+}
+
+impl Strategy for PeggedOrder {
+    fn on_book_update(&mut self, ctx: &mut StrategyContext<'_>, update: &MarketDataUpdate) {
+        if self.fills.is_complete() || self.expiring || ctx.now() < self.parent.start {
+            return;
+        }
+        if !self.started {
+            self.started = true;
+            ctx.schedule_timer(self.parent.end, EXPIRY);
+        }
+        if update.exchange_id != self.parent.exchange_id || update.symbol_id != self.parent.instrument.symbol_id {
+            return;
+        }
+        let Some(target) = self.target(ctx) else { return };
+
+        let working = self.child.and_then(|id| ctx.orders().get(id).copied());
+        match working {
+            Some(order) if order.state == OrderState::PendingNew || order.cancel_pending || order.price == target => {}
+            Some(order) => {
+                if let Ok(replacement) = ctx.amend(order.cl_ord_id, target, order.quantity) {
+                    self.child = replacement;
+                    if let Some(cl_ord_id) = replacement {
+                        let quantity = order.leaves_quantity();
+                        self.fills.push(AlgoEvent::ChildSent { cl_ord_id, price: target, quantity });
+                    }
+                }
+            }
+            None => {
+                let quantity = self.fills.remaining();
+                let (venue, symbol_id) = (self.parent.exchange_id, self.parent.instrument.symbol_id);
+                let cl_ord_id = ctx.submit_limit(venue, symbol_id, self.parent.side, target, quantity);
+                self.child = Some(cl_ord_id);
+                self.fills.push(AlgoEvent::ChildSent { cl_ord_id, price: target, quantity });
+            }
+        }
+    }
+
+    fn on_execution_report(&mut self, ctx: &mut StrategyContext<'_>, report: &ExecutionReport) {
+        // Fills can land on a child after it was replaced
+        if ctx.orders().get(report.cl_ord_id).is_none_or(|o| o.symbol_id != self.parent.instrument.symbol_id) {
+            return;
+        }
+        self.fills.on_report(report);
+        if self.child != Some(report.cl_ord_id) || ctx.orders().get(report.cl_ord_id).is_some_and(|o| o.is_live()) {
+            return;
+        }
+
+        self.child = None;
+        if self.fills.remaining() == 0 {
+            self.fills.complete(Completion::Filled);
+        } else if self.expiring {
+            self.fills.complete(Completion::Expired);
+        }
+    }
+
+    fn on_timer(&mut self, ctx: &mut StrategyContext<'_>, timer_id: u64) {
+        if timer_id != EXPIRY || self.fills.is_complete() {
+            return;
+        }
+        self.expiring = true;
+        match self.child {
+            Some(cl_ord_id) => {
+                let _ = ctx.cancel(cl_ord_id);
+            }
+            None => self.fills.complete(Completion::Expired),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vibe_hft_core::InstrumentType;
+
+    const INSTRUMENT: Instrument = Instrument { symbol_id: 1, instrument_type: InstrumentType::Spot, tick_size: 10, lot_size: 1 };
+
+    #[test]
+    fn test_primary_peg_follows_same_side_touch() {
+        assert_eq!(peg_price(PegKind::Primary, Side::Buy, 1_000, 1_050, &INSTRUMENT, 0, None), 1_000);
+        assert_eq!(peg_price(PegKind::Primary, Side::Buy, 1_000, 1_050, &INSTRUMENT, 2, None), 1_020);
+        // Never crosses, never through the limit
+        assert_eq!(peg_price(PegKind::Primary, Side::Buy, 1_000, 1_050, &INSTRUMENT, 9, None), 1_040);
+        assert_eq!(peg_price(PegKind::Primary, Side::Buy, 1_000, 1_050, &INSTRUMENT, 0, Some(990)), 990);
+        assert_eq!(peg_price(PegKind::Primary, Side::Sell, 1_000, 1_050, &INSTRUMENT, 1, None), 1_040);
+    }
+
+    #[test]
+    fn test_mid_peg_rounds_away_from_other_side() {
+        assert_eq!(peg_price(PegKind::Mid, Side::Buy, 1_000, 1_050, &INSTRUMENT, 0, None), 1_020);
+        assert_eq!(peg_price(PegKind::Mid, Side::Sell, 1_000, 1_050, &INSTRUMENT, 0, None), 1_030);
+        // One-tick market: the mid collapses onto our own touch
+        assert_eq!(peg_price(PegKind::Mid, Side::Buy, 1_000, 1_010, &INSTRUMENT, 0, None), 1_000);
+        assert_eq!(peg_price(PegKind::Mid, Side::Sell, 1_000, 1_010, &INSTRUMENT, 0, None), 1_010);
+    }
+}
@@ -0,0 +1,70 @@
+use vibe_hft_core::{Quantity, Timestamp};
+use vibe_hft_sbe_messages::Trade;
+
+use crate::execution::{Pacing, ParentOrder};
+
+/// Percentage of volume: keeps filled quantity at a fixed share of the
+/// volume printed on the trade feed since the start of the window.
+///
+/// Live feeds include our own prints, so a participation rate `p` of the feed
+/// is `p / (1 - p)` of everyone else's volume.
+#[derive(Debug, Clone, Copy)]
+pub struct Pov {
+    participation: f64,
+    market_volume: Quantity,
+}
+
+impl Pov {
+    pub fn new(participation: f64) -> Self {
+        Self { participation: participation.clamp(0.0, 1.0), market_volume: 0 }
+    }
+
+    /// Volume printed since the start of the window (1e8)
+    pub fn market_volume(&self) -> Quantity {
+        self.market_volume
+    }
+}
+
+impl Pacing for Pov {
+    fn target(&self, _parent: &ParentOrder, _now: Timestamp) -> Quantity {
+        (self.market_volume as f64 * self.participation) as Quantity
+    }
+
+    fn on_trade(&mut self, trade: &Trade) {
+        self.market_volume += trade.quantity;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vibe_hft_core::{Instrument, InstrumentType};
+    use vibe_hft_sbe_messages::{ExchangeID, Side};
+
+    #[test]
+    fn test_target_tracks_printed_volume() {
+        let parent = ParentOrder {
+            exchange_id: ExchangeID::Binance,
+            instrument: Instrument { symbol_id: 1, instrument_type: InstrumentType::Spot, tick_size: 1, lot_size: 1 },
+            side: Side::Sell,
+            quantity: 1_000,
+            limit_price: None,
+            start: 0,
+            end: 100,
+        };
+        let mut pov = Pov::new(0.1);
+        for quantity in [200, 300] {
+            pov.on_trade(&Trade {
+                timestamp: 1,
+                exchange_id: ExchangeID::Binance,
+                symbol_id: 1,
+                trade_id: 1,
+                aggressor_side: Side::Buy,
+                price: 100,
+                quantity,
+            });
+        }
+        assert_eq!(pov.market_volume(), 500);
+        assert_eq!(pov.target(&parent, 50), 50);
+    }
+}
@@ -0,0 +1,434 @@
+use alloc::vec::{Drain, Vec};
+
+use log::info;
+use vibe_hft_core::{Instrument, Price, Quantity, Timestamp};
+use vibe_hft_market_data::{GlobalOrderBook, PriceLevel};
+use vibe_hft_sbe_messages::{ExchangeID, ExecutionReport, MarketDataUpdate, Side};
+use vibe_hft_strategy::{Strategy, StrategyContext};
+
+use crate::execution::{AlgoEvent, Completion};
+use crate::fills::Fills;
+
+const VENUES: [ExchangeID; 3] = [ExchangeID::Binance, ExchangeID::Bybit, ExchangeID::Coinbase];
+const NANOS_PER_SEC: u64 = 1_000_000_000;
+/// Timer id for retrying once a rate limit window reopens; child orders start at 1
+const RETRY: u64 = 0;
+
+fn venue_index(exchange_id: ExchangeID) -> usize {
+    exchange_id as usize - 1
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct VenueRules {
+    pub taker_fee_bps: f64,
+    /// Smallest child the venue accepts (1e8)
+    pub min_size: Quantity,
+    /// Child orders allowed per one-second window
+    pub max_orders_per_sec: u32,
+}
+
+/// Which venues the router may use, and on what terms. `None` disables a venue.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RouterConfig {
+    pub binance: Option<VenueRules>,
+    pub bybit: Option<VenueRules>,
+    pub coinbase: Option<VenueRules>,
+    /// Routing rounds allowed after the first before giving up on the remainder
+    pub max_reroutes: u32,
+}
+
+impl RouterConfig {
+    pub fn rules(&self, exchange_id: ExchangeID) -> Option<&VenueRules> {
+        match exchange_id {
+            ExchangeID::Binance => self.binance.as_ref(),
+            ExchangeID::Bybit => self.bybit.as_ref(),
+            ExchangeID::Coinbase => self.coinbase.as_ref(),
+        }
+    }
+}
+
+/// Quantity sent to one venue in a routing round
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RouteSlice {
+    pub venue: ExchangeID,
+    /// Worst level taken, used as the IOC limit
+    pub price: Price,
+    pub quantity: Quantity,
+    /// Average price of the levels taken, before fees
+    pub expected_price: Price,
+}
+
+/// At most one slice per venue; unused entries are `None`
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RoutePlan {
+    pub slices: [Option<RouteSlice>; 3],
+}
+
+impl RoutePlan {
+    pub fn iter(&self) -> impl Iterator<Item = &RouteSlice> {
+        self.slices.iter().flatten()
+    }
+
+    pub fn quantity(&self) -> Quantity {
+        self.iter().map(|s| s.quantity).sum()
+    }
+}
+
+/// Split `quantity` across the venues allowed by `eligible`, taking levels
+/// from the consolidated book in order of fee-adjusted price and never
+/// through `limit`. Venues whose share would fall under their minimum size are
+/// dropped and the walk is redone without them.
+pub fn plan_route(
+    books: &GlobalOrderBook,
+    instrument: &Instrument,
+    side: Side,
+    quantity: Quantity,
+    limit: Option<Price>,
+    config: &RouterConfig,
+    mut eligible: impl FnMut(ExchangeID) -> bool,
+) -> RoutePlan {
+    let mut allowed = VENUES.map(|v| config.rules(v).is_some() && eligible(v));
+    loop {
+        let plan = walk(books, instrument, side, quantity, limit, config, &allowed);
+        let mut replan = false;
+        for slice in plan.iter() {
+            let min_size = config.rules(slice.venue).map_or(0, |r| r.min_size);
+            if slice.quantity < min_size {
+                allowed[venue_index(slice.venue)] = false;
+                replan = true;
+            }
+        }
+        if !replan {
+            return plan;
+        }
+    }
+}
+
+fn walk(
+    books: &GlobalOrderBook,
+    instrument: &Instrument,
+    side: Side,
+    quantity: Quantity,
+    limit: Option<Price>,
+    config: &RouterConfig,
+    allowed: &[bool; 3],
+) -> RoutePlan {
+    // Buys take asks, sells hit bids
+    let book_side = match side {
+        Side::Buy => Side::Sell,
+        Side::Sell => Side::Buy,
+    };
+    let within_limit = |price: Price| match (side, limit) {
+        (_, None) => true,
+        (Side::Buy, Some(limit)) => price <= limit,
+        (Side::Sell, Some(limit)) => price >= limit,
+    };
+    let effective = |venue: ExchangeID, price: Price| {
+        let fee = config.rules(venue).map_or(0.0, |r| r.taker_fee_bps) / 10000.0;
+        match side {
+            Side::Buy => price as f64 * (1.0 + fee),
+            Side::Sell => price as f64 * (1.0 - fee),
+        }
+    };
+
+    // Cursor per venue: current level and what is left of it
+    let mut cursors: [Option<(PriceLevel, Quantity)>; 3] = [None; 3];
+    for venue in VENUES {
+        let i = venue_index(venue);
+        if allowed[i] {
+            cursors[i] = books.book(venue).next_level(book_side, None).map(|l| (l, l.quantity));
+        }
+    }
+
+    let mut taken = [(0 as Quantity, 0i128, 0 as Price); 3];
+    let mut left = quantity;
+    while left > 0 {
+        let best = VENUES
+            .into_iter()
+            .filter_map(|v| cursors[venue_index(v)].map(|(level, _)| (v, level.price)))
+            .filter(|&(_, price)| within_limit(price))
+            .min_by(|a, b| {
+                let (a, b) = (effective(a.0, a.1), effective(b.0, b.1));
+                match side {
+                    Side::Buy => a.total_cmp(&b),
+                    Side::Sell => b.total_cmp(&a),
+                }
+            });
+        let Some((venue, price)) = best else { break };
+        let i = venue_index(venue);
+        let (level, available) = cursors[i].unwrap();
+        let fill = available.min(left);
+        left -= fill;
+        taken[i].0 += fill;
+        taken[i].1 += price as i128 * fill as i128;
+        taken[i].2 = price;
+        cursors[i] = if fill < available {
+            Some((level, available - fill))
+        } else {
+            books.book(venue).next_level(book_side, Some(level.price)).map(|l| (l, l.quantity))
+        };
+    }
+
+    let mut plan = RoutePlan::default();
+    for venue in VENUES {
+        let (raw, notional, price) = taken[venue_index(venue)];
+        let quantity = instrument.floor_quantity(raw);
+        if quantity > 0 {
+            plan.slices[venue_index(venue)] = Some(RouteSlice {
+                venue,
+                price,
+                quantity,
+                expected_price: (notional / raw as i128) as Price,
+            });
+        }
+    }
+    plan
+}
+
+/// Child orders allowed per venue in the current one-second window
+#[derive(Debug, Clone, Copy, Default)]
+struct RateWindow {
+    start: Timestamp,
+    sent: u32,
+}
+
+impl RateWindow {
+    fn roll(&mut self, now: Timestamp) {
+        if now >= self.start + NANOS_PER_SEC {
+            self.start = now;
+            self.sent = 0;
+        }
+    }
+}
+
+/// A routed child and how it compared with the plan
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RouteRecord {
+    pub venue: ExchangeID,
+    pub cl_ord_id: u64,
+    pub quantity: Quantity,
+    pub expected_price: Price,
+    pub filled: Quantity,
+    /// Average fill price, 0 if nothing filled
+    pub realized_price: Price,
+}
+
+/// Works an order across venues with IOC children planned by `plan_route`.
+///
+/// Each round sends at most one child per venue and waits for all of them to
+/// finish; any remainder is re-planned against the books as they are then.
+/// When there is nothing to take the router waits for book updates, or for
+/// a rate-limit window to reopen.
+pub struct SmartOrderRouter {
+    instrument: Instrument,
+    side: Side,
+    limit: Option<Price>,
+    start: Timestamp,
+    started: bool,
+    config: RouterConfig,
+    windows: [RateWindow; 3],
+    in_flight: Vec<RouteRecord>,
+    records: Vec<RouteRecord>,
+    rounds: u32,
+    fills: Fills,
+}
+
+impl SmartOrderRouter {
+    /// Routing starts at local time `start`, once every venue's book has had a chance to fill in
+    pub fn new(
+        instrument: Instrument,
+        side: Side,
+        quantity: Quantity,
+        limit: Option<Price>,
+        start: Timestamp,
+        config: RouterConfig,
+    ) -> Self {
+        Self {
+            instrument,
+            side,
+            limit,
+            start,
+            started: false,
+            config,
+            windows: [RateWindow::default(); 3],
+            in_flight: Vec::with_capacity(VENUES.len()),
+            records: Vec::with_capacity(16),
+            rounds: 0,
+            fills: Fills::new(quantity),
+        }
+    }
+
+    pub fn filled(&self) -> Quantity {
+        self.fills.filled()
+    }
+
+    pub fn avg_price(&self) -> Price {
+        self.fills.avg_price()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.fills.is_complete()
+    }
+
+    /// Finished children, oldest first
+    pub fn records(&self) -> &[RouteRecord] {
+        &self.records
+    }
+
+    pub fn drain_events(&mut self) -> Drain<'_, AlgoEvent> {
+        self.fills.drain()
+    }
+
+    fn route(&mut self, ctx: &mut StrategyContext<'_>) {
+        if self.fills.is_complete() || !self.in_flight.is_empty() {
+            return;
+        }
+        let now = ctx.now();
+        for window in self.windows.iter_mut() {
+            window.roll(now);
+        }
+        let config = self.config;
+        let windows = self.windows;
+        let mut rate_limited = false;
+        let open = |venue: ExchangeID| {
+            let max = config.rules(venue).map_or(0, |r| r.max_orders_per_sec);
+            let open = windows[venue_index(venue)].sent < max;
+            rate_limited |= !open;
+            open
+        };
+        let plan = plan_route(ctx.books(), &self.instrument, self.side, self.fills.remaining(), self.limit, &config, open);
+
+        if plan.quantity() == 0 {
+            if rate_limited {
+                let reopen = self.windows.iter().map(|w| w.start + NANOS_PER_SEC).min().unwrap_or(now);
+                ctx.schedule_timer(reopen.max(now + 1), RETRY);
+            }
+            return;
+        }
+        for slice in plan.iter() {
+            self.windows[venue_index(slice.venue)].sent += 1;
+            let cl_ord_id = ctx.submit_ioc(slice.venue, self.instrument.symbol_id, self.side, slice.price, slice.quantity);
+            info!(
+                "Route {:?} {:?} {} @ {} expected {} (round {})",
+                slice.venue, self.side, slice.quantity, slice.price, slice.expected_price, self.rounds
+            );
+            self.in_flight.push(RouteRecord {
+                venue: slice.venue,
+                cl_ord_id,
+                quantity: slice.quantity,
+                expected_price: slice.expected_price,
+                filled: 0,
+                realized_price: 0,
+            });
+            self.fills.push(AlgoEvent::ChildSent { cl_ord_id, price: slice.price, quantity: slice.quantity });
+        }
+    }
+}
+
+impl Strategy for SmartOrderRouter {
+    fn on_book_update(&mut self, ctx: &mut StrategyContext<'_>, update: &MarketDataUpdate) {
+        if !self.started {
+            self.started = true;
+            ctx.schedule_timer(ctx.now().max(self.start), RETRY);
+        }
+        if update.symbol_id == self.instrument.symbol_id && ctx.now() >= self.start {
+            self.route(ctx);
+        }
+    }
+
+    fn on_execution_report(&mut self, ctx: &mut StrategyContext<'_>, report: &ExecutionReport) {
+        let Some(i) = self.in_flight.iter().position(|r| r.cl_ord_id == report.cl_ord_id) else { return };
+        self.fills.on_report(report);
+        let record = &mut self.in_flight[i];
+        if report.filled_quantity > 0 {
+            let notional = record.realized_price as i128 * record.filled as i128
+                + report.filled_price as i128 * report.filled_quantity as i128;
+            record.filled += report.filled_quantity;
+            record.realized_price = (notional / record.filled as i128) as Price;
+        }
+        if ctx.orders().get(report.cl_ord_id).is_some_and(|o| o.is_live()) {
+            return;
+        }
+
+        let record = self.in_flight.swap_remove(i);
+        info!(
+            "Routed {:?} {}/{} expected {} realized {}",
+            record.venue, record.filled, record.quantity, record.expected_price, record.realized_price
+        );
+        self.records.push(record);
+        if !self.in_flight.is_empty() {
+            return;
+        }
+
+        self.rounds += 1;
+        if self.fills.remaining() == 0 {
+            self.fills.complete(Completion::Filled);
+        } else if self.rounds > self.config.max_reroutes {
+            self.fills.complete(Completion::Expired);
+        } else {
+            self.route(ctx);
+        }
+    }
+
+    fn on_timer(&mut self, ctx: &mut StrategyContext<'_>, timer_id: u64) {
+        if timer_id == RETRY && ctx.now() >= self.start {
+            self.route(ctx);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vibe_hft_core::InstrumentType;
+
+    const ONE: u64 = 100_000_000;
+    const INSTRUMENT: Instrument = Instrument { symbol_id: 1, instrument_type: InstrumentType::Spot, tick_size: 1, lot_size: 1 };
+
+    fn rules(taker_fee_bps: f64, min_size: Quantity) -> Option<VenueRules> {
+        Some(VenueRules { taker_fee_bps, min_size, max_orders_per_sec: 10 })
+    }
+
+    fn books() -> GlobalOrderBook {
+        let mut books = GlobalOrderBook::new();
+        for (exchange_id, price) in [(ExchangeID::Binance, 10_000), (ExchangeID::Bybit, 10_005), (ExchangeID::Coinbase, 10_010)] {
+            books.on_update(&MarketDataUpdate {
+                timestamp: 1,
+                exchange_id,
+                symbol_id: 1,
+                side: Side::Sell,
+                price,
+                quantity: ONE,
+                is_snapshot: 0,
+            });
+        }
+        books
+    }
+
+    #[test]
+    fn test_fees_decide_the_order_levels_are_taken() {
+        // Binance is cheapest on screen but dearest after its 20 bps fee
+        let config = RouterConfig { binance: rules(20.0, 0), bybit: rules(1.0, 0), coinbase: rules(1.0, 0), max_reroutes: 0 };
+        let plan = plan_route(&books(), &INSTRUMENT, Side::Buy, ONE + ONE / 2, None, &config, |_| true);
+
+        let bybit = plan.slices[venue_index(ExchangeID::Bybit)].unwrap();
+        let coinbase = plan.slices[venue_index(ExchangeID::Coinbase)].unwrap();
+        assert_eq!((bybit.quantity, bybit.price), (ONE, 10_005));
+        assert_eq!((coinbase.quantity, coinbase.price), (ONE / 2, 10_010));
+        assert!(plan.slices[venue_index(ExchangeID::Binance)].is_none());
+    }
+
+    #[test]
+    fn test_min_size_and_limit_reshape_the_plan() {
+        let config = RouterConfig { binance: rules(0.0, 0), bybit: rules(0.0, ONE), coinbase: rules(0.0, 0), max_reroutes: 0 };
+        // Bybit's half-lot share is under its minimum and moves to Coinbase
+        let plan = plan_route(&books(), &INSTRUMENT, Side::Buy, ONE + ONE / 2, None, &config, |_| true);
+        assert!(plan.slices[venue_index(ExchangeID::Bybit)].is_none());
+        assert_eq!(plan.quantity(), ONE + ONE / 2);
+
+        // Nothing through the limit, nothing from ineligible venues
+        let plan = plan_route(&books(), &INSTRUMENT, Side::Buy, 3 * ONE, Some(10_005), &config, |v| v != ExchangeID::Binance);
+        assert_eq!(plan.quantity(), ONE);
+        assert_eq!(plan.iter().next().unwrap().venue, ExchangeID::Bybit);
+    }
+}
@@ -0,0 +1,173 @@
+use alloc::vec::Drain;
+
+use vibe_hft_core::{Price, Quantity};
+use vibe_hft_sbe_messages::{ExecutionReport, MarketDataUpdate, Side};
+use vibe_hft_strategy::{Strategy, StrategyContext};
+
+use crate::execution::{AlgoEvent, Completion, ParentOrder};
+use crate::fills::Fills;
+
+/// Timer id of the expiry; the child order uses its `cl_ord_id`, which starts at 1
+const EXPIRY: u64 = 0;
+
+/// Stop that trails the market by a fixed distance.
+///
+/// A sell stop tracks the highest best bid seen and triggers once the bid
+/// falls `trail` below it; a buy stop mirrors this on the best ask. On
+/// trigger the whole quantity is sent at market, or as an IOC at the
+/// parent's limit price when one is set. The stop arms at `parent.start`
+/// and expires at `parent.end` if it has not triggered by then; once
+/// triggered the child runs to completion regardless of the window.
+pub struct TrailingStop {
+    parent: ParentOrder,
+    trail: Price,
+    started: bool,
+    /// Best price in our favour since arming
+    extreme: Option<Price>,
+    child: Option<u64>,
+    fills: Fills,
+}
+
+impl TrailingStop {
+    pub fn new(parent: ParentOrder, trail: Price) -> Self {
+        Self { parent, trail, started: false, extreme: None, child: None, fills: Fills::new(parent.quantity) }
+    }
+
+    /// Current trigger level, once the market has been seen
+    pub fn stop_price(&self) -> Option<Price> {
+        self.extreme.map(|extreme| match self.parent.side {
+            Side::Sell => extreme - self.trail,
+            Side::Buy => extreme + self.trail,
+        })
+    }
+
+    pub fn is_triggered(&self) -> bool {
+        self.child.is_some() || self.fills.filled() > 0
+    }
+
+    pub fn filled(&self) -> Quantity {
+        self.fills.filled()
+    }
+
+    pub fn avg_price(&self) -> Price {
+        self.fills.avg_price()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.fills.is_complete()
+    }
+
+    pub fn drain_events(&mut self) -> Drain<'_, AlgoEvent> {
+        self.fills.drain()
+    }
+
+    /// Move the trail with `price` and report whether it was hit
+    fn on_price(&mut self, price: Price) -> bool {
+        let extreme = match (self.parent.side, self.extreme) {
+            (_, None) => price,
+            (Side::Sell, Some(high)) => high.max(price),
+            (Side::Buy, Some(low)) => low.min(price),
+        };
+        self.extreme = Some(extreme);
+        match self.parent.side {
+            Side::Sell => price <= extreme - self.trail,
+            Side::Buy => price >= extreme + self.trail,
+        }
+    }
+
+    fn trigger(&mut self, ctx: &mut StrategyContext<'_>, price: Price) {
+        self.fills.push(AlgoEvent::Triggered { price });
+        let (venue, symbol_id, side) = (self.parent.exchange_id, self.parent.instrument.symbol_id, self.parent.side);
+        let quantity = self.parent.quantity;
+        let cl_ord_id = match self.parent.limit_price {
+            Some(limit) => ctx.submit_ioc(venue, symbol_id, side, limit, quantity),
+            None => ctx.submit_market(venue, symbol_id, side, quantity),
+        };
+        self.child = Some(cl_ord_id);
+        let child_price = self.parent.limit_price.unwrap_or(0);
+        self.fills.push(AlgoEvent::ChildSent { cl_ord_id, price: child_price, quantity });
+    }
+}
+
+impl Strategy for TrailingStop {
+    fn on_book_update(&mut self, ctx: &mut StrategyContext<'_>, update: &MarketDataUpdate) {
+        if self.is_triggered() || self.fills.is_complete() || ctx.now() < self.parent.start {
+            return;
+        }
+        if !self.started {
+            self.started = true;
+            ctx.schedule_timer(self.parent.end, EXPIRY);
+        }
+        if update.exchange_id != self.parent.exchange_id || update.symbol_id != self.parent.instrument.symbol_id {
+            return;
+        }
+        let book = ctx.book(self.parent.exchange_id);
+        // A sell stop watches where it could sell, a buy stop where it could buy
+        let level = match self.parent.side {
+            Side::Sell => book.best_bid(),
+            Side::Buy => book.best_ask(),
+        };
+        let Some(level) = level else { return };
+        if self.on_price(level.price) {
+            self.trigger(ctx, level.price);
+        }
+    }
+
+    fn on_execution_report(&mut self, ctx: &mut StrategyContext<'_>, report: &ExecutionReport) {
+        if self.child != Some(report.cl_ord_id) {
+            return;
+        }
+        self.fills.on_report(report);
+        if !ctx.orders().get(report.cl_ord_id).is_some_and(|o| o.is_live()) {
+            // Whatever the book could not absorb is left unfilled
+            let reason = if self.fills.remaining() == 0 { Completion::Filled } else { Completion::Expired };
+            self.fills.complete(reason);
+        }
+    }
+
+    fn on_timer(&mut self, _ctx: &mut StrategyContext<'_>, timer_id: u64) {
+        if timer_id == EXPIRY && !self.is_triggered() && !self.fills.is_complete() {
+            self.fills.complete(Completion::Expired);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vibe_hft_core::{Instrument, InstrumentType};
+    use vibe_hft_sbe_messages::ExchangeID;
+
+    fn stop(side: Side) -> TrailingStop {
+        let parent = ParentOrder {
+            exchange_id: ExchangeID::Binance,
+            instrument: Instrument { symbol_id: 1, instrument_type: InstrumentType::Spot, tick_size: 1, lot_size: 1 },
+            side,
+            quantity: 10,
+            limit_price: None,
+            start: 0,
+            end: u64::MAX,
+        };
+        TrailingStop::new(parent, 50)
+    }
+
+    #[test]
+    fn test_sell_stop_ratchets_up_only() {
+        let mut stop = stop(Side::Sell);
+        assert!(!stop.on_price(1_000));
+        assert_eq!(stop.stop_price(), Some(950));
+        assert!(!stop.on_price(1_100));
+        assert!(!stop.on_price(1_060));
+        assert_eq!(stop.stop_price(), Some(1_050));
+        assert!(stop.on_price(1_050));
+    }
+
+    #[test]
+    fn test_buy_stop_follows_market_down() {
+        let mut stop = stop(Side::Buy);
+        assert!(!stop.on_price(1_000));
+        assert!(!stop.on_price(900));
+        assert_eq!(stop.stop_price(), Some(950));
+        assert!(stop.on_price(960));
+    }
+}
@@ -0,0 +1,36 @@
+use vibe_hft_core::{Quantity, Timestamp};
+
+use crate::execution::{Pacing, ParentOrder};
+
+/// Even pace over the window: the target grows linearly from start to end
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Twap;
+
+impl Pacing for Twap {
+    fn target(&self, parent: &ParentOrder, now: Timestamp) -> Quantity {
+        (parent.quantity as f64 * parent.elapsed(now)) as Quantity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vibe_hft_core::{Instrument, InstrumentType};
+    use vibe_hft_sbe_messages::{ExchangeID, Side};
+
+    #[test]
+    fn test_target_is_linear_in_time() {
+        let parent = ParentOrder {
+            exchange_id: ExchangeID::Binance,
+            instrument: Instrument { symbol_id: 1, instrument_type: InstrumentType::Spot, tick_size: 1, lot_size: 1 },
+            side: Side::Buy,
+            quantity: 1_000,
+            limit_price: None,
+            start: 100,
+            end: 200,
+        };
+        assert_eq!(Twap.target(&parent, 50), 0);
+        assert_eq!(Twap.target(&parent, 125), 250);
+        assert_eq!(Twap.target(&parent, 300), 1_000);
+    }
+}
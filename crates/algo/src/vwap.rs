@@ -0,0 +1,68 @@
+use alloc::vec::Vec;
+
+use vibe_hft_core::{Quantity, Timestamp};
+
+use crate::execution::{Pacing, ParentOrder};
+
+/// Follows a historical volume curve: the window is split into equal buckets
+/// and each bucket gets its share of the parent, spread evenly within it.
+#[derive(Debug, Clone)]
+pub struct Vwap {
+    /// Cumulative volume share at the end of each bucket, ending at 1
+    cumulative: Vec<f64>,
+}
+
+impl Vwap {
+    /// `curve` holds the relative volume expected in each bucket; it need not sum to 1.
+    /// An empty or all-zero curve falls back to an even pace.
+    pub fn new(curve: &[f64]) -> Self {
+        let total: f64 = curve.iter().map(|v| v.max(0.0)).sum();
+        let cumulative = if total > 0.0 {
+            curve
+                .iter()
+                .scan(0.0, |sum, v| {
+                    *sum += v.max(0.0) / total;
+                    Some(*sum)
+                })
+                .collect()
+        } else {
+            Vec::from([1.0])
+        };
+        Self { cumulative }
+    }
+
+    /// Share of the parent due after `elapsed` of the window
+    pub fn share(&self, elapsed: f64) -> f64 {
+        let buckets = self.cumulative.len() as f64;
+        let position = elapsed.clamp(0.0, 1.0) * buckets;
+        let bucket = (position as usize).min(self.cumulative.len() - 1);
+        let before = if bucket == 0 { 0.0 } else { self.cumulative[bucket - 1] };
+        let within = position - bucket as f64;
+        before + (self.cumulative[bucket] - before) * within
+    }
+}
+
+impl Pacing for Vwap {
+    fn target(&self, parent: &ParentOrder, now: Timestamp) -> Quantity {
+        (parent.quantity as f64 * self.share(parent.elapsed(now))) as Quantity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_share_follows_curve() {
+        // U-shaped day: heavy open and close
+        let vwap = Vwap::new(&[3.0, 1.0, 1.0, 3.0]);
+        assert_eq!(vwap.share(0.0), 0.0);
+        assert_eq!(vwap.share(0.25), 0.375);
+        assert_eq!(vwap.share(0.125), 0.1875);
+        assert_eq!(vwap.share(0.5), 0.5);
+        assert_eq!(vwap.share(1.0), 1.0);
+
+        let flat = Vwap::new(&[]);
+        assert_eq!(flat.share(0.3), 0.3);
+    }
+}
@@ -0,0 +1,105 @@
+use vibe_hft_algo::{AlgoEvent, Completion, ExecutionAlgo, Pacing, ParentOrder, Pov, Slicing, Twap, Vwap};
+use vibe_hft_backtest::testing::{self, book, print, CENT, ONE, SEC};
+use vibe_hft_backtest::{LatencyConfig, TapeEvent};
+use vibe_hft_core::{Instrument, InstrumentType};
+use vibe_hft_sbe_messages::{ExchangeID, Side};
+use vibe_hft_strategy::StrategyHost;
+
+fn parent(side: Side, quantity: u64, limit_cents: Option<i64>) -> ParentOrder {
+    ParentOrder {
+        exchange_id: ExchangeID::Binance,
+        instrument: Instrument {
+            symbol_id: 1,
+            instrument_type: InstrumentType::Spot,
+            tick_size: CENT,
+            lot_size: 1_000,
+        },
+        side,
+        quantity,
+        limit_price: limit_cents.map(|c| c * CENT),
+        start: SEC,
+        end: 11 * SEC,
+    }
+}
+
+fn slicing() -> Slicing {
+    Slicing { interval: SEC, min_child: ONE / 10, max_child: 100 * ONE }
+}
+
+/// Deep 100.00 / 100.01 market, with a far deep-level update keeping the tape alive past the window
+fn tape() -> Vec<TapeEvent> {
+    vec![
+        book(1_000, Side::Buy, 10_000, 1_000 * ONE),
+        book(2_000, Side::Sell, 10_001, 1_000 * ONE),
+        book(20 * SEC, Side::Buy, 9_000, ONE),
+    ]
+}
+
+fn backtest<P: Pacing>(algo: ExecutionAlgo<P>, tape: Vec<TapeEvent>) -> (StrategyHost<ExecutionAlgo<P>>, Vec<AlgoEvent>) {
+    let mut host = testing::backtest(algo, tape, LatencyConfig::zero());
+    let events = host.strategy_mut().drain_events().collect();
+    (host, events)
+}
+
+fn children(events: &[AlgoEvent]) -> Vec<u64> {
+    events
+        .iter()
+        .filter_map(|e| match e {
+            AlgoEvent::ChildSent { quantity, .. } => Some(*quantity),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_twap_slices_evenly_and_completes() {
+    let (host, events) = backtest(ExecutionAlgo::new(parent(Side::Buy, 10 * ONE, None), slicing(), Twap), tape());
+
+    assert_eq!(children(&events), [ONE; 10]);
+    assert_eq!(
+        events.last(),
+        Some(&AlgoEvent::Completed { reason: Completion::Filled, filled: 10 * ONE, avg_price: 10_001 * CENT })
+    );
+    assert!(host.strategy().is_complete());
+}
+
+#[test]
+fn test_vwap_front_loads_heavy_buckets() {
+    let algo = ExecutionAlgo::new(parent(Side::Sell, 10 * ONE, None), slicing(), Vwap::new(&[4.0, 1.0]));
+    let (host, events) = backtest(algo, tape());
+
+    // 80% of the curve falls in the first half of the window
+    let sizes = children(&events);
+    assert_eq!(sizes[..5].iter().sum::<u64>(), 8 * ONE);
+    assert_eq!(host.strategy().filled(), 10 * ONE);
+    assert_eq!(host.strategy().avg_price(), 10_000 * CENT);
+}
+
+#[test]
+fn test_pov_follows_trade_feed() {
+    let mut tape = tape();
+    tape.extend((1..=5).map(|i| print(i * SEC + SEC / 2, Side::Buy, 10_001, 10 * ONE)));
+    let algo = ExecutionAlgo::new(parent(Side::Buy, 100 * ONE, None), slicing(), Pov::new(0.1));
+    let (host, events) = backtest(algo, tape);
+
+    assert_eq!(host.strategy().pacing().market_volume(), 50 * ONE);
+    assert!(events.iter().any(|e| matches!(e, AlgoEvent::Progress { filled, .. } if *filled == 5 * ONE)));
+    // The final slice sweeps the rest once the window closes
+    assert_eq!(host.strategy().filled(), 100 * ONE);
+}
+
+#[test]
+fn test_limit_price_blocks_children() {
+    let mut tape = tape();
+    // Offer lifts through the limit halfway through the window
+    tape.push(book(6 * SEC, Side::Sell, 10_050, 1_000 * ONE));
+    tape.push(book(6 * SEC + 1, Side::Sell, 10_001, 0));
+    let algo = ExecutionAlgo::new(parent(Side::Buy, 10 * ONE, Some(10_010)), slicing(), Twap);
+    let (host, events) = backtest(algo, tape);
+
+    assert!(children(&events).len() <= 5);
+    let filled = host.strategy().filled();
+    assert!(filled > 0 && filled < 10 * ONE);
+    assert!(matches!(events.last(), Some(AlgoEvent::Completed { reason: Completion::Expired, .. })));
+    assert_eq!(host.strategy().avg_price(), 10_001 * CENT);
+}
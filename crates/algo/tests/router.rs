@@ -0,0 +1,74 @@
+use vibe_hft_algo::{RouterConfig, SmartOrderRouter, VenueRules};
+use vibe_hft_backtest::testing::{self, book_on, CENT, ONE};
+use vibe_hft_backtest::{ExchangeConfig, FeeSchedule, LatencyConfig, TapeEvent};
+use vibe_hft_core::{Instrument, InstrumentType};
+use vibe_hft_sbe_messages::{ExchangeID, Side};
+use vibe_hft_strategy::StrategyHost;
+
+const INSTRUMENT: Instrument = Instrument {
+    symbol_id: 1,
+    instrument_type: InstrumentType::Spot,
+    tick_size: CENT,
+    lot_size: 1_000,
+};
+
+fn rules(taker_fee_bps: f64) -> Option<VenueRules> {
+    Some(VenueRules { taker_fee_bps, min_size: ONE / 100, max_orders_per_sec: 5 })
+}
+
+fn config() -> RouterConfig {
+    RouterConfig { binance: rules(20.0), bybit: rules(1.0), coinbase: rules(1.0), max_reroutes: 2 }
+}
+
+/// 100.00 on Binance, 100.05 on Bybit and 100.10 on Coinbase, one lot each
+/// except Coinbase; the router sees the last of them at 1_012 and starts at 1_100.
+fn books() -> Vec<TapeEvent> {
+    vec![
+        book_on(1_000, ExchangeID::Binance, Side::Sell, 10_000, ONE),
+        book_on(1_001, ExchangeID::Bybit, Side::Sell, 10_005, ONE),
+        book_on(1_002, ExchangeID::Coinbase, Side::Sell, 10_010, 5 * ONE),
+        // Deep level keeping the tape alive past the start
+        book_on(10_000, ExchangeID::Coinbase, Side::Sell, 10_100, ONE),
+    ]
+}
+
+fn backtest(router: SmartOrderRouter, tape: Vec<TapeEvent>) -> StrategyHost<SmartOrderRouter> {
+    let taker_fees = [(ExchangeID::Binance, 20.0), (ExchangeID::Bybit, 1.0), (ExchangeID::Coinbase, 1.0)];
+    let venues = taker_fees.map(|(venue, taker_bps)| {
+        let fees = FeeSchedule { maker_bps: 0.0, taker_bps };
+        (venue, ExchangeConfig { fees, ..ExchangeConfig::default() })
+    });
+    testing::backtest_venues(router, tape, venues, LatencyConfig::constant(10))
+}
+
+#[test]
+fn test_routes_by_fee_adjusted_price() {
+    let router = SmartOrderRouter::new(INSTRUMENT, Side::Buy, 2 * ONE, None, 1_100, config());
+    let host = backtest(router, books());
+
+    let router = host.strategy();
+    assert!(router.is_complete());
+    assert_eq!(router.filled(), 2 * ONE);
+    let mut venues: Vec<_> = router.records().iter().map(|r| (r.venue, r.filled, r.realized_price / CENT)).collect();
+    venues.sort_by_key(|v| v.0 as u8);
+    // Binance is cheapest on screen but loses once its 20 bps fee is added
+    assert_eq!(venues, [(ExchangeID::Bybit, ONE, 10_005), (ExchangeID::Coinbase, ONE, 10_010)]);
+}
+
+#[test]
+fn test_reroutes_unfilled_remainder() {
+    let mut tape = books();
+    // Bybit's offer is gone before our child arrives, and then from our book too
+    tape.push(book_on(1_105, ExchangeID::Bybit, Side::Sell, 10_005, 0));
+    let router = SmartOrderRouter::new(INSTRUMENT, Side::Buy, 2 * ONE, None, 1_100, config());
+    let host = backtest(router, tape);
+
+    let router = host.strategy();
+    assert_eq!(router.filled(), 2 * ONE);
+    let bybit = router.records().iter().find(|r| r.venue == ExchangeID::Bybit).unwrap();
+    assert_eq!((bybit.expected_price, bybit.filled), (10_005 * CENT, 0));
+    // The missed lot went to Coinbase in a second round
+    let coinbase: u64 = router.records().iter().filter(|r| r.venue == ExchangeID::Coinbase).map(|r| r.filled).sum();
+    assert_eq!(coinbase, 2 * ONE);
+    assert_eq!(router.records().len(), 3);
+}
@@ -0,0 +1,155 @@
+use vibe_hft_algo::{AlgoEvent, Completion, Iceberg, ParentOrder, PegKind, PeggedOrder, TrailingStop};
+use vibe_hft_backtest::testing::{self, book, print, CENT, ONE, SEC};
+use vibe_hft_backtest::{LatencyConfig, LatencyModel, TapeEvent};
+use vibe_hft_core::{Instrument, InstrumentType};
+use vibe_hft_sbe_messages::{ExchangeID, Side};
+use vibe_hft_strategy::{Strategy, StrategyHost};
+
+fn parent(side: Side, quantity: u64, limit_cents: Option<i64>) -> ParentOrder {
+    ParentOrder {
+        exchange_id: ExchangeID::Binance,
+        instrument: Instrument {
+            symbol_id: 1,
+            instrument_type: InstrumentType::Spot,
+            tick_size: CENT,
+            lot_size: 1_000,
+        },
+        side,
+        quantity,
+        limit_price: limit_cents.map(|c| c * CENT),
+        start: 0,
+        end: 100 * SEC,
+    }
+}
+
+fn backtest<S: Strategy>(strategy: S, tape: Vec<TapeEvent>) -> StrategyHost<S> {
+    testing::backtest(strategy, tape, LatencyConfig::zero())
+}
+
+/// (price in cents, quantity) of every working order
+fn working<S: Strategy>(host: &StrategyHost<S>) -> Vec<(i64, u64)> {
+    host.orders().live_orders().map(|o| (o.price / CENT, o.leaves_quantity())).collect()
+}
+
+#[test]
+fn test_iceberg_shows_display_and_replenishes() {
+    let tape = vec![
+        book(1_000, Side::Buy, 10_000, 10 * ONE),
+        book(2_000, Side::Sell, 10_002, 10 * ONE),
+        // The queue ahead of us trades away, then 2.5 clips of ours
+        print(3_000, Side::Sell, 10_000, 10 * ONE),
+        book(3_500, Side::Buy, 10_000, 0),
+        print(4_000, Side::Sell, 10_000, ONE),
+        print(5_000, Side::Sell, 10_000, ONE),
+        print(6_000, Side::Sell, 10_000, ONE / 2),
+    ];
+    let mut host = backtest(Iceberg::new(parent(Side::Buy, 5 * ONE, Some(10_000)), ONE), tape);
+
+    assert_eq!(host.strategy().filled(), 5 * ONE / 2);
+    // Only the current clip is visible, and it is what is left of the third one
+    assert_eq!(working(&host), [(10_000, ONE / 2)]);
+    let clips = host
+        .strategy_mut()
+        .drain_events()
+        .filter(|e| matches!(e, AlgoEvent::ChildSent { quantity, .. } if *quantity == ONE))
+        .count();
+    assert_eq!(clips, 3);
+}
+
+#[test]
+fn test_primary_peg_follows_bid() {
+    let tape = vec![
+        book(1_000, Side::Buy, 10_000, ONE),
+        book(2_000, Side::Sell, 10_005, ONE),
+        // Bid steps up twice, then back down
+        book(3_000, Side::Buy, 10_001, ONE),
+        book(4_000, Side::Buy, 10_002, ONE),
+        book(5_000, Side::Buy, 10_002, 0),
+    ];
+    let host = backtest(PeggedOrder::new(parent(Side::Buy, ONE, Some(10_003)), PegKind::Primary, 0), tape);
+
+    assert_eq!(working(&host), [(10_001, ONE)]);
+}
+
+#[test]
+fn test_peg_waits_for_the_ack_before_repricing() {
+    let tape = vec![
+        book(1_000, Side::Buy, 10_000, ONE),
+        book(2_000, Side::Sell, 10_005, ONE),
+        // The bid moves before the child is acknowledged, then again after
+        book(2_100, Side::Buy, 10_001, ONE),
+        book(3_000, Side::Buy, 10_002, ONE),
+    ];
+    let latency = LatencyConfig { ack: LatencyModel::Constant(500), ..LatencyConfig::zero() };
+    let mut host = testing::backtest(PeggedOrder::new(parent(Side::Buy, ONE, None), PegKind::Primary, 0), tape, latency);
+
+    assert_eq!(working(&host), [(10_002, ONE)]);
+    let sent: Vec<_> = host
+        .strategy_mut()
+        .drain_events()
+        .filter_map(|e| match e {
+            AlgoEvent::ChildSent { price, .. } => Some(price / CENT),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(sent, [10_000, 10_002]);
+}
+
+#[test]
+fn test_mid_peg_capped_by_limit() {
+    let tape = vec![
+        book(1_000, Side::Buy, 10_000, ONE),
+        book(2_000, Side::Sell, 10_010, ONE),
+        book(3_000, Side::Sell, 10_020, ONE),
+        book(4_000, Side::Sell, 10_010, 0),
+    ];
+    // Mid moves 100.05 -> 100.10; the sell limit keeps it at or above 100.08
+    let host = backtest(PeggedOrder::new(parent(Side::Sell, ONE, Some(10_008)), PegKind::Mid, 0), tape.clone());
+    assert_eq!(working(&host), [(10_010, ONE)]);
+
+    let mut tape = tape;
+    tape.push(book(5_000, Side::Sell, 10_020, 0));
+    tape.push(book(5_001, Side::Sell, 10_012, ONE));
+    let host = backtest(PeggedOrder::new(parent(Side::Sell, ONE, Some(10_008)), PegKind::Mid, 0), tape);
+    assert_eq!(working(&host), [(10_008, ONE)]);
+}
+
+#[test]
+fn test_trailing_stop_sells_after_pullback() {
+    let tape = vec![
+        book(1_000, Side::Buy, 10_000, 5 * ONE),
+        book(2_000, Side::Sell, 10_002, 5 * ONE),
+        // Rally to 100.50, then fall back 30 cents
+        book(3_000, Side::Buy, 10_050, 5 * ONE),
+        book(4_000, Side::Buy, 10_020, 5 * ONE),
+        book(5_000, Side::Buy, 10_050, 0),
+    ];
+    let mut host = backtest(TrailingStop::new(parent(Side::Sell, 2 * ONE, None), 25 * CENT), tape);
+
+    let stop = host.strategy();
+    assert_eq!(stop.stop_price(), Some(10_025 * CENT));
+    assert_eq!(stop.filled(), 2 * ONE);
+    assert_eq!(stop.avg_price(), 10_020 * CENT);
+    let events: Vec<_> = host.strategy_mut().drain_events().collect();
+    assert_eq!(events[0], AlgoEvent::Triggered { price: 10_020 * CENT });
+    assert!(matches!(events.last(), Some(AlgoEvent::Completed { reason: Completion::Filled, .. })));
+}
+
+#[test]
+fn test_trailing_stop_expires_at_window_end() {
+    let tape = vec![
+        book(1_000, Side::Buy, 10_000, 5 * ONE),
+        book(2_000, Side::Sell, 10_002, 5 * ONE),
+        book(3_000, Side::Buy, 10_050, 5 * ONE),
+        // The pullback comes after the window closed
+        book(5_000, Side::Buy, 10_020, 5 * ONE),
+        book(6_000, Side::Buy, 10_050, 0),
+    ];
+    let parent = ParentOrder { end: 4_000, ..parent(Side::Sell, 2 * ONE, None) };
+    let mut host = backtest(TrailingStop::new(parent, 25 * CENT), tape);
+
+    assert!(!host.strategy().is_triggered());
+    assert!(host.strategy().is_complete());
+    let events: Vec<_> = host.strategy_mut().drain_events().collect();
+    assert_eq!(events, [AlgoEvent::Completed { reason: Completion::Expired, filled: 0, avg_price: 0 }]);
+}
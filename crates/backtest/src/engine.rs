@@ -0,0 +1,365 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::iter::Peekable;
+
+use vibe_hft_core::Timestamp;
+use vibe_hft_sbe_messages::{
+    ExchangeID, ExecutionReport, MarketDataUpdate, OrdStatus, OrderCancel, OrderEntry, Trade,
+};
+
+use crate::exchange::SimulatedExchange;
+use crate::latency::{LatencyConfig, Rng};
+
+/// A recorded public market event, stamped with the venue's timestamp.
+#[derive(Debug, Clone, Copy)]
+pub enum TapeEvent {
+    Book(MarketDataUpdate),
+    Trade(Trade),
+}
+
+impl TapeEvent {
+    pub fn timestamp(&self) -> Timestamp {
+        match self {
+            TapeEvent::Book(update) => update.timestamp,
+            TapeEvent::Trade(trade) => trade.timestamp,
+        }
+    }
+
+    pub fn exchange_id(&self) -> ExchangeID {
+        match self {
+            TapeEvent::Book(update) => update.exchange_id,
+            TapeEvent::Trade(trade) => trade.exchange_id,
+        }
+    }
+}
+
+/// An event as seen by the trading side, after its latency has elapsed.
+#[derive(Debug, Clone, Copy)]
+pub enum Event {
+    Book(MarketDataUpdate),
+    Trade(Trade),
+    Report(ExecutionReport),
+}
+
+#[derive(Debug)]
+enum Pending {
+    /// Happens at the venue: the tape event itself
+    Tape(TapeEvent),
+    /// Our order reaches the venue
+    Order(OrderEntry),
+    /// Our cancel reaches the venue
+    Cancel(OrderCancel),
+    /// Reaches us
+    Deliver(Event),
+}
+
+#[derive(Debug)]
+struct Scheduled {
+    time: Timestamp,
+    // Insertion order breaks ties so equal timestamps stay FIFO
+    seq: u64,
+    pending: Pending,
+}
+
+impl PartialEq for Scheduled {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Scheduled {}
+
+impl PartialOrd for Scheduled {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Scheduled {
+    // Reversed so the max-heap pops the earliest event first
+    fn cmp(&self, other: &Self) -> Ordering {
+        (other.time, other.seq).cmp(&(self.time, self.seq))
+    }
+}
+
+struct Venue {
+    exchange: SimulatedExchange,
+    latency: LatencyConfig,
+    // Each channel is FIFO: a message never overtakes an earlier one on the
+    // same link even if it drew a shorter latency.
+    last_feed: Timestamp,
+    last_entry: Timestamp,
+    last_ack: Timestamp,
+}
+
+/// Event-driven backtest clock.
+///
+/// Replays a tape through the simulated venues at exchange time and hands
+/// events to the trading side only once the feed or ack latency has elapsed.
+/// Orders sent by the trading side reach their venue after the order-entry
+/// latency, so a strategy can never act on data it could not have seen yet.
+pub struct BacktestEngine<I: Iterator<Item = TapeEvent>> {
+    tape: Peekable<I>,
+    venues: Vec<Venue>,
+    queue: BinaryHeap<Scheduled>,
+    seq: u64,
+    rng: Rng,
+    now: Timestamp,
+}
+
+impl<I: Iterator<Item = TapeEvent>> BacktestEngine<I> {
+    pub fn new(tape: I, seed: u64) -> Self {
+        Self {
+            tape: tape.peekable(),
+            venues: Vec::new(),
+            queue: BinaryHeap::new(),
+            seq: 0,
+            rng: Rng::new(seed),
+            now: 0,
+        }
+    }
+
+    pub fn add_venue(&mut self, exchange: SimulatedExchange, latency: LatencyConfig) {
+        self.venues.push(Venue {
+            exchange,
+            latency,
+            last_feed: 0,
+            last_entry: 0,
+            last_ack: 0,
+        });
+    }
+
+    /// Local time of the last event handed to the trading side
+    pub fn now(&self) -> Timestamp {
+        self.now
+    }
+
+    pub fn venue(&mut self, exchange_id: ExchangeID) -> Option<&mut SimulatedExchange> {
+        self.venues
+            .iter_mut()
+            .find(|v| v.exchange.exchange_id() == exchange_id)
+            .map(|v| &mut v.exchange)
+    }
+
+    /// Send an order at the current local time
+    pub fn submit(&mut self, order: OrderEntry) {
+        match self.venue_index(order.exchange_id) {
+            Some(i) => {
+                let arrival = self.entry_arrival(i);
+                self.schedule(arrival, Pending::Order(order));
+            }
+            None => {
+                // Nowhere to route it: reject locally without any latency
+                let report = ExecutionReport {
+                    timestamp: self.now,
+                    cl_ord_id: order.cl_ord_id,
+                    exec_id: 0,
+                    exchange_id: order.exchange_id,
+                    symbol_id: order.symbol_id,
+                    side: order.side,
+                    filled_quantity: 0,
+                    filled_price: 0,
+                    leaves_quantity: 0,
+                    fee: 0,
+                    status: OrdStatus::Rejected,
+                };
+                self.schedule(self.now, Pending::Deliver(Event::Report(report)));
+            }
+        }
+    }
+
+    /// Send a cancel at the current local time. Cancels for unknown venues are dropped.
+    pub fn cancel(&mut self, cancel: OrderCancel) {
+        if let Some(i) = self.venue_index(cancel.exchange_id) {
+            let arrival = self.entry_arrival(i);
+            self.schedule(arrival, Pending::Cancel(cancel));
+        }
+    }
+
+    /// Advance the simulation until the next event reaches the trading side.
+    /// Returns `None` once the tape and all in-flight messages are exhausted.
+    pub fn next_event(&mut self) -> Option<(Timestamp, Event)> {
+        self.next_event_until(Timestamp::MAX)
+    }
+
+    /// Like `next_event`, but stops at local time `deadline` if nothing reaches
+    /// the trading side by then. The local clock is moved to `deadline` in that
+    /// case so orders sent next (e.g. from a timer) leave at the right time.
+    pub fn next_event_until(&mut self, deadline: Timestamp) -> Option<(Timestamp, Event)> {
+        loop {
+            // Only pull from the tape what happens before the next scheduled event
+            while let Some(next) = self.tape.peek() {
+                let time = next.timestamp();
+                if time > deadline || self.queue.peek().is_some_and(|s| s.time < time) {
+                    break;
+                }
+                let event = self.tape.next()?;
+                self.schedule(event.timestamp(), Pending::Tape(event));
+            }
+
+            if self.queue.peek().is_none_or(|s| s.time > deadline) {
+                if deadline != Timestamp::MAX {
+                    self.now = self.now.max(deadline);
+                }
+                return None;
+            }
+            let scheduled = self.queue.pop()?;
+            match scheduled.pending {
+                Pending::Deliver(event) => {
+                    self.now = self.now.max(scheduled.time);
+                    return Some((self.now, event));
+                }
+                Pending::Tape(event) => {
+                    let Some(i) = self.venue_index(event.exchange_id()) else {
+                        continue;
+                    };
+                    let venue = &mut self.venues[i];
+                    venue.exchange.advance_to(scheduled.time);
+                    let delivered = match event {
+                        TapeEvent::Book(update) => {
+                            venue.exchange.on_market_data(&update);
+                            Event::Book(update)
+                        }
+                        TapeEvent::Trade(trade) => {
+                            venue.exchange.on_trade(&trade);
+                            Event::Trade(trade)
+                        }
+                    };
+                    let latency = venue.latency.feed.sample(scheduled.time, &mut self.rng);
+                    let arrival = (scheduled.time + latency).max(venue.last_feed);
+                    venue.last_feed = arrival;
+                    self.schedule(arrival, Pending::Deliver(delivered));
+                    self.deliver_reports(i, scheduled.time);
+                }
+                Pending::Order(order) => {
+                    if let Some(i) = self.venue_index(order.exchange_id) {
+                        self.venues[i].exchange.advance_to(scheduled.time);
+                        self.venues[i].exchange.submit(&order);
+                        self.deliver_reports(i, scheduled.time);
+                    }
+                }
+                Pending::Cancel(cancel) => {
+                    if let Some(i) = self.venue_index(cancel.exchange_id) {
+                        self.venues[i].exchange.advance_to(scheduled.time);
+                        self.venues[i].exchange.cancel(&cancel);
+                        self.deliver_reports(i, scheduled.time);
+                    }
+                }
+            }
+        }
+    }
+
+    /// True once the tape is consumed and nothing is left in flight
+    pub fn is_finished(&mut self) -> bool {
+        self.queue.is_empty() && self.tape.peek().is_none()
+    }
+
+    fn venue_index(&self, exchange_id: ExchangeID) -> Option<usize> {
+        self.venues.iter().position(|v| v.exchange.exchange_id() == exchange_id)
+    }
+
+    fn entry_arrival(&mut self, venue: usize) -> Timestamp {
+        let venue = &mut self.venues[venue];
+        let latency = venue.latency.order_entry.sample(self.now, &mut self.rng);
+        let arrival = (self.now + latency).max(venue.last_entry);
+        venue.last_entry = arrival;
+        arrival
+    }
+
+    fn deliver_reports(&mut self, venue: usize, at: Timestamp) {
+        let v = &mut self.venues[venue];
+        let mut arrivals = Vec::new();
+        for report in v.exchange.drain_reports() {
+            let latency = v.latency.ack.sample(at, &mut self.rng);
+            let arrival = (at + latency).max(v.last_ack);
+            v.last_ack = arrival;
+            arrivals.push((arrival, report));
+        }
+        for (arrival, report) in arrivals {
+            self.schedule(arrival, Pending::Deliver(Event::Report(report)));
+        }
+    }
+
+    fn schedule(&mut self, time: Timestamp, pending: Pending) {
+        self.seq += 1;
+        self.queue.push(Scheduled { time, seq: self.seq, pending });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exchange::ExchangeConfig;
+    use crate::latency::LatencyModel;
+    use vibe_hft_sbe_messages::{OrdType, Side, TimeInForce};
+
+    const ONE: u64 = 100_000_000;
+
+    fn book(timestamp: u64, side: Side, price: i64, quantity: u64) -> TapeEvent {
+        TapeEvent::Book(MarketDataUpdate {
+            timestamp,
+            exchange_id: ExchangeID::Binance,
+            symbol_id: 1,
+            side,
+            price,
+            quantity,
+            is_snapshot: 0,
+        })
+    }
+
+    fn engine(tape: Vec<TapeEvent>) -> BacktestEngine<std::vec::IntoIter<TapeEvent>> {
+        let mut engine = BacktestEngine::new(tape.into_iter(), 1);
+        engine.add_venue(
+            SimulatedExchange::new(ExchangeID::Binance, 1, ExchangeConfig::default()),
+            LatencyConfig {
+                feed: LatencyModel::Constant(100),
+                order_entry: LatencyModel::Constant(50),
+                ack: LatencyModel::Constant(30),
+            },
+        );
+        engine
+    }
+
+    #[test]
+    fn test_feed_latency_delays_delivery() {
+        let mut engine = engine(vec![
+            book(1_000, Side::Sell, 101 * ONE as i64, ONE),
+            book(1_010, Side::Buy, 100 * ONE as i64, ONE),
+        ]);
+        let (t0, _) = engine.next_event().unwrap();
+        let (t1, _) = engine.next_event().unwrap();
+        assert_eq!((t0, t1), (1_100, 1_110));
+        assert!(engine.next_event().is_none());
+    }
+
+    #[test]
+    fn test_order_sees_book_at_arrival_time() {
+        // The ask is pulled 20ns after we see it, while our order is still in flight
+        let mut engine = engine(vec![
+            book(1_000, Side::Sell, 101 * ONE as i64, ONE),
+            book(1_120, Side::Sell, 101 * ONE as i64, 0),
+        ]);
+        let (now, _) = engine.next_event().unwrap();
+        assert_eq!(now, 1_100);
+        engine.submit(OrderEntry {
+            timestamp: now,
+            cl_ord_id: 1,
+            exchange_id: ExchangeID::Binance,
+            symbol_id: 1,
+            side: Side::Buy,
+            price: 0,
+            quantity: ONE,
+            order_type: OrdType::Market,
+            time_in_force: TimeInForce::Ioc,
+        });
+
+        let mut reports = Vec::new();
+        while let Some((t, event)) = engine.next_event() {
+            if let Event::Report(report) = event {
+                reports.push((t, report.status));
+            }
+        }
+        // Arrives at 1_150, acked at 1_180, and finds nothing left to take
+        assert_eq!(reports, [(1_180, OrdStatus::New), (1_180, OrdStatus::Canceled)]);
+    }
+}
@@ -0,0 +1,549 @@
+use vibe_hft_core::{Price, Quantity, Timestamp};
+use vibe_hft_market_data::OrderBook;
+use vibe_hft_sbe_messages::{
+    ExchangeID, ExecutionReport, MarketDataUpdate, OrdStatus, OrdType, OrderCancel, OrderEntry,
+    Side, TimeInForce, Trade,
+};
+
+const FIXED_POINT: i128 = 100_000_000;
+
+/// Fee schedule in basis points of notional. Negative values are rebates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FeeSchedule {
+    pub maker_bps: f64,
+    pub taker_bps: f64,
+}
+
+impl FeeSchedule {
+    /// Fee in quote units (1e8) for a fill of `quantity` at `price`
+    pub fn fee(&self, price: Price, quantity: Quantity, is_maker: bool) -> i64 {
+        let bps = if is_maker { self.maker_bps } else { self.taker_bps };
+        let notional = price as i128 * quantity as i128 / FIXED_POINT;
+        (notional as f64 * bps / 10_000.0) as i64
+    }
+}
+
+/// How cancellations at our price level move our resting orders up the queue.
+/// Historical books never contain our own orders, so a level shrinking is
+/// ambiguous between cancels ahead of us and cancels behind us.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueueModel {
+    /// Assume cancels come from behind us: we only advance when the level
+    /// shrinks below the quantity that was ahead of us.
+    RiskAverse,
+    /// Spread each decrease over the queue in proportion to the quantity
+    /// ahead of us versus behind us.
+    Proportional,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ExchangeConfig {
+    pub fees: FeeSchedule,
+    pub queue_model: QueueModel,
+}
+
+impl Default for ExchangeConfig {
+    fn default() -> Self {
+        Self {
+            fees: FeeSchedule { maker_bps: 1.0, taker_bps: 5.0 },
+            queue_model: QueueModel::RiskAverse,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct RestingOrder {
+    entry: OrderEntry,
+    leaves: Quantity,
+    /// Market quantity ahead of us at our price level
+    queue_ahead: Quantity,
+}
+
+/// Simulated venue for a single instrument.
+///
+/// Replays the venue's public book and trade prints, accepts our `OrderEntry`
+/// and `OrderCancel` messages and produces the `ExecutionReport`s the real
+/// venue would have sent. Aggressive orders take visible liquidity without
+/// impacting the replayed book; passive orders join the back of the queue and
+/// are filled once the quantity ahead of them has traded.
+pub struct SimulatedExchange {
+    exchange_id: ExchangeID,
+    symbol_id: u32,
+    config: ExchangeConfig,
+    book: OrderBook,
+    // Kept in arrival order, which is also time priority within a level
+    orders: Vec<RestingOrder>,
+    /// Quantity traded at levels where we rest that the replayed depth has
+    /// not shown leaving yet, as (side, price, quantity)
+    traded: Vec<(Side, Price, Quantity)>,
+    reports: Vec<ExecutionReport>,
+    next_exec_id: u64,
+    now: Timestamp,
+}
+
+impl SimulatedExchange {
+    pub fn new(exchange_id: ExchangeID, symbol_id: u32, config: ExchangeConfig) -> Self {
+        Self {
+            exchange_id,
+            symbol_id,
+            config,
+            book: OrderBook::new(),
+            orders: Vec::new(),
+            traded: Vec::new(),
+            reports: Vec::new(),
+            next_exec_id: 1,
+            now: 0,
+        }
+    }
+
+    pub fn exchange_id(&self) -> ExchangeID {
+        self.exchange_id
+    }
+
+    pub fn book(&mut self) -> &mut OrderBook {
+        &mut self.book
+    }
+
+    /// Number of our orders currently resting on the simulated book
+    pub fn open_orders(&self) -> usize {
+        self.orders.len()
+    }
+
+    /// Market quantity still ahead of a resting order, if it is live
+    pub fn queue_ahead(&self, cl_ord_id: u64) -> Option<Quantity> {
+        self.orders
+            .iter()
+            .find(|o| o.entry.cl_ord_id == cl_ord_id)
+            .map(|o| o.queue_ahead)
+    }
+
+    /// Move the venue clock forward, e.g. to the arrival time of a delayed order
+    pub fn advance_to(&mut self, now: Timestamp) {
+        self.now = self.now.max(now);
+    }
+
+    /// Take the execution reports produced since the last call
+    pub fn drain_reports(&mut self) -> std::vec::Drain<'_, ExecutionReport> {
+        self.reports.drain(..)
+    }
+
+    fn is_ours(&self, exchange_id: ExchangeID, symbol_id: u32) -> bool {
+        exchange_id == self.exchange_id && symbol_id == self.symbol_id
+    }
+
+    pub fn on_market_data(&mut self, update: &MarketDataUpdate) {
+        if !self.is_ours(update.exchange_id, update.symbol_id) {
+            return;
+        }
+        self.now = self.now.max(update.timestamp);
+
+        let prev_qty = self.book.quantity_at(update.side, update.price);
+        self.book.apply_update(update);
+        let traded = self.take_traded(update.side, update.price);
+
+        // Growth at a level joins behind us; only decreases move us forward.
+        // The part of a decrease that trades already took off the queue is
+        // not counted again as cancels.
+        if update.quantity < prev_qty {
+            let decrease = (prev_qty - update.quantity).saturating_sub(traded);
+            let remaining = prev_qty.saturating_sub(traded);
+            for order in self.orders.iter_mut() {
+                if order.entry.side != update.side || order.entry.price != update.price {
+                    continue;
+                }
+                if self.config.queue_model == QueueModel::Proportional && decrease > 0 {
+                    let share = order.queue_ahead as u128 * decrease as u128 / remaining as u128;
+                    order.queue_ahead -= share as Quantity;
+                }
+                order.queue_ahead = order.queue_ahead.min(update.quantity);
+            }
+        }
+
+        // The market trading through our price means we were filled in full
+        let best_bid = self.book.best_bid().map(|l| l.price);
+        let best_ask = self.book.best_ask().map(|l| l.price);
+        let mut i = 0;
+        while i < self.orders.len() {
+            let order = self.orders[i];
+            let crossed = match order.entry.side {
+                Side::Buy => best_ask.is_some_and(|ask| ask < order.entry.price),
+                Side::Sell => best_bid.is_some_and(|bid| bid > order.entry.price),
+            };
+            if crossed {
+                self.fill_resting(i, order.leaves);
+            } else {
+                i += 1;
+            }
+        }
+    }
+
+    pub fn on_trade(&mut self, trade: &Trade) {
+        if !self.is_ours(trade.exchange_id, trade.symbol_id) {
+            return;
+        }
+        self.now = self.now.max(trade.timestamp);
+
+        // A sell aggressor hits resting bids, a buy aggressor lifts resting asks
+        let passive_side = match trade.aggressor_side {
+            Side::Buy => Side::Sell,
+            Side::Sell => Side::Buy,
+        };
+        if self.orders.iter().any(|o| o.entry.side == passive_side && o.entry.price == trade.price) {
+            match self.traded.iter_mut().find(|(side, price, _)| *side == passive_side && *price == trade.price) {
+                Some((_, _, quantity)) => *quantity += trade.quantity,
+                None => self.traded.push((passive_side, trade.price, trade.quantity)),
+            }
+        }
+
+        let mut i = 0;
+        while i < self.orders.len() {
+            let order = &mut self.orders[i];
+            if order.entry.side != passive_side {
+                i += 1;
+                continue;
+            }
+            let traded_through = match passive_side {
+                Side::Buy => trade.price < order.entry.price,
+                Side::Sell => trade.price > order.entry.price,
+            };
+            let fill_qty = if traded_through {
+                order.leaves
+            } else if trade.price == order.entry.price {
+                let fill = trade.quantity.saturating_sub(order.queue_ahead).min(order.leaves);
+                order.queue_ahead = order.queue_ahead.saturating_sub(trade.quantity);
+                fill
+            } else {
+                0
+            };
+
+            if fill_qty > 0 && self.fill_resting(i, fill_qty) {
+                continue;
+            }
+            i += 1;
+        }
+    }
+
+    pub fn submit(&mut self, order: &OrderEntry) {
+        self.now = self.now.max(order.timestamp);
+
+        let valid = self.is_ours(order.exchange_id, order.symbol_id)
+            && order.quantity > 0
+            && (order.order_type == OrdType::Market || order.price > 0)
+            && !self.orders.iter().any(|o| o.entry.cl_ord_id == order.cl_ord_id);
+        if !valid {
+            self.report(order, 0, 0, 0, 0, OrdStatus::Rejected);
+            return;
+        }
+        self.report(order, 0, 0, order.quantity, 0, OrdStatus::New);
+
+        let leaves = self.take_liquidity(order);
+        if leaves == 0 {
+            return;
+        }
+
+        match (order.order_type, order.time_in_force) {
+            // Market and IOC orders never rest; whatever the book could not absorb is canceled
+            (OrdType::Market, _) | (_, TimeInForce::Ioc) => self.report(order, 0, 0, 0, 0, OrdStatus::Canceled),
+            (OrdType::Limit, TimeInForce::Gtc) => self.orders.push(RestingOrder {
+                entry: *order,
+                leaves,
+                queue_ahead: self.book.quantity_at(order.side, order.price),
+            }),
+        }
+    }
+
+    pub fn cancel(&mut self, cancel: &OrderCancel) {
+        self.now = self.now.max(cancel.timestamp);
+
+        match self.orders.iter().position(|o| o.entry.cl_ord_id == cancel.cl_ord_id) {
+            Some(i) => {
+                let order = self.orders.remove(i);
+                self.report(&order.entry, 0, 0, 0, 0, OrdStatus::Canceled);
+            }
+            None => {
+                // Unknown or already done: reject the cancel against the requested id
+                // and the side the client gave for it
+                let exec_id = self.next_exec_id();
+                self.reports.push(ExecutionReport {
+                    timestamp: self.now,
+                    cl_ord_id: cancel.cl_ord_id,
+                    exec_id,
+                    exchange_id: cancel.exchange_id,
+                    symbol_id: cancel.symbol_id,
+                    side: cancel.side,
+                    filled_quantity: 0,
+                    filled_price: 0,
+                    leaves_quantity: 0,
+                    fee: 0,
+                    status: OrdStatus::Rejected,
+                });
+            }
+        }
+    }
+
+    /// Take the trade quantity recorded at a level, now that its depth updated
+    fn take_traded(&mut self, side: Side, price: Price) -> Quantity {
+        match self.traded.iter().position(|&(s, p, _)| s == side && p == price) {
+            Some(i) => self.traded.swap_remove(i).2,
+            None => 0,
+        }
+    }
+
+    /// Walk the opposite side of the book from the best price, filling as a taker.
+    /// Returns the quantity left unfilled.
+    fn take_liquidity(&mut self, order: &OrderEntry) -> Quantity {
+        let limit = match (order.order_type, order.side) {
+            (OrdType::Market, Side::Buy) => Price::MAX,
+            (OrdType::Market, Side::Sell) => Price::MIN,
+            (OrdType::Limit, _) => order.price,
+        };
+
+        let levels = match order.side {
+            Side::Buy => &self.book.asks,
+            Side::Sell => &self.book.bids,
+        };
+        let mut candidates: Vec<(Price, Quantity)> = levels
+            .iter()
+            .filter(|l| l.quantity > 0)
+            .filter(|l| match order.side {
+                Side::Buy => l.price <= limit,
+                Side::Sell => l.price >= limit,
+            })
+            .map(|l| (l.price, l.quantity))
+            .collect();
+        match order.side {
+            Side::Buy => candidates.sort_unstable_by_key(|&(price, _)| price),
+            Side::Sell => candidates.sort_unstable_by_key(|&(price, _)| core::cmp::Reverse(price)),
+        }
+
+        let mut leaves = order.quantity;
+        for (price, available) in candidates {
+            if leaves == 0 {
+                break;
+            }
+            let fill_qty = leaves.min(available);
+            leaves -= fill_qty;
+            let status = if leaves == 0 { OrdStatus::Filled } else { OrdStatus::PartiallyFilled };
+            let fee = self.config.fees.fee(price, fill_qty, false);
+            self.report(order, fill_qty, price, leaves, fee, status);
+        }
+        leaves
+    }
+
+    /// Fill a resting order at its own price. Returns true if it was removed.
+    fn fill_resting(&mut self, index: usize, quantity: Quantity) -> bool {
+        let order = &mut self.orders[index];
+        order.leaves -= quantity;
+        let (entry, leaves) = (order.entry, order.leaves);
+
+        let fee = self.config.fees.fee(entry.price, quantity, true);
+        let status = if leaves == 0 { OrdStatus::Filled } else { OrdStatus::PartiallyFilled };
+        self.report(&entry, quantity, entry.price, leaves, fee, status);
+
+        if leaves == 0 {
+            self.orders.remove(index);
+            true
+        } else {
+            false
+        }
+    }
+
+    fn next_exec_id(&mut self) -> u64 {
+        let id = self.next_exec_id;
+        self.next_exec_id += 1;
+        id
+    }
+
+    fn report(
+        &mut self,
+        order: &OrderEntry,
+        filled_quantity: Quantity,
+        filled_price: Price,
+        leaves_quantity: Quantity,
+        fee: i64,
+        status: OrdStatus,
+    ) {
+        let exec_id = self.next_exec_id();
+        self.reports.push(ExecutionReport {
+            timestamp: self.now,
+            cl_ord_id: order.cl_ord_id,
+            exec_id,
+            exchange_id: order.exchange_id,
+            symbol_id: order.symbol_id,
+            side: order.side,
+            filled_quantity,
+            filled_price,
+            leaves_quantity,
+            fee,
+            status,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ONE: u64 = 100_000_000;
+
+    fn level(side: Side, price: i64, quantity: u64) -> MarketDataUpdate {
+        MarketDataUpdate {
+            timestamp: 1,
+            exchange_id: ExchangeID::Binance,
+            symbol_id: 1,
+            side,
+            price,
+            quantity,
+            is_snapshot: 0,
+        }
+    }
+
+    fn order(cl_ord_id: u64, side: Side, price: i64, quantity: u64, order_type: OrdType) -> OrderEntry {
+        OrderEntry {
+            timestamp: 2,
+            cl_ord_id,
+            exchange_id: ExchangeID::Binance,
+            symbol_id: 1,
+            side,
+            price,
+            quantity,
+            order_type,
+            time_in_force: match order_type {
+                OrdType::Market => TimeInForce::Ioc,
+                OrdType::Limit => TimeInForce::Gtc,
+            },
+        }
+    }
+
+    fn trade(aggressor_side: Side, price: i64, quantity: u64) -> Trade {
+        Trade {
+            timestamp: 3,
+            exchange_id: ExchangeID::Binance,
+            symbol_id: 1,
+            trade_id: 1,
+            aggressor_side,
+            price,
+            quantity,
+        }
+    }
+
+    fn exchange() -> SimulatedExchange {
+        let mut exchange = SimulatedExchange::new(ExchangeID::Binance, 1, ExchangeConfig::default());
+        exchange.on_market_data(&level(Side::Buy, 100 * ONE as i64, 5 * ONE));
+        exchange.on_market_data(&level(Side::Sell, 101 * ONE as i64, 5 * ONE));
+        exchange
+    }
+
+    #[test]
+    fn test_passive_order_fills_after_queue_ahead() {
+        let mut ex = exchange();
+        ex.submit(&order(1, Side::Buy, 100 * ONE as i64, 2 * ONE, OrdType::Limit));
+        assert_eq!(ex.queue_ahead(1), Some(5 * ONE));
+
+        // Cancels shrink the level below our queue position
+        ex.on_market_data(&level(Side::Buy, 100 * ONE as i64, 3 * ONE));
+        assert_eq!(ex.queue_ahead(1), Some(3 * ONE));
+
+        // 4 lots trade: 3 clear the queue ahead, 1 fills us partially
+        ex.on_trade(&trade(Side::Sell, 100 * ONE as i64, 4 * ONE));
+        let reports: Vec<_> = ex.drain_reports().collect();
+        assert_eq!(reports.len(), 2);
+        assert_eq!(reports[0].status, OrdStatus::New);
+        assert_eq!(reports[1].status, OrdStatus::PartiallyFilled);
+        assert_eq!(reports[1].filled_quantity, ONE);
+        assert_eq!(reports[1].leaves_quantity, ONE);
+        assert_eq!(reports[1].fee, 10_000 * ONE as i64 / 1_000_000);
+
+        // A trade through our price fills the rest
+        ex.on_trade(&trade(Side::Sell, 99 * ONE as i64, ONE));
+        let reports: Vec<_> = ex.drain_reports().collect();
+        assert_eq!(reports[0].status, OrdStatus::Filled);
+        assert_eq!(ex.open_orders(), 0);
+    }
+
+    #[test]
+    fn test_proportional_queue_model() {
+        let config = ExchangeConfig { queue_model: QueueModel::Proportional, ..ExchangeConfig::default() };
+        let mut ex = SimulatedExchange::new(ExchangeID::Binance, 1, config);
+        ex.on_market_data(&level(Side::Sell, 101 * ONE as i64, 4 * ONE));
+        ex.submit(&order(1, Side::Sell, 101 * ONE as i64, ONE, OrdType::Limit));
+
+        // More quantity joins behind us, then half the level cancels
+        ex.on_market_data(&level(Side::Sell, 101 * ONE as i64, 8 * ONE));
+        ex.on_market_data(&level(Side::Sell, 101 * ONE as i64, 4 * ONE));
+        assert_eq!(ex.queue_ahead(1), Some(2 * ONE));
+    }
+
+    #[test]
+    fn test_trade_is_not_counted_again_as_a_cancel() {
+        let config = ExchangeConfig { queue_model: QueueModel::Proportional, ..ExchangeConfig::default() };
+        let mut ex = SimulatedExchange::new(ExchangeID::Binance, 1, config);
+        ex.on_market_data(&level(Side::Sell, 101 * ONE as i64, 4 * ONE));
+        ex.submit(&order(1, Side::Sell, 101 * ONE as i64, ONE, OrdType::Limit));
+        ex.on_market_data(&level(Side::Sell, 101 * ONE as i64, 8 * ONE));
+
+        // The print moves us up, and the depth then shows the same 2 lots leaving
+        ex.on_trade(&trade(Side::Buy, 101 * ONE as i64, 2 * ONE));
+        assert_eq!(ex.queue_ahead(1), Some(2 * ONE));
+        ex.on_market_data(&level(Side::Sell, 101 * ONE as i64, 6 * ONE));
+        assert_eq!(ex.queue_ahead(1), Some(2 * ONE));
+
+        // Later decreases are cancels again, spread over what is left
+        ex.on_market_data(&level(Side::Sell, 101 * ONE as i64, 3 * ONE));
+        assert_eq!(ex.queue_ahead(1), Some(ONE));
+    }
+
+    #[test]
+    fn test_market_order_walks_book() {
+        let mut ex = exchange();
+        ex.on_market_data(&level(Side::Sell, 102 * ONE as i64, 5 * ONE));
+        ex.submit(&order(1, Side::Buy, 0, 7 * ONE, OrdType::Market));
+
+        let reports: Vec<_> = ex.drain_reports().collect();
+        assert_eq!(reports.len(), 3);
+        assert_eq!(reports[1].filled_price, 101 * ONE as i64);
+        assert_eq!(reports[1].filled_quantity, 5 * ONE);
+        assert_eq!(reports[2].filled_price, 102 * ONE as i64);
+        assert_eq!(reports[2].status, OrdStatus::Filled);
+        assert!(reports[2].fee > 0);
+    }
+
+    #[test]
+    fn test_ioc_remainder_is_canceled() {
+        let mut ex = exchange();
+        let ioc = OrderEntry { time_in_force: TimeInForce::Ioc, ..order(1, Side::Buy, 101 * ONE as i64, 7 * ONE, OrdType::Limit) };
+        ex.submit(&ioc);
+
+        let reports: Vec<_> = ex.drain_reports().collect();
+        assert_eq!(reports.len(), 3);
+        assert_eq!(reports[1].filled_quantity, 5 * ONE);
+        assert_eq!(reports[2].status, OrdStatus::Canceled);
+        assert_eq!(ex.open_orders(), 0);
+    }
+
+    #[test]
+    fn test_cancel_and_reject() {
+        let mut ex = exchange();
+        ex.submit(&order(1, Side::Buy, 99 * ONE as i64, ONE, OrdType::Limit));
+        ex.submit(&order(1, Side::Buy, 99 * ONE as i64, ONE, OrdType::Limit));
+        let cancel = |timestamp| OrderCancel {
+            timestamp,
+            cl_ord_id: 1,
+            exchange_id: ExchangeID::Binance,
+            symbol_id: 1,
+            side: Side::Buy,
+        };
+        ex.cancel(&cancel(4));
+        ex.cancel(&cancel(5));
+        ex.cancel(&OrderCancel { side: Side::Sell, ..cancel(6) });
+
+        let reports: Vec<_> = ex.drain_reports().map(|r| (r.status, r.side)).collect();
+        assert_eq!(reports, [
+            (OrdStatus::New, Side::Buy),
+            (OrdStatus::Rejected, Side::Buy),
+            (OrdStatus::Canceled, Side::Buy),
+            (OrdStatus::Rejected, Side::Buy),
+            (OrdStatus::Rejected, Side::Sell),
+        ]);
+    }
+}
@@ -0,0 +1,144 @@
+use vibe_hft_core::Timestamp;
+
+/// One-way delay applied to a message, in nanoseconds.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LatencyModel {
+    Constant(u64),
+    /// Uniformly distributed between `min` and `max` inclusive
+    Uniform { min: u64, max: u64 },
+    /// Log-normal around `median` with shape `sigma`, which gives the long
+    /// right tail seen on real network paths. Capped at `max`.
+    LogNormal { median: u64, sigma: f64, max: u64 },
+    /// Recorded `(timestamp, latency)` samples sorted by timestamp. The latency
+    /// at a given time is linearly interpolated between the surrounding samples
+    /// and held flat before the first and after the last one.
+    Trace(Vec<(Timestamp, u64)>),
+}
+
+impl LatencyModel {
+    /// Delay for a message sent at `now`
+    pub fn sample(&self, now: Timestamp, rng: &mut Rng) -> u64 {
+        match self {
+            LatencyModel::Constant(latency) => *latency,
+            LatencyModel::Uniform { min, max } => {
+                let span = max.saturating_sub(*min);
+                if span == 0 {
+                    *min
+                } else {
+                    min + rng.next_u64() % (span + 1)
+                }
+            }
+            LatencyModel::LogNormal { median, sigma, max } => {
+                let sample = *median as f64 * (sigma * rng.next_gaussian()).exp();
+                (sample as u64).min(*max)
+            }
+            LatencyModel::Trace(samples) => interpolate(samples, now),
+        }
+    }
+}
+
+fn interpolate(samples: &[(Timestamp, u64)], now: Timestamp) -> u64 {
+    let (first, last) = match (samples.first(), samples.last()) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return 0,
+    };
+    if now <= first.0 {
+        return first.1;
+    }
+    if now >= last.0 {
+        return last.1;
+    }
+
+    // First sample strictly after `now`; the one before it is at or before `now`
+    let next = samples.partition_point(|&(t, _)| t <= now);
+    let (t0, l0) = samples[next - 1];
+    let (t1, l1) = samples[next];
+    let frac = (now - t0) as f64 / (t1 - t0) as f64;
+    (l0 as f64 + (l1 as f64 - l0 as f64) * frac) as u64
+}
+
+/// Latencies for the three legs of a round trip with one venue.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LatencyConfig {
+    /// Venue publishes market data -> we receive it
+    pub feed: LatencyModel,
+    /// We send an order or cancel -> the venue's matching engine sees it
+    pub order_entry: LatencyModel,
+    /// The venue emits an execution report -> we receive it
+    pub ack: LatencyModel,
+}
+
+impl LatencyConfig {
+    pub fn zero() -> Self {
+        Self::constant(0)
+    }
+
+    /// The same fixed delay on the feed, order entry and ack paths
+    pub fn constant(delay: Timestamp) -> Self {
+        Self {
+            feed: LatencyModel::Constant(delay),
+            order_entry: LatencyModel::Constant(delay),
+            ack: LatencyModel::Constant(delay),
+        }
+    }
+}
+
+/// Small deterministic PRNG (xorshift64*) so backtests are reproducible
+/// from a seed without pulling a dependency into the workspace.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        // xorshift gets stuck on a zero state
+        Self { state: seed.max(1) }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let mut x = self.state;
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        self.state = x;
+        x.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// Uniform in (0, 1]
+    pub fn next_f64(&mut self) -> f64 {
+        ((self.next_u64() >> 11) + 1) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Standard normal via Box-Muller
+    pub fn next_gaussian(&mut self) -> f64 {
+        let u1 = self.next_f64();
+        let u2 = self.next_f64();
+        (-2.0 * u1.ln()).sqrt() * (core::f64::consts::TAU * u2).cos()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trace_interpolation() {
+        let model = LatencyModel::Trace(vec![(1_000, 100), (2_000, 300)]);
+        let mut rng = Rng::new(7);
+        assert_eq!(model.sample(0, &mut rng), 100);
+        assert_eq!(model.sample(1_500, &mut rng), 200);
+        assert_eq!(model.sample(5_000, &mut rng), 300);
+    }
+
+    #[test]
+    fn test_distributions_stay_in_bounds() {
+        let uniform = LatencyModel::Uniform { min: 50, max: 60 };
+        let lognormal = LatencyModel::LogNormal { median: 1_000, sigma: 0.5, max: 5_000 };
+        let mut rng = Rng::new(42);
+        for _ in 0..1_000 {
+            assert!((50..=60).contains(&uniform.sample(0, &mut rng)));
+            assert!(lognormal.sample(0, &mut rng) <= 5_000);
+        }
+    }
+}
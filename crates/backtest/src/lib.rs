@@ -0,0 +1,14 @@
+// Offline simulation of venues for strategy backtests.
+// Not on the live hot path, so std collections are allowed here.
+
+pub mod engine;
+pub mod exchange;
+pub mod latency;
+pub mod runner;
+#[cfg(feature = "testing")]
+pub mod testing;
+
+pub use engine::{BacktestEngine, Event, TapeEvent};
+pub use exchange::{ExchangeConfig, FeeSchedule, QueueModel, SimulatedExchange};
+pub use latency::{LatencyConfig, LatencyModel, Rng};
+pub use runner::run;
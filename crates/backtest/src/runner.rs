@@ -0,0 +1,134 @@
+use vibe_hft_strategy::{OrderAction, Strategy, StrategyHost};
+
+use crate::engine::{BacktestEngine, Event, TapeEvent};
+
+/// Drive a strategy through a backtest until the tape is exhausted.
+///
+/// Events are dispatched to the host at their local arrival time, timers fire
+/// in between, and every action the strategy produces is sent to the engine
+/// with the current local time so order-entry latency applies.
+pub fn run<S, I>(engine: &mut BacktestEngine<I>, host: &mut StrategyHost<S>)
+where
+    S: Strategy,
+    I: Iterator<Item = TapeEvent>,
+{
+    loop {
+        let deadline = host.next_timer().unwrap_or(u64::MAX);
+        match engine.next_event_until(deadline) {
+            Some((now, Event::Book(update))) => host.on_market_data(now, &update),
+            Some((now, Event::Trade(trade))) => host.on_trade(now, &trade),
+            Some((now, Event::Report(report))) => host.on_execution_report(now, &report),
+            None => {
+                // Timers past the end of the data have nothing left to act on
+                if engine.is_finished() {
+                    break;
+                }
+                host.fire_timers(deadline);
+            }
+        }
+
+        for action in host.drain_actions() {
+            match action {
+                OrderAction::Submit(entry) => engine.submit(entry),
+                OrderAction::Cancel(cancel) => engine.cancel(cancel),
+                OrderAction::Amend { cancel, replacement } => {
+                    engine.cancel(cancel);
+                    if let Some(entry) = replacement {
+                        engine.submit(entry);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exchange::{ExchangeConfig, SimulatedExchange};
+    use crate::latency::{LatencyConfig, LatencyModel};
+    use vibe_hft_sbe_messages::{ExchangeID, ExecutionReport, MarketDataUpdate, OrdStatus, Side, Trade};
+    use vibe_hft_strategy::StrategyContext;
+
+    /// Joins the bid once, then cancels whatever is left when its timer fires
+    #[derive(Default)]
+    struct JoinThenCancel {
+        order: Option<u64>,
+        statuses: Vec<(u64, OrdStatus)>,
+    }
+
+    impl Strategy for JoinThenCancel {
+        fn on_book_update(&mut self, ctx: &mut StrategyContext<'_>, update: &MarketDataUpdate) {
+            if self.order.is_none() && update.side == Side::Buy {
+                self.order = Some(ctx.submit_limit(update.exchange_id, 1, Side::Buy, update.price, 2));
+                ctx.schedule_timer(ctx.now() + 1_000, 1);
+            }
+        }
+
+        fn on_execution_report(&mut self, ctx: &mut StrategyContext<'_>, report: &ExecutionReport) {
+            self.statuses.push((ctx.now(), report.status));
+        }
+
+        fn on_timer(&mut self, ctx: &mut StrategyContext<'_>, _timer_id: u64) {
+            if let Some(id) = self.order {
+                let _ = ctx.cancel(id);
+            }
+        }
+    }
+
+    #[test]
+    fn test_run_with_latency_and_timer() {
+        let tape = vec![
+            TapeEvent::Book(MarketDataUpdate {
+                timestamp: 1_000,
+                exchange_id: ExchangeID::Binance,
+                symbol_id: 1,
+                side: Side::Buy,
+                price: 100,
+                quantity: 5,
+                is_snapshot: 0,
+            }),
+            TapeEvent::Trade(Trade {
+                timestamp: 1_500,
+                exchange_id: ExchangeID::Binance,
+                symbol_id: 1,
+                trade_id: 1,
+                aggressor_side: Side::Sell,
+                price: 100,
+                quantity: 6,
+            }),
+            TapeEvent::Book(MarketDataUpdate {
+                timestamp: 5_000,
+                exchange_id: ExchangeID::Binance,
+                symbol_id: 1,
+                side: Side::Buy,
+                price: 100,
+                quantity: 3,
+                is_snapshot: 0,
+            }),
+        ];
+        let mut engine = BacktestEngine::new(tape.into_iter(), 1);
+        engine.add_venue(
+            SimulatedExchange::new(ExchangeID::Binance, 1, ExchangeConfig::default()),
+            LatencyConfig {
+                feed: LatencyModel::Constant(100),
+                order_entry: LatencyModel::Constant(100),
+                ack: LatencyModel::Constant(100),
+            },
+        );
+        let mut host = StrategyHost::new(JoinThenCancel::default());
+        run(&mut engine, &mut host);
+
+        // Seen at 1_100, at the venue at 1_200 behind 5 lots; the 6-lot print
+        // fills one of our two, then the timer at 2_100 cancels the rest.
+        assert_eq!(
+            host.strategy().statuses,
+            [
+                (1_300, OrdStatus::New),
+                (1_600, OrdStatus::PartiallyFilled),
+                (2_300, OrdStatus::Canceled),
+            ]
+        );
+        assert_eq!(host.orders().live_orders().count(), 0);
+    }
+}
@@ -0,0 +1,66 @@
+//! Tape builders and backtest harnesses shared by the strategy, algo and
+//! gateway tests. Everything trades symbol 1, with prices given in cents.
+
+use vibe_hft_core::{Price, Quantity, Timestamp};
+use vibe_hft_sbe_messages::{ExchangeID, MarketDataUpdate, Side, Trade};
+use vibe_hft_strategy::{Strategy, StrategyHost};
+
+use crate::{run, BacktestEngine, ExchangeConfig, LatencyConfig, SimulatedExchange, TapeEvent};
+
+/// 0.01 in 1e8 fixed point
+pub const CENT: Price = 1_000_000;
+pub const ONE: Quantity = 100_000_000;
+pub const SEC: Timestamp = 1_000_000_000;
+
+/// Binance book level at `cents`
+pub fn book(timestamp: Timestamp, side: Side, cents: i64, quantity: Quantity) -> TapeEvent {
+    book_on(timestamp, ExchangeID::Binance, side, cents, quantity)
+}
+
+pub fn book_on(timestamp: Timestamp, exchange_id: ExchangeID, side: Side, cents: i64, quantity: Quantity) -> TapeEvent {
+    TapeEvent::Book(MarketDataUpdate {
+        timestamp,
+        exchange_id,
+        symbol_id: 1,
+        side,
+        price: cents * CENT,
+        quantity,
+        is_snapshot: 0,
+    })
+}
+
+/// Binance trade print at `cents`, with the timestamp as trade id
+pub fn print(timestamp: Timestamp, aggressor_side: Side, cents: i64, quantity: Quantity) -> TapeEvent {
+    TapeEvent::Trade(Trade {
+        timestamp,
+        exchange_id: ExchangeID::Binance,
+        symbol_id: 1,
+        trade_id: timestamp,
+        aggressor_side,
+        price: cents * CENT,
+        quantity,
+    })
+}
+
+/// Run `strategy` over `tape` against a default Binance venue
+pub fn backtest<S: Strategy>(strategy: S, tape: Vec<TapeEvent>, latency: LatencyConfig) -> StrategyHost<S> {
+    backtest_venues(strategy, tape, [(ExchangeID::Binance, ExchangeConfig::default())], latency)
+}
+
+/// Run `strategy` over `tape` against one simulated venue per entry, all
+/// behind the same latency. The tape is sorted by timestamp first.
+pub fn backtest_venues<S: Strategy>(
+    strategy: S,
+    mut tape: Vec<TapeEvent>,
+    venues: impl IntoIterator<Item = (ExchangeID, ExchangeConfig)>,
+    latency: LatencyConfig,
+) -> StrategyHost<S> {
+    tape.sort_by_key(|e| e.timestamp());
+    let mut engine = BacktestEngine::new(tape.into_iter(), 1);
+    for (exchange_id, config) in venues {
+        engine.add_venue(SimulatedExchange::new(exchange_id, 1, config), latency.clone());
+    }
+    let mut host = StrategyHost::new(strategy);
+    run(&mut engine, &mut host);
+    host
+}
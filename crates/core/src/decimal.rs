@@ -0,0 +1,61 @@
+//! Exact conversion between venue decimal strings and 1e8 fixed point.
+
+use alloc::format;
+use alloc::string::String;
+
+const SCALE: i64 = 100_000_000;
+const DIGITS: usize = 8;
+
+/// `30000.5` for 3_000_050_000_000. Trailing zeros are trimmed so the value
+/// passes the venue's tick and step size filters.
+pub fn format_fixed(value: i64) -> String {
+    let sign = if value < 0 { "-" } else { "" };
+    let abs = value.unsigned_abs();
+    let (int, frac) = (abs / SCALE as u64, abs % SCALE as u64);
+    if frac == 0 {
+        return format!("{}{}", sign, int);
+    }
+    let frac = format!("{:08}", frac);
+    format!("{}{}.{}", sign, int, frac.trim_end_matches('0'))
+}
+
+/// Parses `"0.00150000"` into 150_000. Digits beyond 1e8 are truncated.
+pub fn parse_fixed(text: &str) -> Option<i64> {
+    let (negative, digits) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text),
+    };
+    let (int, frac) = digits.split_once('.').unwrap_or((digits, ""));
+    if int.is_empty() && frac.is_empty() {
+        return None;
+    }
+    if !int.bytes().chain(frac.bytes()).all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    let mut value: i64 = if int.is_empty() { 0 } else { int.parse::<i64>().ok()?.checked_mul(SCALE)? };
+    let mut unit = SCALE / 10;
+    for b in frac.bytes().take(DIGITS) {
+        value = value.checked_add((b - b'0') as i64 * unit)?;
+        unit /= 10;
+    }
+    Some(if negative { -value } else { value })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        assert_eq!(format_fixed(3_000_050_000_000), "30000.5");
+        assert_eq!(format_fixed(1_000_000), "0.01");
+        assert_eq!(format_fixed(-25 * SCALE), "-25");
+        assert_eq!(parse_fixed("30000.50000000"), Some(3_000_050_000_000));
+        assert_eq!(parse_fixed("0.00000001"), Some(1));
+        assert_eq!(parse_fixed("-0.1"), Some(-10_000_000));
+        assert_eq!(parse_fixed("1.123456789"), Some(112_345_678));
+        assert_eq!(parse_fixed(""), None);
+        assert_eq!(parse_fixed("1e5"), None);
+    }
+}
@@ -2,10 +2,29 @@
 
 // Common types used across the workspace
 
+extern crate alloc;
+
+mod decimal;
+
+pub use decimal::{format_fixed, parse_fixed};
+
 pub type Timestamp = u64; // Nanoseconds
 pub type Price = i64;     // Fixed point 1e8
 pub type Quantity = u64;  // Fixed point 1e8
 
+pub const NANOS_PER_MILLI: Timestamp = 1_000_000;
+
+/// Venue epoch milliseconds as a `Timestamp`, for converting at ingestion
+pub const fn from_millis(ms: u64) -> Timestamp {
+    ms.saturating_mul(NANOS_PER_MILLI)
+}
+
+/// Epoch milliseconds of a `Timestamp`, for consumers that cannot hold
+/// nanoseconds (JavaScript numbers are exact up to 2^53)
+pub const fn to_millis(timestamp: Timestamp) -> u64 {
+    timestamp / NANOS_PER_MILLI
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum InstrumentType {
     Spot,
@@ -24,3 +43,31 @@ pub struct Order {
     pub quantity: Quantity,
     pub side: OrderSide,
 }
+
+/// Trading rules for an instrument on one venue. Tick and lot are in the
+/// same 1e8 fixed point as `Price` and `Quantity`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Instrument {
+    pub symbol_id: u32,
+    pub instrument_type: InstrumentType,
+    pub tick_size: Price,
+    pub lot_size: Quantity,
+}
+
+impl Instrument {
+    /// Round down to the tick grid (for bids)
+    pub fn floor_price(&self, price: Price) -> Price {
+        price.div_euclid(self.tick_size) * self.tick_size
+    }
+
+    /// Round up to the tick grid (for asks)
+    pub fn ceil_price(&self, price: Price) -> Price {
+        let floor = self.floor_price(price);
+        if floor == price { floor } else { floor + self.tick_size }
+    }
+
+    /// Round down to a whole number of lots
+    pub fn floor_quantity(&self, quantity: Quantity) -> Quantity {
+        quantity / self.lot_size * self.lot_size
+    }
+}
@@ -0,0 +1,187 @@
+//! USDⓈ-M perpetual market data: mark price and funding from
+//! `<symbol>@markPrice`, venue liquidations from `<symbol>@forceOrder`, and
+//! open interest, which is only served over REST.
+
+use reqwest::{Client, Url};
+use serde::Deserialize;
+use serde_json::Value;
+use vibe_hft_core::{from_millis, parse_fixed};
+use vibe_hft_sbe_messages::{ExchangeID, ForcedLiquidation, MarkPriceUpdate, OpenInterest, Side};
+
+use crate::error::ConnectorError;
+use crate::perp::{find, PerpEvent, PerpSymbol};
+
+const OPEN_INTEREST_PATH: &str = "/fapi/v1/openInterest";
+
+#[derive(Debug, Deserialize)]
+struct ForceOrder {
+    #[serde(rename = "s")]
+    symbol: String,
+    /// Side of the liquidation order, opposite to the position
+    #[serde(rename = "S")]
+    side: String,
+    #[serde(rename = "p")]
+    price: String,
+    #[serde(rename = "ap")]
+    average_price: String,
+    #[serde(rename = "q")]
+    quantity: String,
+    #[serde(rename = "z")]
+    filled_quantity: String,
+    #[serde(rename = "T")]
+    trade_time: u64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "e")]
+enum MarketEvent {
+    #[serde(rename = "markPriceUpdate")]
+    MarkPrice {
+        #[serde(rename = "E")]
+        event_time: u64,
+        #[serde(rename = "s")]
+        symbol: String,
+        #[serde(rename = "p")]
+        mark_price: String,
+        #[serde(rename = "i")]
+        index_price: String,
+        #[serde(rename = "r")]
+        funding_rate: String,
+        #[serde(rename = "T")]
+        next_funding_time: u64,
+    },
+    #[serde(rename = "forceOrder")]
+    ForceOrder {
+        #[serde(rename = "o")]
+        order: ForceOrder,
+    },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenInterestBody {
+    symbol: String,
+    #[serde(rename = "openInterest")]
+    open_interest: String,
+    time: u64,
+}
+
+/// Combined-stream path for mark price (every second) and liquidations of
+/// `symbols`, to append to the futures WebSocket URL
+pub fn perp_streams(symbols: &[PerpSymbol]) -> String {
+    let streams: Vec<String> = symbols
+        .iter()
+        .flat_map(|s| {
+            let name = s.name.to_lowercase();
+            [format!("{}@markPrice@1s", name), format!("{}@forceOrder", name)]
+        })
+        .collect();
+    format!("/stream?streams={}", streams.join("/"))
+}
+
+/// Parse one message of the futures market streams, raw or wrapped by the
+/// combined stream, single or as the `!markPrice@arr` array. `emit`
+/// receives an event per update on one of `symbols`.
+pub fn parse_perp_event(text: &str, symbols: &[PerpSymbol], mut emit: impl FnMut(PerpEvent)) {
+    let Ok(mut value) = serde_json::from_str::<Value>(text) else { return };
+    if let Some(data) = value.get_mut("data") {
+        value = data.take();
+    }
+    let events = match value {
+        Value::Array(events) => events,
+        event => vec![event],
+    };
+    for event in events {
+        if let Some(event) = serde_json::from_value(event).ok().and_then(|e| convert(e, symbols)) {
+            emit(event);
+        }
+    }
+}
+
+fn convert(event: MarketEvent, symbols: &[PerpSymbol]) -> Option<PerpEvent> {
+    match event {
+        MarketEvent::MarkPrice { event_time, symbol, mark_price, index_price, funding_rate, next_funding_time } => {
+            let symbol = find(symbols, &symbol)?;
+            Some(PerpEvent::MarkPrice(MarkPriceUpdate {
+                timestamp: from_millis(event_time),
+                exchange_id: ExchangeID::Binance,
+                symbol_id: symbol.symbol_id(),
+                mark_price: parse_fixed(&mark_price)?,
+                index_price: parse_fixed(&index_price)?,
+                funding_rate: parse_fixed(&funding_rate)?,
+                next_funding_time: from_millis(next_funding_time),
+            }))
+        }
+        MarketEvent::ForceOrder { order } => {
+            let symbol = find(symbols, &order.symbol)?;
+            let side = match order.side.as_str() {
+                "SELL" => Side::Buy,
+                "BUY" => Side::Sell,
+                _ => return None,
+            };
+            // Filled size and price once executed, the order's own before
+            let price = match parse_fixed(&order.average_price)? {
+                0 => parse_fixed(&order.price)?,
+                average => average,
+            };
+            let quantity = match parse_fixed(&order.filled_quantity)? {
+                0 => parse_fixed(&order.quantity)?,
+                filled => filled,
+            };
+            Some(PerpEvent::Liquidation(ForcedLiquidation {
+                timestamp: from_millis(order.trade_time),
+                exchange_id: ExchangeID::Binance,
+                symbol_id: symbol.symbol_id(),
+                side,
+                price,
+                quantity: quantity as u64,
+            }))
+        }
+        MarketEvent::Other => None,
+    }
+}
+
+/// Parse a `GET /fapi/v1/openInterest` response body
+pub fn parse_open_interest(body: &str, symbols: &[PerpSymbol]) -> Option<OpenInterest> {
+    let body: OpenInterestBody = serde_json::from_str(body).ok()?;
+    let symbol = find(symbols, &body.symbol)?;
+    Some(OpenInterest {
+        timestamp: from_millis(body.time),
+        exchange_id: ExchangeID::Binance,
+        symbol_id: symbol.symbol_id(),
+        open_interest: parse_fixed(&body.open_interest)? as u64,
+    })
+}
+
+/// Open interest from the public REST API, with one pooled client for all
+/// polls. Weight 1 per request, no key needed.
+#[derive(Debug, Clone)]
+pub struct OpenInterestClient {
+    base: Url,
+    client: Client,
+}
+
+impl OpenInterestClient {
+    /// `rest_url` is the futures API base, `https://fapi.binance.com`
+    pub fn new(rest_url: &str) -> Result<Self, ConnectorError> {
+        let base =
+            Url::parse(rest_url).map_err(|e| ConnectorError::Config(format!("bad REST url {}: {}", rest_url, e)))?;
+        let client = Client::builder().tcp_nodelay(true).build().map_err(|e| ConnectorError::Config(e.to_string()))?;
+        Ok(Self { base, client })
+    }
+
+    /// Current open interest of `symbol`
+    pub async fn fetch(&self, symbol: &PerpSymbol) -> Result<OpenInterest, ConnectorError> {
+        let mut url = self.base.join(OPEN_INTEREST_PATH).map_err(|e| ConnectorError::Config(e.to_string()))?;
+        url.query_pairs_mut().append_pair("symbol", &symbol.name);
+        let response = self.client.get(url).send().await?;
+        let status = response.status().as_u16();
+        let body = response.text().await?;
+        if status >= 400 {
+            return Err(ConnectorError::Venue { status, message: body });
+        }
+        parse_open_interest(&body, std::slice::from_ref(symbol))
+            .ok_or_else(|| ConnectorError::Protocol(format!("unexpected open interest response: {}", body)))
+    }
+}
@@ -0,0 +1,179 @@
+//! Bybit linear perpetual market data from the v5 public stream: mark,
+//! index, funding and open interest from `tickers.<symbol>`, venue
+//! liquidations from `allLiquidation.<symbol>`.
+
+use serde::Deserialize;
+use serde_json::Value;
+use vibe_hft_core::{from_millis, parse_fixed};
+use vibe_hft_sbe_messages::{ExchangeID, ForcedLiquidation, MarkPriceUpdate, OpenInterest, Side};
+
+use crate::perp::{find, PerpEvent, PerpSymbol};
+
+pub const BYBIT_LINEAR_WS_URL: &str = "wss://stream.bybit.com/v5/public/linear";
+
+#[derive(Debug, Deserialize)]
+struct Envelope {
+    topic: String,
+    ts: u64,
+    data: Value,
+}
+
+/// Ticker fields we follow; a `delta` only carries the ones that changed
+#[derive(Debug, Deserialize)]
+struct TickerData {
+    symbol: String,
+    #[serde(rename = "markPrice")]
+    mark_price: Option<String>,
+    #[serde(rename = "indexPrice")]
+    index_price: Option<String>,
+    #[serde(rename = "fundingRate")]
+    funding_rate: Option<String>,
+    #[serde(rename = "nextFundingTime")]
+    next_funding_time: Option<String>,
+    #[serde(rename = "openInterest")]
+    open_interest: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LiquidationData {
+    #[serde(rename = "T")]
+    time: u64,
+    #[serde(rename = "s")]
+    symbol: String,
+    /// Side of the liquidated position
+    #[serde(rename = "S")]
+    side: String,
+    #[serde(rename = "v")]
+    size: String,
+    #[serde(rename = "p")]
+    price: String,
+}
+
+/// Latest ticker fields of one symbol
+#[derive(Debug, Clone, Copy, Default)]
+struct Ticker {
+    mark_price: Option<i64>,
+    index_price: Option<i64>,
+    funding_rate: Option<i64>,
+    next_funding_time: Option<u64>,
+}
+
+/// `{"op":"subscribe",...}` request for the topics of `symbols`
+pub fn subscribe_request(symbols: &[PerpSymbol]) -> String {
+    let args: Vec<String> = symbols
+        .iter()
+        .flat_map(|s| [format!("\"tickers.{}\"", s.name), format!("\"allLiquidation.{}\"", s.name)])
+        .collect();
+    format!("{{\"op\":\"subscribe\",\"args\":[{}]}}", args.join(","))
+}
+
+/// Parser for one connection to the linear public stream.
+///
+/// Tickers arrive as a snapshot and then deltas of the changed fields only,
+/// so the latest value of each is kept per symbol and a `MarkPrice` event
+/// is only emitted once all of them are known. Call `reset` on reconnect.
+#[derive(Debug, Clone)]
+pub struct BybitPerpFeed {
+    symbols: Vec<PerpSymbol>,
+    tickers: Vec<Ticker>,
+}
+
+impl BybitPerpFeed {
+    pub fn new(symbols: Vec<PerpSymbol>) -> Self {
+        let tickers = vec![Ticker::default(); symbols.len()];
+        Self { symbols, tickers }
+    }
+
+    pub fn symbols(&self) -> &[PerpSymbol] {
+        &self.symbols
+    }
+
+    /// Forget merged ticker state; the next snapshot starts over
+    pub fn reset(&mut self) {
+        self.tickers.fill(Ticker::default());
+    }
+
+    /// Parse one stream message. Subscription acks, pongs and unknown
+    /// topics or symbols emit nothing.
+    pub fn on_message(&mut self, text: &str, mut emit: impl FnMut(PerpEvent)) {
+        let Ok(envelope) = serde_json::from_str::<Envelope>(text) else { return };
+        let Some((kind, _)) = envelope.topic.split_once('.') else { return };
+        match kind {
+            "tickers" => {
+                if let Ok(data) = serde_json::from_value::<TickerData>(envelope.data) {
+                    self.on_ticker(from_millis(envelope.ts), data, &mut emit);
+                }
+            }
+            "allLiquidation" => {
+                let Ok(liquidations) = serde_json::from_value::<Vec<LiquidationData>>(envelope.data) else { return };
+                for liquidation in liquidations {
+                    if let Some(event) = self.liquidation(liquidation) {
+                        emit(event);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn on_ticker(&mut self, ts: u64, data: TickerData, emit: &mut impl FnMut(PerpEvent)) {
+        let Some(index) = self.symbols.iter().position(|s| s.name == data.symbol) else { return };
+        let symbol_id = self.symbols[index].symbol_id();
+        let ticker = &mut self.tickers[index];
+
+        let mut changed = false;
+        for (field, slot) in [
+            (&data.mark_price, &mut ticker.mark_price),
+            (&data.index_price, &mut ticker.index_price),
+            (&data.funding_rate, &mut ticker.funding_rate),
+        ] {
+            if let Some(value) = field.as_deref().and_then(parse_fixed) {
+                *slot = Some(value);
+                changed = true;
+            }
+        }
+        if let Some(time) = data.next_funding_time.as_deref().and_then(|t| t.parse().ok()) {
+            ticker.next_funding_time = Some(from_millis(time));
+            changed = true;
+        }
+
+        if let Some(open_interest) = data.open_interest.as_deref().and_then(parse_fixed) {
+            emit(PerpEvent::OpenInterest(OpenInterest {
+                timestamp: ts,
+                exchange_id: ExchangeID::Bybit,
+                symbol_id,
+                open_interest: open_interest as u64,
+            }));
+        }
+        if let (true, Some(mark_price), Some(index_price), Some(funding_rate), Some(next_funding_time)) =
+            (changed, ticker.mark_price, ticker.index_price, ticker.funding_rate, ticker.next_funding_time)
+        {
+            emit(PerpEvent::MarkPrice(MarkPriceUpdate {
+                timestamp: ts,
+                exchange_id: ExchangeID::Bybit,
+                symbol_id,
+                mark_price,
+                index_price,
+                funding_rate,
+                next_funding_time,
+            }));
+        }
+    }
+
+    fn liquidation(&self, data: LiquidationData) -> Option<PerpEvent> {
+        let symbol = find(&self.symbols, &data.symbol)?;
+        let side = match data.side.as_str() {
+            "Buy" => Side::Buy,
+            "Sell" => Side::Sell,
+            _ => return None,
+        };
+        Some(PerpEvent::Liquidation(ForcedLiquidation {
+            timestamp: from_millis(data.time),
+            exchange_id: ExchangeID::Bybit,
+            symbol_id: symbol.symbol_id(),
+            side,
+            price: parse_fixed(&data.price)?,
+            quantity: parse_fixed(&data.size)? as u64,
+        }))
+    }
+}
@@ -0,0 +1,32 @@
+use std::fmt;
+
+#[derive(Debug)]
+pub enum ConnectorError {
+    /// A REST request that did not complete: connect, TLS or transfer
+    Http(reqwest::Error),
+    /// The venue answered with an HTTP error status
+    Venue { status: u16, message: String },
+    /// A response we could not make sense of
+    Protocol(String),
+    /// Invalid endpoint configuration
+    Config(String),
+}
+
+impl fmt::Display for ConnectorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConnectorError::Http(e) => write!(f, "http error: {}", e),
+            ConnectorError::Venue { status, message } => write!(f, "venue error (HTTP {}): {}", status, message),
+            ConnectorError::Protocol(message) => write!(f, "protocol error: {}", message),
+            ConnectorError::Config(message) => write!(f, "configuration error: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for ConnectorError {}
+
+impl From<reqwest::Error> for ConnectorError {
+    fn from(e: reqwest::Error) -> Self {
+        ConnectorError::Http(e)
+    }
+}
@@ -0,0 +1,13 @@
+// Public market data connectors for the perpetual swap feeds the spot
+// stream does not carry: open interest, mark price, funding and venue
+// liquidations from Binance USDⓈ-M and Bybit linear. Stream parsers and
+// the open interest REST client; the gateway owns the WebSocket sessions.
+// Network code, so std is allowed here.
+
+pub mod binance;
+pub mod bybit;
+pub mod error;
+pub mod perp;
+
+pub use error::ConnectorError;
+pub use perp::{PerpEvent, PerpSymbol};
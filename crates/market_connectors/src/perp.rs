@@ -0,0 +1,49 @@
+//! Venue-neutral view of the perpetual swap feeds: the symbols we follow
+//! and the events the connectors parse out of their public streams.
+
+use vibe_hft_core::{Instrument, InstrumentType};
+use vibe_hft_sbe_messages::{ForcedLiquidation, MarkPriceUpdate, OpenInterest};
+
+/// A perpetual by its venue name, e.g. `BTCUSDT` on both Binance USDⓈ-M
+/// and Bybit linear
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PerpSymbol {
+    pub name: String,
+    pub instrument: Instrument,
+}
+
+impl PerpSymbol {
+    /// Panics unless `instrument` is a `Perpetual`
+    pub fn new(name: &str, instrument: Instrument) -> Self {
+        assert_eq!(instrument.instrument_type, InstrumentType::Perpetual, "{} is not a perpetual", name);
+        Self { name: name.to_string(), instrument }
+    }
+
+    pub fn symbol_id(&self) -> u32 {
+        self.instrument.symbol_id
+    }
+}
+
+pub(crate) fn find<'a>(symbols: &'a [PerpSymbol], name: &str) -> Option<&'a PerpSymbol> {
+    symbols.iter().find(|s| s.name == name)
+}
+
+/// One parsed message of a derivatives feed. Timestamps are venue event
+/// times, converted to ns like the gateway's spot market data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PerpEvent {
+    OpenInterest(OpenInterest),
+    MarkPrice(MarkPriceUpdate),
+    Liquidation(ForcedLiquidation),
+}
+
+impl PerpEvent {
+    /// The framed SBE message, for publishing
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            PerpEvent::OpenInterest(m) => m.to_bytes().to_vec(),
+            PerpEvent::MarkPrice(m) => m.to_bytes().to_vec(),
+            PerpEvent::Liquidation(m) => m.to_bytes().to_vec(),
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use vibe_hft_core::{Instrument, InstrumentType};
+use vibe_hft_market_connectors::binance::{parse_open_interest, parse_perp_event, perp_streams, OpenInterestClient};
+use vibe_hft_market_connectors::bybit::{subscribe_request, BybitPerpFeed};
+use vibe_hft_market_connectors::{PerpEvent, PerpSymbol};
+use vibe_hft_sbe_messages::{ExchangeID, ForcedLiquidation, MarkPriceUpdate, OpenInterest, Side};
+
+fn symbols() -> Vec<PerpSymbol> {
+    let instrument =
+        Instrument { symbol_id: 1, instrument_type: InstrumentType::Perpetual, tick_size: 10_000_000, lot_size: 100_000 };
+    vec![PerpSymbol::new("BTCUSDT", instrument)]
+}
+
+#[test]
+#[should_panic(expected = "not a perpetual")]
+fn test_perp_symbol_requires_a_perpetual() {
+    PerpSymbol::new("BTCUSDT", Instrument { symbol_id: 1, instrument_type: InstrumentType::Spot, tick_size: 1, lot_size: 1 });
+}
+
+#[test]
+fn test_binance_usdm_stream_fixture() {
+    let mut events = Vec::new();
+    for line in include_str!("fixtures/binance/usdm_perp_stream.jsonl").lines() {
+        parse_perp_event(line, &symbols(), |e| events.push(e));
+    }
+
+    // Subscription result and ETHUSDT updates are skipped
+    assert_eq!(events.len(), 5);
+    assert_eq!(events[0], PerpEvent::MarkPrice(MarkPriceUpdate {
+        timestamp: 1_700_000_000_000_000_000,
+        exchange_id: ExchangeID::Binance,
+        symbol_id: 1,
+        mark_price: 3_725_010_000_000,
+        index_price: 3_726_851_913_043,
+        funding_rate: 10_000,
+        next_funding_time: 1_700_006_400_000_000_000,
+    }));
+    // A SELL liquidation order closes a long, at its average fill price
+    assert_eq!(events[1], PerpEvent::Liquidation(ForcedLiquidation {
+        timestamp: 1_700_000_000_508_000_000,
+        exchange_id: ExchangeID::Binance,
+        symbol_id: 1,
+        side: Side::Buy,
+        price: 3_718_040_000_000,
+        quantity: 25_000_000,
+    }));
+    let PerpEvent::MarkPrice(mark) = events[2] else { panic!("expected mark price, got {:?}", events[2]) };
+    assert_eq!(mark.funding_rate, -2_150);
+    let PerpEvent::Liquidation(short) = events[3] else { panic!("expected liquidation, got {:?}", events[3]) };
+    assert_eq!((short.side, short.price, short.quantity), (Side::Sell, 3_730_120_000_000, 100_400_000));
+    // Unwrapped `!markPrice@arr` array
+    let PerpEvent::MarkPrice(mark) = events[4] else { panic!("expected mark price, got {:?}", events[4]) };
+    assert_eq!((mark.timestamp, mark.mark_price), (1_700_000_002_000_000_000, 3_732_050_000_000));
+
+    assert_eq!(perp_streams(&symbols()), "/stream?streams=btcusdt@markPrice@1s/btcusdt@forceOrder");
+}
+
+#[test]
+fn test_bybit_linear_stream_fixture() {
+    let mut feed = BybitPerpFeed::new(symbols());
+    let mut events = Vec::new();
+    for line in include_str!("fixtures/bybit/linear_public.jsonl").lines() {
+        feed.on_message(line, |e| events.push(e));
+    }
+
+    let open_interest = |timestamp, open_interest| {
+        PerpEvent::OpenInterest(OpenInterest { timestamp, exchange_id: ExchangeID::Bybit, symbol_id: 1, open_interest })
+    };
+    let mark = |timestamp, mark_price, index_price| {
+        PerpEvent::MarkPrice(MarkPriceUpdate {
+            timestamp,
+            exchange_id: ExchangeID::Bybit,
+            symbol_id: 1,
+            mark_price,
+            index_price,
+            funding_rate: -21_200,
+            next_funding_time: 1_700_006_400_000_000_000,
+        })
+    };
+    let liquidation = |timestamp, side, price, quantity| {
+        PerpEvent::Liquidation(ForcedLiquidation { timestamp, exchange_id: ExchangeID::Bybit, symbol_id: 1, side, price, quantity })
+    };
+    assert_eq!(events, [
+        open_interest(1_700_000_000_105_000_000, 5_234_171_800_000),
+        mark(1_700_000_000_105_000_000, 3_725_031_000_000, 3_726_802_000_000),
+        // A delta without mark fields only moves open interest
+        open_interest(1_700_000_000_205_000_000, 5_234_411_800_000),
+        // Funding carried over from the snapshot
+        mark(1_700_000_000_305_000_000, 3_725_264_000_000, 3_726_990_000_000),
+        // Bybit reports the liquidated position's side directly
+        liquidation(1_700_000_000_410_000_000, Side::Buy, 3_719_040_000_000, 73_100_000),
+        liquidation(1_700_000_000_412_000_000, Side::Sell, 3_726_000_000_000, 200_000),
+    ]);
+
+    // After a reconnect, deltas wait for a new snapshot
+    feed.reset();
+    let mut events = Vec::new();
+    feed.on_message(
+        r#"{"topic":"tickers.BTCUSDT","type":"delta","data":{"symbol":"BTCUSDT","markPrice":"37300.00"},"cs":1,"ts":5}"#,
+        |e| events.push(e),
+    );
+    assert!(events.is_empty());
+
+    assert_eq!(
+        subscribe_request(feed.symbols()),
+        r#"{"op":"subscribe","args":["tickers.BTCUSDT","allLiquidation.BTCUSDT"]}"#
+    );
+}
+
+#[tokio::test]
+async fn test_binance_open_interest_over_rest() {
+    let body = include_str!("fixtures/binance/open_interest.json");
+    assert_eq!(parse_open_interest(body, &symbols()), Some(OpenInterest {
+        timestamp: 1_700_000_001_500_000_000,
+        exchange_id: ExchangeID::Binance,
+        symbol_id: 1,
+        open_interest: 8_123_456_700_000,
+    }));
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let server = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut raw = Vec::new();
+        let mut buf = [0u8; 1024];
+        while !raw.windows(4).any(|w| w == b"\r\n\r\n") {
+            let n = stream.read(&mut buf).await.unwrap();
+            raw.extend_from_slice(&buf[..n]);
+        }
+        let response = format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", body.len(), body);
+        stream.write_all(response.as_bytes()).await.unwrap();
+        String::from_utf8(raw).unwrap()
+    });
+
+    let client = OpenInterestClient::new(&url).unwrap();
+    let open_interest = client.fetch(&symbols()[0]).await.unwrap();
+    assert_eq!(open_interest.open_interest, 8_123_456_700_000);
+    let request = server.await.unwrap();
+    assert!(request.starts_with("GET /fapi/v1/openInterest?symbol=BTCUSDT HTTP/1.1\r\n"));
+}
@@ -0,0 +1,282 @@
+use vibe_hft_core::Timestamp;
+use vibe_hft_sbe_messages::{Alert, CvdUpdate, ExchangeID, IndicatorKind, IndicatorUpdate, Side};
+
+/// Rules an `AlertEngine` holds
+pub const ALERT_MAX_RULES: usize = 32;
+/// Conditions one rule can combine
+pub const ALERT_MAX_CONDITIONS: usize = 4;
+
+/// One test on an indicator stream. `side` is the direction it looks for:
+/// `Buy` for at/above or rising, `Sell` for at/below or falling.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Condition {
+    /// The value is at or beyond `level`
+    Threshold { level: f64, side: Side },
+    /// The value went through `level` and has stayed beyond it since. Unlike
+    /// a threshold, a series that starts beyond the level does not count.
+    Cross { level: f64, side: Side },
+    /// The value moves at least `rate` per second between two samples
+    Slope { rate: f64, side: Side },
+}
+
+/// Conditions on one indicator series, all of which must hold.
+///
+/// A rule fires once when its conditions become true and have held for
+/// `persistence`, then not again until they have been false in between and
+/// `cooldown` has passed since it last fired.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AlertRule {
+    /// Carried by the alerts, e.g. `Alert::AGGRESSIVE_FLOW`
+    pub id: u32,
+    pub indicator: IndicatorKind,
+    /// Venue of the series, `None` for the one across venues
+    pub exchange_id: Option<ExchangeID>,
+    /// Only values over this window, for indicators published over several; 0 for any
+    pub window: u64,
+    /// Pressure the alert points to
+    pub direction: Side,
+    pub conditions: [Option<Condition>; ALERT_MAX_CONDITIONS],
+    pub persistence: u64,
+    pub cooldown: u64,
+}
+
+impl AlertRule {
+    pub fn new(id: u32, indicator: IndicatorKind, exchange_id: Option<ExchangeID>, direction: Side) -> Self {
+        Self {
+            id,
+            indicator,
+            exchange_id,
+            window: 0,
+            direction,
+            conditions: [None; ALERT_MAX_CONDITIONS],
+            persistence: 0,
+            cooldown: 0,
+        }
+    }
+
+    /// Add a condition. Panics past `ALERT_MAX_CONDITIONS`.
+    pub fn when(mut self, condition: Condition) -> Self {
+        let slot = self.conditions.iter_mut().find(|c| c.is_none()).expect("too many alert conditions");
+        *slot = Some(condition);
+        self
+    }
+
+    /// "Aggressive Flow" from the PRD: NOBI crosses 0.5 (-0.5 for `Sell`)
+    /// while accelerating by more than 0.1/s in the same direction
+    pub fn aggressive_flow(exchange_id: Option<ExchangeID>, side: Side, cooldown: u64) -> Self {
+        let level = match side {
+            Side::Buy => 0.5,
+            Side::Sell => -0.5,
+        };
+        Self { cooldown, ..Self::new(Alert::AGGRESSIVE_FLOW, IndicatorKind::Nobi, exchange_id, side) }
+            .when(Condition::Cross { level, side })
+            .when(Condition::Slope { rate: 0.1, side })
+    }
+
+    fn matches(&self, indicator: IndicatorKind, exchange_id: Option<ExchangeID>, window: u64) -> bool {
+        self.indicator == indicator && self.exchange_id == exchange_id && (self.window == 0 || self.window == window)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct RuleState {
+    last: Option<(Timestamp, f64)>,
+    crossed: [bool; ALERT_MAX_CONDITIONS],
+    active_since: Option<Timestamp>,
+    /// Fired during the current activation
+    fired: bool,
+    last_fired: Option<Timestamp>,
+}
+
+/// `value` is beyond `level` in the direction of `side`
+fn beyond(value: f64, level: f64, side: Side) -> bool {
+    match side {
+        Side::Buy => value >= level,
+        Side::Sell => value <= level,
+    }
+}
+
+/// Nearest 1e8 fixed-point value (no `f64::round` without std)
+fn fixed(value: f64) -> i64 {
+    let scaled = value * 100_000_000.0;
+    (if scaled < 0.0 { scaled - 0.5 } else { scaled + 0.5 }) as i64
+}
+
+/// Rule-based alerts over the indicator streams of one symbol.
+///
+/// Rules are registered up front into fixed slots and keep their own view
+/// of the series they watch, so evaluating a sample allocates nothing.
+#[derive(Debug, Clone)]
+pub struct AlertEngine {
+    symbol_id: u32,
+    /// Timestamp units in one second, for slopes
+    ticks_per_second: u64,
+    rules: [Option<(AlertRule, RuleState)>; ALERT_MAX_RULES],
+}
+
+impl AlertEngine {
+    pub fn new(symbol_id: u32, ticks_per_second: u64) -> Self {
+        Self { symbol_id, ticks_per_second, rules: [None; ALERT_MAX_RULES] }
+    }
+
+    /// Register a rule; false when all slots are taken
+    pub fn add_rule(&mut self, rule: AlertRule) -> bool {
+        match self.rules.iter_mut().find(|r| r.is_none()) {
+            Some(slot) => {
+                *slot = Some((rule, RuleState::default()));
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn rules(&self) -> impl Iterator<Item = &AlertRule> {
+        self.rules.iter().flatten().map(|(rule, _)| rule)
+    }
+
+    pub fn on_indicator(&mut self, update: &IndicatorUpdate, emit: impl FnMut(&Alert)) {
+        if update.symbol_id == self.symbol_id {
+            let value = update.value as f64 / 100_000_000.0;
+            self.on_value(update.timestamp, update.kind, update.exchange_id, update.window, value, emit);
+        }
+    }
+
+    /// Feeds the CVD after the latest trade of the bar
+    pub fn on_cvd(&mut self, update: &CvdUpdate, emit: impl FnMut(&Alert)) {
+        if update.symbol_id == self.symbol_id {
+            let value = update.close as f64 / 100_000_000.0;
+            self.on_value(update.timestamp, IndicatorKind::Cvd, update.exchange_id, 0, value, emit);
+        }
+    }
+
+    /// Evaluate a new sample of a series, in plain units (e.g. NOBI in
+    /// `[-1, 1]`, spread in bps). `emit` receives the alerts it fires.
+    pub fn on_value(
+        &mut self,
+        now: Timestamp,
+        indicator: IndicatorKind,
+        exchange_id: Option<ExchangeID>,
+        window: u64,
+        value: f64,
+        mut emit: impl FnMut(&Alert),
+    ) {
+        for (rule, state) in self.rules.iter_mut().flatten() {
+            if !rule.matches(indicator, exchange_id, window) {
+                continue;
+            }
+            let rate = match state.last {
+                Some((t, last)) if now > t => Some((value - last) * self.ticks_per_second as f64 / (now - t) as f64),
+                _ => None,
+            };
+
+            let mut all = rule.conditions.iter().any(Option::is_some);
+            for (i, condition) in rule.conditions.iter().enumerate() {
+                let holds = match *condition {
+                    None => true,
+                    Some(Condition::Threshold { level, side }) => beyond(value, level, side),
+                    Some(Condition::Cross { level, side }) => {
+                        let through = state.last.is_some_and(|(_, last)| !beyond(last, level, side));
+                        state.crossed[i] = beyond(value, level, side) && (state.crossed[i] || through);
+                        state.crossed[i]
+                    }
+                    Some(Condition::Slope { rate: min, side }) => rate.is_some_and(|r| match side {
+                        Side::Buy => r >= min,
+                        Side::Sell => r <= -min,
+                    }),
+                };
+                all &= holds;
+            }
+            state.last = Some((now, value));
+
+            if !all {
+                state.active_since = None;
+                state.fired = false;
+                continue;
+            }
+            let since = *state.active_since.get_or_insert(now);
+            let cooled = state.last_fired.is_none_or(|t| now - t >= rule.cooldown);
+            if state.fired || now - since < rule.persistence || !cooled {
+                continue;
+            }
+            state.fired = true;
+            state.last_fired = Some(now);
+            emit(&Alert {
+                timestamp: now,
+                exchange_id,
+                symbol_id: self.symbol_id,
+                rule_id: rule.id,
+                indicator,
+                direction: rule.direction,
+                value: fixed(value),
+                rate: fixed(rate.unwrap_or(0.0)),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    /// Feed (time in ms, value) samples of the global NOBI
+    fn feed(engine: &mut AlertEngine, samples: &[(u64, f64)]) -> Vec<Alert> {
+        let mut alerts = Vec::new();
+        for &(now, value) in samples {
+            engine.on_value(now, IndicatorKind::Nobi, None, 1_000, value, |a| alerts.push(*a));
+        }
+        alerts
+    }
+
+    #[test]
+    fn test_aggressive_flow_needs_cross_and_acceleration() {
+        let mut engine = AlertEngine::new(1, 1_000);
+        assert!(engine.add_rule(AlertRule::aggressive_flow(None, Side::Buy, 5_000)));
+        assert!(engine.add_rule(AlertRule::aggressive_flow(None, Side::Sell, 5_000)));
+
+        // Drifting through 0.5 at 0.05/s: no alert
+        assert!(feed(&mut engine, &[(0, 0.45), (1_000, 0.5), (2_000, 0.55)]).is_empty());
+
+        // Back under, then through again at 0.2/s
+        let alerts = feed(&mut engine, &[(3_000, 0.4), (3_500, 0.6), (3_600, 0.65)]);
+        assert_eq!(alerts.len(), 1);
+        let alert = alerts[0];
+        assert_eq!((alert.rule_id, alert.direction, alert.timestamp), (Alert::AGGRESSIVE_FLOW, Side::Buy, 3_500));
+        assert_eq!((alert.value, alert.rate), (60_000_000, 40_000_000));
+
+        // Starting beyond the level is not a cross
+        let mut engine = AlertEngine::new(1, 1_000);
+        engine.add_rule(AlertRule::aggressive_flow(None, Side::Sell, 0));
+        assert!(feed(&mut engine, &[(0, -0.6), (100, -0.7)]).is_empty());
+        assert_eq!(feed(&mut engine, &[(200, -0.4), (300, -0.6)]).len(), 1);
+    }
+
+    #[test]
+    fn test_persistence_and_cooldown() {
+        let mut engine = AlertEngine::new(1, 1_000);
+        let rule = AlertRule { persistence: 200, cooldown: 1_000, ..AlertRule::new(7, IndicatorKind::Spread, Some(ExchangeID::Bybit), Side::Sell) }
+            .when(Condition::Threshold { level: 5.0, side: Side::Buy });
+        engine.add_rule(rule);
+
+        let mut alerts = Vec::new();
+        let mut spread = |now, bps| engine.on_value(now, IndicatorKind::Spread, Some(ExchangeID::Bybit), 0, bps, |a| alerts.push(*a));
+        spread(0, 6.0);
+        spread(100, 7.0);
+        // Held for 200: fires once while it stays wide
+        spread(200, 6.5);
+        spread(300, 8.0);
+        // Narrow, then wide again within the cooldown and past it
+        spread(400, 2.0);
+        spread(500, 6.0);
+        spread(900, 6.0);
+        spread(1_300, 6.0);
+        let times: Vec<_> = alerts.iter().map(|a| a.timestamp).collect();
+        assert_eq!(times, [200, 1_300]);
+
+        // Other venues and indicators are not this rule's series
+        let mut other = Vec::new();
+        engine.on_value(2_000, IndicatorKind::Spread, None, 0, 9.0, |a| other.push(*a));
+        engine.on_value(2_000, IndicatorKind::Ofi, Some(ExchangeID::Bybit), 0, 9.0, |a| other.push(*a));
+        assert!(other.is_empty());
+    }
+}
@@ -0,0 +1,300 @@
+use vibe_hft_core::Timestamp;
+use vibe_hft_sbe_messages::{CvdUpdate, ExchangeID, Side, Trade};
+
+use crate::{venue_index, VENUES};
+
+/// Bar and session boundaries, in the units of the trade timestamps
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CvdConfig {
+    /// Length of one CVD candle
+    pub bar_interval: u64,
+    /// CVD restarts from zero at every session boundary
+    pub session_length: u64,
+    /// Session start relative to the epoch, e.g. 0 for UTC midnight with daily sessions
+    pub session_offset: u64,
+}
+
+impl Default for CvdConfig {
+    /// One-minute bars, daily sessions from UTC midnight (ns)
+    fn default() -> Self {
+        Self {
+            bar_interval: 60_000_000_000,
+            session_length: 86_400_000_000_000,
+            session_offset: 0,
+        }
+    }
+}
+
+impl CvdConfig {
+    fn bar_start(&self, now: Timestamp) -> Timestamp {
+        now - now % self.bar_interval
+    }
+
+    pub(crate) fn session(&self, now: Timestamp) -> u64 {
+        now.saturating_sub(self.session_offset) / self.session_length
+    }
+}
+
+/// One CVD candle: the delta path over a bar
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CvdBar {
+    pub start: Timestamp,
+    pub open: i64,
+    pub high: i64,
+    pub low: i64,
+    pub close: i64,
+    pub buy_volume: u64,
+    pub sell_volume: u64,
+}
+
+impl CvdBar {
+    fn open_at(start: Timestamp, cvd: i64) -> Self {
+        Self { start, open: cvd, high: cvd, low: cvd, close: cvd, buy_volume: 0, sell_volume: 0 }
+    }
+}
+
+impl From<&CvdUpdate> for CvdBar {
+    fn from(update: &CvdUpdate) -> Self {
+        Self {
+            start: update.bar_start,
+            open: update.open,
+            high: update.high,
+            low: update.low,
+            close: update.close,
+            buy_volume: update.buy_volume,
+            sell_volume: update.sell_volume,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct CvdSeries {
+    session: Option<u64>,
+    cvd: i64,
+    bar: Option<CvdBar>,
+    /// End of the last closed bar; trades before it arrived too late
+    closed_until: Timestamp,
+}
+
+impl CvdSeries {
+    /// Close the current bar if `now` is past its end or in a new session
+    fn roll(&mut self, config: &CvdConfig, now: Timestamp) -> Option<CvdBar> {
+        let new_session = self.session.is_some_and(|s| s != config.session(now));
+        let closed = match self.bar {
+            Some(bar) if new_session || now >= bar.start + config.bar_interval => self.bar.take(),
+            _ => None,
+        };
+        if let Some(bar) = closed {
+            self.closed_until = bar.start + config.bar_interval;
+        }
+        if new_session {
+            self.session = None;
+            self.cvd = 0;
+        }
+        closed
+    }
+
+    /// A trade from a bar already published as closed
+    fn is_late(&self, trade: &Trade) -> bool {
+        trade.timestamp < self.closed_until
+    }
+
+    fn on_trade(&mut self, config: &CvdConfig, trade: &Trade) -> Option<CvdBar> {
+        let closed = self.roll(config, trade.timestamp);
+        self.session = Some(config.session(trade.timestamp));
+        let bar = self.bar.get_or_insert(CvdBar::open_at(config.bar_start(trade.timestamp), self.cvd));
+        match trade.aggressor_side {
+            Side::Buy => {
+                self.cvd += trade.quantity as i64;
+                bar.buy_volume += trade.quantity;
+            }
+            Side::Sell => {
+                self.cvd -= trade.quantity as i64;
+                bar.sell_volume += trade.quantity;
+            }
+        }
+        bar.close = self.cvd;
+        bar.high = bar.high.max(self.cvd);
+        bar.low = bar.low.min(self.cvd);
+        closed
+    }
+}
+
+/// Cumulative volume delta from trade prints, per venue and across venues,
+/// for one symbol.
+///
+/// Each aggressive buy adds its quantity and each aggressive sell subtracts
+/// it. The running value is also cut into bar-aligned candles, and restarts
+/// from zero at every session boundary. State is fixed-size and updates are
+/// handed to a callback, so nothing allocates per trade.
+#[derive(Debug, Clone)]
+pub struct CvdEngine {
+    config: CvdConfig,
+    symbol_id: u32,
+    venues: [CvdSeries; VENUES],
+    global: CvdSeries,
+}
+
+impl CvdEngine {
+    pub fn new(symbol_id: u32, config: CvdConfig) -> Self {
+        Self { config, symbol_id, venues: [CvdSeries::default(); VENUES], global: CvdSeries::default() }
+    }
+
+    pub fn config(&self) -> &CvdConfig {
+        &self.config
+    }
+
+    /// Add a trade. `emit` receives, for the venue then across venues, the
+    /// bar the trade closed (if any) followed by the updated current bar.
+    /// Trades on other symbols are ignored, as are trades older than the
+    /// last closed bar of a series, since that candle is already final.
+    pub fn on_trade(&mut self, trade: &Trade, mut emit: impl FnMut(&CvdUpdate)) {
+        if trade.symbol_id != self.symbol_id {
+            return;
+        }
+        let venue = Some(trade.exchange_id);
+        let index = venue_index(trade.exchange_id);
+        if !self.venues[index].is_late(trade) {
+            if let Some(bar) = self.venues[index].on_trade(&self.config, trade) {
+                emit(&self.update(trade.timestamp, venue, &bar, true));
+            }
+            if let Some(bar) = self.venues[index].bar {
+                emit(&self.update(trade.timestamp, venue, &bar, false));
+            }
+        }
+        if !self.global.is_late(trade) {
+            if let Some(bar) = self.global.on_trade(&self.config, trade) {
+                emit(&self.update(trade.timestamp, None, &bar, true));
+            }
+            if let Some(bar) = self.global.bar {
+                emit(&self.update(trade.timestamp, None, &bar, false));
+            }
+        }
+    }
+
+    /// Close bars that ended without a trade since, so quiet venues still
+    /// publish their final candle. Call from a timer.
+    pub fn on_timer(&mut self, now: Timestamp, mut emit: impl FnMut(&CvdUpdate)) {
+        for (i, exchange_id) in [ExchangeID::Binance, ExchangeID::Bybit, ExchangeID::Coinbase].into_iter().enumerate() {
+            if let Some(bar) = self.venues[i].roll(&self.config, now) {
+                emit(&self.update(now, Some(exchange_id), &bar, true));
+            }
+        }
+        if let Some(bar) = self.global.roll(&self.config, now) {
+            emit(&self.update(now, None, &bar, true));
+        }
+    }
+
+    /// Current CVD of a venue, or across venues for `None`
+    pub fn cvd(&self, exchange_id: Option<ExchangeID>) -> i64 {
+        self.series(exchange_id).cvd
+    }
+
+    /// Bar in progress of a venue, or across venues for `None`
+    pub fn bar(&self, exchange_id: Option<ExchangeID>) -> Option<CvdBar> {
+        self.series(exchange_id).bar
+    }
+
+    /// Start a new session now, outside the configured boundaries
+    pub fn reset(&mut self) {
+        self.venues = [CvdSeries::default(); VENUES];
+        self.global = CvdSeries::default();
+    }
+
+    fn series(&self, exchange_id: Option<ExchangeID>) -> &CvdSeries {
+        exchange_id.map_or(&self.global, |id| &self.venues[venue_index(id)])
+    }
+
+    fn update(&self, now: Timestamp, exchange_id: Option<ExchangeID>, bar: &CvdBar, closed: bool) -> CvdUpdate {
+        CvdUpdate {
+            timestamp: now,
+            exchange_id,
+            symbol_id: self.symbol_id,
+            bar_start: bar.start,
+            open: bar.open,
+            high: bar.high,
+            low: bar.low,
+            close: bar.close,
+            buy_volume: bar.buy_volume,
+            sell_volume: bar.sell_volume,
+            closed,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    const CONFIG: CvdConfig = CvdConfig { bar_interval: 10, session_length: 100, session_offset: 0 };
+
+    fn trade(timestamp: u64, exchange_id: ExchangeID, aggressor_side: Side, quantity: u64) -> Trade {
+        Trade { timestamp, exchange_id, symbol_id: 1, trade_id: timestamp, aggressor_side, price: 100, quantity }
+    }
+
+    fn run(engine: &mut CvdEngine, trade: Trade) -> Vec<CvdUpdate> {
+        let mut updates = Vec::new();
+        engine.on_trade(&trade, |u| updates.push(*u));
+        updates
+    }
+
+    #[test]
+    fn test_candles_per_venue_and_global() {
+        let mut engine = CvdEngine::new(1, CONFIG);
+        run(&mut engine, trade(1, ExchangeID::Binance, Side::Buy, 5));
+        run(&mut engine, trade(2, ExchangeID::Bybit, Side::Sell, 8));
+        let updates = run(&mut engine, trade(3, ExchangeID::Binance, Side::Sell, 2));
+        assert_eq!(updates.len(), 2);
+        assert_eq!((engine.cvd(Some(ExchangeID::Binance)), engine.cvd(Some(ExchangeID::Bybit)), engine.cvd(None)), (3, -8, -5));
+
+        let global = engine.bar(None).unwrap();
+        assert_eq!((global.open, global.high, global.low, global.close), (0, 5, -5, -5));
+        assert_eq!((global.buy_volume, global.sell_volume), (5, 10));
+
+        // The next bar closes the previous one and opens at its close
+        let updates = run(&mut engine, trade(12, ExchangeID::Binance, Side::Buy, 1));
+        assert_eq!(updates.len(), 4);
+        assert!(updates[0].closed && updates[2].closed);
+        assert_eq!((updates[0].exchange_id, updates[0].close), (Some(ExchangeID::Binance), 3));
+        assert_eq!((updates[1].bar_start, updates[1].open, updates[1].close), (10, 3, 4));
+        assert_eq!((updates[3].exchange_id, updates[3].open, updates[3].close), (None, -5, -4));
+    }
+
+    #[test]
+    fn test_timer_closes_quiet_bars_and_sessions_reset() {
+        let mut engine = CvdEngine::new(1, CONFIG);
+        run(&mut engine, trade(95, ExchangeID::Coinbase, Side::Buy, 7));
+        let mut closed = Vec::new();
+        engine.on_timer(99, |u| closed.push(*u));
+        assert!(closed.is_empty());
+        engine.on_timer(100, |u| closed.push(*u));
+        assert_eq!(closed.len(), 2);
+        assert!(closed.iter().all(|u| u.closed && u.close == 7));
+
+        // New session: back to zero
+        let updates = run(&mut engine, trade(105, ExchangeID::Coinbase, Side::Sell, 2));
+        assert_eq!(updates.len(), 2);
+        assert_eq!((updates[0].open, updates[0].close), (0, -2));
+        assert_eq!(engine.cvd(None), -2);
+        assert!(run(&mut engine, Trade { symbol_id: 2, ..trade(106, ExchangeID::Coinbase, Side::Buy, 1) }).is_empty());
+    }
+
+    #[test]
+    fn test_late_trades_do_not_reopen_closed_bars() {
+        let mut engine = CvdEngine::new(1, CONFIG);
+        run(&mut engine, trade(5, ExchangeID::Binance, Side::Buy, 4));
+        run(&mut engine, trade(12, ExchangeID::Bybit, Side::Sell, 1));
+
+        // Bar 0..10 closed globally, still open on Binance
+        let updates = run(&mut engine, trade(8, ExchangeID::Binance, Side::Buy, 2));
+        assert_eq!(updates.len(), 1);
+        assert_eq!((updates[0].exchange_id, updates[0].bar_start, updates[0].close), (Some(ExchangeID::Binance), 0, 6));
+        assert_eq!(engine.bar(None).map(|bar| bar.start), Some(10));
+        assert_eq!(engine.cvd(None), 3);
+
+        run(&mut engine, trade(15, ExchangeID::Binance, Side::Buy, 1));
+        assert!(run(&mut engine, trade(9, ExchangeID::Binance, Side::Sell, 3)).is_empty());
+        assert_eq!((engine.cvd(Some(ExchangeID::Binance)), engine.cvd(None)), (7, 4));
+    }
+}
@@ -0,0 +1,329 @@
+use vibe_hft_core::{Price, Quantity, Timestamp};
+use vibe_hft_sbe_messages::{DivergenceKind, DivergenceSignal, ExchangeID, Side, Trade};
+
+use crate::cvd::{CvdBar, CvdConfig};
+
+/// Bars kept for swing detection and the volume average
+pub const DIVERGENCE_HISTORY: usize = 64;
+
+/// Price candle of the same bar as a `CvdBar`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PriceBar {
+    pub start: Timestamp,
+    pub open: Price,
+    pub high: Price,
+    pub low: Price,
+    pub close: Price,
+    pub volume: Quantity,
+}
+
+impl PriceBar {
+    pub fn open_with(start: Timestamp, trade: &Trade) -> Self {
+        Self {
+            start,
+            open: trade.price,
+            high: trade.price,
+            low: trade.price,
+            close: trade.price,
+            volume: trade.quantity,
+        }
+    }
+
+    pub fn add(&mut self, trade: &Trade) {
+        self.high = self.high.max(trade.price);
+        self.low = self.low.min(trade.price);
+        self.close = trade.price;
+        self.volume += trade.quantity;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DivergenceConfig {
+    /// Bars on each side a swing high/low must exceed
+    pub swing_window: usize,
+    /// Bars averaged for the relative volume behind the confidence score
+    pub volume_lookback: usize,
+    /// Swings further apart than this many bars are not compared
+    pub max_pivot_distance: u64,
+    /// Price moves within this band of the previous swing count as holding
+    /// the level, which makes a CVD breakout an absorption
+    pub absorption_tolerance_bps: i64,
+    /// Session boundaries of the CVD bars; CVD restarts from zero at each,
+    /// so swings in different sessions are not compared
+    pub cvd: CvdConfig,
+}
+
+impl Default for DivergenceConfig {
+    fn default() -> Self {
+        Self {
+            swing_window: 3,
+            volume_lookback: 20,
+            max_pivot_distance: 50,
+            absorption_tolerance_bps: 5,
+            cvd: CvdConfig::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Pivot {
+    index: u64,
+    session: u64,
+    start: Timestamp,
+    price: Price,
+    cvd: i64,
+}
+
+/// Price/CVD divergences at swing points, for one venue or across venues.
+///
+/// A bar is a swing high (low) once `swing_window` bars on each side are
+/// all lower (higher); each swing is compared with the previous one of the
+/// same kind:
+///
+/// | Swing | Price                 | CVD          | Kind       | Direction |
+/// |-------|-----------------------|--------------|------------|-----------|
+/// | high  | higher high           | lower high   | Regular    | bearish   |
+/// | high  | lower high            | higher high  | Hidden     | bearish   |
+/// | high  | holds the level       | higher high  | Absorption | bearish   |
+/// | low   | lower low             | higher low   | Regular    | bullish   |
+/// | low   | higher low            | lower low    | Hidden     | bullish   |
+/// | low   | holds the level       | lower low    | Absorption | bullish   |
+///
+/// Bars live in a fixed ring, so nothing allocates per bar.
+#[derive(Debug, Clone)]
+pub struct DivergenceDetector {
+    config: DivergenceConfig,
+    exchange_id: Option<ExchangeID>,
+    symbol_id: u32,
+    bars: [(PriceBar, CvdBar); DIVERGENCE_HISTORY],
+    /// Bars seen so far; the newest is at `(seen - 1) % DIVERGENCE_HISTORY`
+    seen: u64,
+    last_high: Option<Pivot>,
+    last_low: Option<Pivot>,
+}
+
+impl DivergenceDetector {
+    pub fn new(exchange_id: Option<ExchangeID>, symbol_id: u32, config: DivergenceConfig) -> Self {
+        assert!(config.swing_window >= 1 && 2 * config.swing_window < DIVERGENCE_HISTORY);
+        assert!(config.volume_lookback >= 1 && config.volume_lookback <= DIVERGENCE_HISTORY);
+        Self {
+            config,
+            exchange_id,
+            symbol_id,
+            bars: [(PriceBar::default(), CvdBar::default()); DIVERGENCE_HISTORY],
+            seen: 0,
+            last_high: None,
+            last_low: None,
+        }
+    }
+
+    /// Add a closed bar. `emit` receives the divergences it confirms: the
+    /// swing is `swing_window` bars back, so signals lag by that many bars.
+    pub fn on_bar(&mut self, price: &PriceBar, cvd: &CvdBar, mut emit: impl FnMut(&DivergenceSignal)) {
+        self.bars[(self.seen % DIVERGENCE_HISTORY as u64) as usize] = (*price, *cvd);
+        self.seen += 1;
+
+        let w = self.config.swing_window;
+        if self.seen < (2 * w + 1) as u64 {
+            return;
+        }
+        let (pivot_price, pivot_cvd) = *self.back(w);
+        let index = self.seen - 1 - w as u64;
+        let (mut is_high, mut is_low) = (true, true);
+        for back in (0..=2 * w).filter(|&b| b != w) {
+            let (bar, _) = self.back(back);
+            // Ties go to the earlier bar
+            let older = back > w;
+            is_high &= if older { bar.high < pivot_price.high } else { bar.high <= pivot_price.high };
+            is_low &= if older { bar.low > pivot_price.low } else { bar.low >= pivot_price.low };
+        }
+
+        let now = price.start;
+        let confidence = self.confidence(pivot_price.volume);
+        let session = self.config.cvd.session(pivot_cvd.start);
+        if is_high {
+            let pivot = Pivot { index, session, start: pivot_price.start, price: pivot_price.high, cvd: pivot_cvd.high };
+            if let Some(signal) = self.compare(now, self.last_high, pivot, Side::Sell, confidence) {
+                emit(&signal);
+            }
+            self.last_high = Some(pivot);
+        }
+        if is_low {
+            let pivot = Pivot { index, session, start: pivot_price.start, price: pivot_price.low, cvd: pivot_cvd.low };
+            if let Some(signal) = self.compare(now, self.last_low, pivot, Side::Buy, confidence) {
+                emit(&signal);
+            }
+            self.last_low = Some(pivot);
+        }
+    }
+
+    /// `back` bars before the newest
+    fn back(&self, back: usize) -> &(PriceBar, CvdBar) {
+        let index = (self.seen - 1 - back as u64) % DIVERGENCE_HISTORY as u64;
+        &self.bars[index as usize]
+    }
+
+    /// `v / (v + mean)`: 50 at average volume, towards 100 as it grows
+    fn confidence(&self, volume: Quantity) -> u8 {
+        let n = (self.config.volume_lookback as u64).min(self.seen) as usize;
+        let total: u128 = (0..n).map(|b| self.back(b).0.volume as u128).sum();
+        let mean = total / n as u128;
+        let volume = volume as u128;
+        if volume + mean == 0 {
+            return 0;
+        }
+        (100 * volume / (volume + mean)) as u8
+    }
+
+    /// `direction` is the expected move: `Sell` for swing highs, `Buy` for lows
+    fn compare(
+        &self,
+        now: Timestamp,
+        prev: Option<Pivot>,
+        pivot: Pivot,
+        direction: Side,
+        confidence: u8,
+    ) -> Option<DivergenceSignal> {
+        let prev =
+            prev.filter(|p| p.session == pivot.session && pivot.index - p.index <= self.config.max_pivot_distance)?;
+        let tolerance = prev.price.abs() as i128 * self.config.absorption_tolerance_bps as i128 / 10_000;
+        let dp = pivot.price as i128 - prev.price as i128;
+        let dc = pivot.cvd - prev.cvd;
+        // Signs relative to the swing: "beyond" is higher for highs, lower for lows
+        let (beyond_price, beyond_cvd) = match direction {
+            Side::Sell => (dp, dc),
+            Side::Buy => (-dp, -dc),
+        };
+
+        let kind = if dp.abs() <= tolerance {
+            (beyond_cvd > 0).then_some(DivergenceKind::Absorption)?
+        } else if beyond_price > 0 && beyond_cvd < 0 {
+            DivergenceKind::Regular
+        } else if beyond_price < 0 && beyond_cvd > 0 {
+            DivergenceKind::Hidden
+        } else {
+            return None;
+        };
+
+        Some(DivergenceSignal {
+            timestamp: now,
+            exchange_id: self.exchange_id,
+            symbol_id: self.symbol_id,
+            kind,
+            direction,
+            pivot_time: pivot.start,
+            pivot_price: pivot.price,
+            pivot_cvd: pivot.cvd,
+            prev_pivot_time: prev.start,
+            prev_pivot_price: prev.price,
+            prev_pivot_cvd: prev.cvd,
+            confidence,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    const CONFIG: DivergenceConfig = DivergenceConfig {
+        swing_window: 2,
+        volume_lookback: 4,
+        max_pivot_distance: 20,
+        absorption_tolerance_bps: 10,
+        cvd: CvdConfig { bar_interval: 10, session_length: 1000, session_offset: 0 },
+    };
+
+    /// Feed bars with the given (high, low, cvd high, cvd low, volume)
+    fn feed(detector: &mut DivergenceDetector, bars: &[(Price, Price, i64, i64, Quantity)]) -> Vec<DivergenceSignal> {
+        let mut signals = Vec::new();
+        for (i, &(high, low, cvd_high, cvd_low, volume)) in bars.iter().enumerate() {
+            let start = i as u64 * 10;
+            let price = PriceBar { start, open: low, high, low, close: high, volume };
+            let cvd =
+                CvdBar { start, open: cvd_low, high: cvd_high, low: cvd_low, close: cvd_high, buy_volume: 0, sell_volume: 0 };
+            detector.on_bar(&price, &cvd, |s| signals.push(*s));
+        }
+        signals
+    }
+
+    #[test]
+    fn test_regular_bearish_divergence() {
+        let mut detector = DivergenceDetector::new(None, 1, CONFIG);
+        // Higher high in price at bar 6, lower high in CVD
+        let signals = feed(&mut detector, &[
+            (10000, 9900, 10, 0, 10),
+            (10100, 10000, 20, 10, 10),
+            (10500, 10100, 50, 20, 10),
+            (10200, 10000, 30, 10, 10),
+            (10100, 9950, 20, 5, 10),
+            (10400, 10000, 30, 10, 10),
+            (10800, 10300, 40, 25, 30),
+            (10600, 10400, 35, 20, 10),
+            (10500, 10350, 30, 15, 10),
+        ]);
+        assert_eq!(signals.len(), 1);
+        let signal = signals[0];
+        assert_eq!((signal.kind, signal.direction), (DivergenceKind::Regular, Side::Sell));
+        assert_eq!((signal.prev_pivot_price, signal.pivot_price), (10500, 10800));
+        assert_eq!((signal.prev_pivot_cvd, signal.pivot_cvd), (50, 40));
+        assert_eq!((signal.pivot_time, signal.timestamp), (60, 80));
+        // 30 against a mean of 15 over the last 4 bars
+        assert_eq!(signal.confidence, 66);
+    }
+
+    #[test]
+    fn test_swings_across_a_session_reset_are_not_compared() {
+        // Same bars as above with a session boundary between the two highs
+        let config = DivergenceConfig { cvd: CvdConfig { session_length: 50, ..CONFIG.cvd }, ..CONFIG };
+        let mut detector = DivergenceDetector::new(None, 1, config);
+        let signals = feed(&mut detector, &[
+            (10000, 9900, 10, 0, 10),
+            (10100, 10000, 20, 10, 10),
+            (10500, 10100, 50, 20, 10),
+            (10200, 10000, 30, 10, 10),
+            (10100, 9950, 20, 5, 10),
+            (10400, 10000, 30, 10, 10),
+            (10800, 10300, 40, 25, 30),
+            (10600, 10400, 35, 20, 10),
+            (10500, 10350, 30, 15, 10),
+        ]);
+        assert!(signals.is_empty());
+    }
+
+    #[test]
+    fn test_hidden_and_absorption_at_lows() {
+        let lows = |second_low: Price, second_cvd: i64| {
+            let mut detector = DivergenceDetector::new(Some(ExchangeID::Binance), 1, CONFIG);
+            feed(&mut detector, &[
+                (10200, 10100, 0, -10, 10),
+                (10100, 10000, -10, -20, 10),
+                (10000, 9800, -20, -40, 10),
+                (10100, 9900, -15, -30, 10),
+                (10200, 10000, -10, -20, 10),
+                (10100, 9950, -15, -30, 10),
+                (10000, second_low, -30, second_cvd, 10),
+                (10100, 9960, -25, -40, 10),
+                (10200, 10000, -20, -35, 10),
+            ])
+        };
+
+        // Higher low in price, lower low in CVD: continuation
+        let signals = lows(9900, -60);
+        assert_eq!(signals.len(), 1);
+        assert_eq!((signals[0].kind, signals[0].direction), (DivergenceKind::Hidden, Side::Buy));
+        assert_eq!(signals[0].kind.marker(), 'C');
+
+        // Same low within 10 bps while CVD breaks lower: absorbed
+        let signals = lows(9805, -60);
+        assert_eq!(signals[0].kind, DivergenceKind::Absorption);
+
+        // Lower low in price, higher low in CVD: reversal
+        let signals = lows(9700, -35);
+        assert_eq!(signals[0].kind, DivergenceKind::Regular);
+
+        // Both confirm: no divergence
+        assert!(lows(9700, -60).is_empty());
+    }
+}
@@ -1,11 +1,17 @@
-#![no_std]
+#![cfg_attr(not(test), no_std)]
 
 extern crate alloc;
+pub mod alerts;
+pub mod cvd;
+pub mod divergence;
+pub mod liquidation;
+pub mod nobi;
 pub mod ofi;
+pub mod volume_profile;
 
 use vibe_hft_sbe_messages::{MarketDataUpdate, Side, ExchangeID};
-use smallvec::SmallVec;
-use core::cell::RefCell;
+
+use crate::nobi::{weighted_nobi, DepthBand};
 
 // Placeholder for hftbacktest structures if available, otherwise we define our own optimized ones.
 // For this scaffolding, we simulate the "No Heap Allocation" constraint using fixed-size arrays or pre-allocated buffers.
@@ -13,6 +19,13 @@ use core::cell::RefCell;
 pub const MAX_PRICE_LEVELS: usize = 1000;
 pub const MAX_ORDERS: usize = 10000;
 
+/// Venues the per-venue indicator series are kept for
+pub(crate) const VENUES: usize = 3;
+
+pub(crate) fn venue_index(exchange_id: ExchangeID) -> usize {
+    exchange_id as usize - 1
+}
+
 #[derive(Debug, Clone, Copy, Default)]
 pub struct PriceLevel {
     pub price: i64,
@@ -31,6 +44,12 @@ pub struct OrderBook {
     // In a real HFT system, we would use a HashMap<OrderId, OrderNode> backed by a pre-allocated Arena.
 }
 
+impl Default for OrderBook {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl OrderBook {
     pub fn new() -> Self {
         Self {
@@ -55,20 +74,26 @@ impl OrderBook {
 
         // Naive implementation for demonstration: find level and update.
         // Real implementation would use binary search or direct indexing if price is normalized.
-        for level in levels.iter_mut() {
-            if level.price == update.price {
-                level.quantity = update.quantity;
-                // Invalidate cache when updating existing level
-                self.invalidate_cache(update.side);
-                return;
-            }
-            if level.price == 0 { // Empty slot
-                level.price = update.price;
-                level.quantity = update.quantity;
-                // Invalidate cache when adding new level
-                self.invalidate_cache(update.side);
-                return;
-            }
+        // Removed levels are cleared (price 0), so their slots are reused and
+        // a price lives in at most one slot.
+        if let Some(level) = levels.iter_mut().find(|l| l.price == update.price && l.price != 0) {
+            *level = if update.quantity > 0 {
+                PriceLevel { price: update.price, quantity: update.quantity }
+            } else {
+                PriceLevel::default()
+            };
+            // Invalidate cache when updating existing level
+            self.invalidate_cache(update.side);
+            return;
+        }
+        if update.quantity == 0 {
+            return;
+        }
+        if let Some(level) = levels.iter_mut().find(|l| l.price == 0) { // Empty slot
+            level.price = update.price;
+            level.quantity = update.quantity;
+            // Invalidate cache when adding new level
+            self.invalidate_cache(update.side);
         }
     }
 
@@ -124,6 +149,41 @@ impl OrderBook {
         }
     }
     
+    /// Best non-empty level strictly behind `after` (the best level when `None`).
+    /// Lets callers walk the book level by level without sorting or allocating.
+    pub fn next_level(&self, side: Side, after: Option<i64>) -> Option<PriceLevel> {
+        let levels = match side {
+            Side::Buy => &self.bids,
+            Side::Sell => &self.asks,
+        };
+        let behind = |price: i64| match (side, after) {
+            (_, None) => true,
+            (Side::Buy, Some(after)) => price < after,
+            (Side::Sell, Some(after)) => price > after,
+        };
+        levels
+            .iter()
+            .filter(|l| l.quantity > 0 && behind(l.price))
+            .fold(None, |best: Option<PriceLevel>, l| match (side, best) {
+                (_, None) => Some(*l),
+                (Side::Buy, Some(b)) if l.price > b.price => Some(*l),
+                (Side::Sell, Some(b)) if l.price < b.price => Some(*l),
+                _ => best,
+            })
+    }
+
+    /// Resting quantity at an exact price level, 0 if the level is empty or unknown
+    pub fn quantity_at(&self, side: Side, price: i64) -> u64 {
+        let levels = match side {
+            Side::Buy => &self.bids,
+            Side::Sell => &self.asks,
+        };
+        levels
+            .iter()
+            .find(|l| l.price == price)
+            .map_or(0, |l| l.quantity)
+    }
+
     pub fn total_volume(&self, side: Side) -> u64 {
         let levels = match side {
             Side::Buy => &self.bids,
@@ -139,6 +199,12 @@ pub struct GlobalOrderBook {
     pub coinbase: OrderBook,
 }
 
+impl Default for GlobalOrderBook {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl GlobalOrderBook {
     pub fn new() -> Self {
         Self {
@@ -149,45 +215,38 @@ impl GlobalOrderBook {
     }
 
     pub fn on_update(&mut self, update: &MarketDataUpdate) {
-        match update.exchange_id {
-            ExchangeID::Binance => self.binance.apply_update(update),
-            ExchangeID::Bybit => self.bybit.apply_update(update),
-            ExchangeID::Coinbase => self.coinbase.apply_update(update),
-        }
+        self.book_mut(update.exchange_id).apply_update(update);
     }
 
-    // NOBI Calculation: Normalized Order Book Imbalance
-    // Formula: (Vol_Bid - Vol_Ask) / (Vol_Bid + Vol_Ask)
-    // Weighted by exchange volume (simplified here with equal weights or static weights)
-    pub fn calculate_nobi(&self) -> f64 {
-        let w_binance = 0.6;
-        let w_bybit = 0.3;
-        let w_coinbase = 0.1;
-
-        let b_bid = self.binance.total_volume(Side::Buy) as f64;
-        let b_ask = self.binance.total_volume(Side::Sell) as f64;
-        
-        let by_bid = self.bybit.total_volume(Side::Buy) as f64;
-        let by_ask = self.bybit.total_volume(Side::Sell) as f64;
-
-        let c_bid = self.coinbase.total_volume(Side::Buy) as f64;
-        let c_ask = self.coinbase.total_volume(Side::Sell) as f64;
-
-        let weighted_net_flow = w_binance * (b_bid - b_ask) + w_bybit * (by_bid - by_ask) + w_coinbase * (c_bid - c_ask);
-        let weighted_total_depth = w_binance * (b_bid + b_ask) + w_bybit * (by_bid + by_ask) + w_coinbase * (c_bid + c_ask);
+    pub fn book(&self, exchange_id: ExchangeID) -> &OrderBook {
+        match exchange_id {
+            ExchangeID::Binance => &self.binance,
+            ExchangeID::Bybit => &self.bybit,
+            ExchangeID::Coinbase => &self.coinbase,
+        }
+    }
 
-        if weighted_total_depth == 0.0 {
-            0.0
-        } else {
-            weighted_net_flow / weighted_total_depth
+    pub fn book_mut(&mut self, exchange_id: ExchangeID) -> &mut OrderBook {
+        match exchange_id {
+            ExchangeID::Binance => &mut self.binance,
+            ExchangeID::Bybit => &mut self.bybit,
+            ExchangeID::Coinbase => &mut self.coinbase,
         }
     }
+
+    // NOBI Calculation: Normalized Order Book Imbalance
+    // Formula: sum w (Vol_Bid - Vol_Ask) / sum w (Vol_Bid + Vol_Ask)
+    // Depth is taken inside `band` of each venue's book; `weights` are per
+    // venue in ExchangeID order, e.g. 24h market shares from `nobi::VolumeShare`
+    pub fn calculate_nobi(&self, band: DepthBand, weights: &[f64; VENUES]) -> f64 {
+        let depths = [&self.binance, &self.bybit, &self.coinbase].map(|book| band.depth(book));
+        weighted_nobi(&depths, weights)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use core::time::Duration;
 
     #[test]
     fn test_order_book_update_benchmark() {
@@ -203,7 +262,7 @@ mod tests {
         };
 
         let iterations = 1_000_000;
-        let start = core::time::Instant::now();
+        let start = std::time::Instant::now();
         
         for _ in 0..iterations {
             book.on_update(&update);
@@ -223,4 +282,60 @@ mod tests {
         // For HFT, we target < 1 microsecond (1000ns) easily.
         assert!(nanos_per_op < 10000.0, "Update too slow! Expected <10000ns, got {:.2}ns", nanos_per_op);
     }
+
+    #[test]
+    fn test_next_level_walks_from_the_touch() {
+        let mut book = OrderBook::new();
+        for (price, quantity) in [(101, 1), (103, 2), (102, 0), (104, 3)] {
+            book.apply_update(&MarketDataUpdate {
+                timestamp: 1,
+                exchange_id: ExchangeID::Binance,
+                symbol_id: 1,
+                side: Side::Sell,
+                price,
+                quantity,
+                is_snapshot: 0,
+            });
+        }
+
+        let mut prices = [0; 3];
+        let mut after = None;
+        for price in prices.iter_mut() {
+            let level = book.next_level(Side::Sell, after).unwrap();
+            *price = level.price;
+            after = Some(level.price);
+        }
+        assert_eq!(prices, [101, 103, 104]);
+        assert!(book.next_level(Side::Sell, after).is_none());
+        assert!(book.next_level(Side::Buy, None).is_none());
+    }
+
+    #[test]
+    fn test_removed_levels_free_their_slot() {
+        fn update(book: &mut OrderBook, price: i64, quantity: u64) {
+            book.apply_update(&MarketDataUpdate {
+                timestamp: 1,
+                exchange_id: ExchangeID::Binance,
+                symbol_id: 1,
+                side: Side::Buy,
+                price,
+                quantity,
+                is_snapshot: 0,
+            })
+        }
+
+        let mut book = OrderBook::new();
+        for price in 1..=MAX_PRICE_LEVELS as i64 {
+            update(&mut book, price, 1);
+        }
+        // Full: a removal makes room for a new price
+        update(&mut book, 5, 0);
+        update(&mut book, 2_000, 7);
+        assert_eq!(book.quantity_at(Side::Buy, 5), 0);
+        assert_eq!(book.best_bid().map(|l| (l.price, l.quantity)), Some((2_000, 7)));
+
+        // Removing an unknown price takes no slot
+        update(&mut book, 3_000, 0);
+        assert_eq!(book.bids.iter().filter(|l| l.price == 3_000).count(), 0);
+    }
 }
@@ -0,0 +1,350 @@
+use vibe_hft_core::{Price, Quantity, Timestamp};
+use vibe_hft_sbe_messages::{ExchangeID, LiquidationLevel, Side, Trade};
+
+/// Price buckets per side of the grid
+pub const LIQUIDATION_BUCKETS: usize = 1024;
+/// Leverage scenarios new positions are spread over
+pub const LEVERAGE_TIERS: usize = 4;
+
+/// Intensities below this (1e8 quantity) are dropped after decay
+const MIN_INTENSITY: f64 = 1_000.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LiquidationConfig {
+    /// Width of one grid bucket (1e8)
+    pub bucket_size: Price,
+    pub leverage: [u32; LEVERAGE_TIERS],
+    /// Share of new positions assumed at each leverage
+    pub leverage_weights: [f64; LEVERAGE_TIERS],
+    /// Time for an estimated cluster to lose half its intensity
+    pub half_life: u64,
+    /// Time between decay steps, each of which republishes the grid
+    pub decay_interval: u64,
+}
+
+impl Default for LiquidationConfig {
+    /// 25 USDT buckets, 10/25/50/100x, 7-day half-life applied every minute (ns)
+    fn default() -> Self {
+        Self {
+            bucket_size: 2_500_000_000,
+            leverage: [10, 25, 50, 100],
+            leverage_weights: [0.4, 0.3, 0.2, 0.1],
+            half_life: 7 * 86_400_000_000_000,
+            decay_interval: 60_000_000_000,
+        }
+    }
+}
+
+/// `e^x` without std: halve into Taylor range, then square back
+fn exp(x: f64) -> f64 {
+    let (mut y, mut halvings) = (x, 0);
+    while !(-1e-3..=1e-3).contains(&y) {
+        y /= 2.0;
+        halvings += 1;
+    }
+    let mut result = 1.0 + y + y * y / 2.0 + y * y * y / 6.0 + y * y * y * y / 24.0;
+    for _ in 0..halvings {
+        result *= result;
+    }
+    result
+}
+
+/// Trades since the previous open-interest reading
+#[derive(Debug, Clone, Copy, Default)]
+struct Interval {
+    notional: f64,
+    volume: Quantity,
+    buy_volume: Quantity,
+}
+
+/// Liquidation heatmap estimated from open interest, for one venue or
+/// across venues.
+///
+/// Exchanges do not publish where positions get liquidated, so each rise in
+/// open interest is taken as new longs and shorts opened at the VWAP of the
+/// trades since the previous reading, split by aggressor side. Their
+/// bankruptcy prices at every leverage tier, `VWAP * (1 -+ 1/L)`, collect
+/// the new size in a fixed grid of price buckets. A fall in open interest
+/// closes the same fraction everywhere, trading through a bucket clears it,
+/// and everything halves every `half_life`. Changed buckets are handed to a
+/// callback, so nothing allocates.
+#[derive(Debug, Clone)]
+pub struct LiquidationEstimator {
+    config: LiquidationConfig,
+    exchange_id: Option<ExchangeID>,
+    symbol_id: u32,
+    /// Multiplier applied every `decay_interval`
+    decay: f64,
+    /// Lower bound of bucket 0, set around the first projected entry
+    origin: Option<Price>,
+    longs: [f64; LIQUIDATION_BUCKETS],
+    shorts: [f64; LIQUIDATION_BUCKETS],
+    interval: Interval,
+    last_vwap: Option<Price>,
+    last_open_interest: Option<Quantity>,
+    next_decay: Option<Timestamp>,
+}
+
+impl LiquidationEstimator {
+    pub fn new(exchange_id: Option<ExchangeID>, symbol_id: u32, config: LiquidationConfig) -> Self {
+        assert!(config.bucket_size > 0 && config.half_life > 0);
+        Self {
+            config,
+            exchange_id,
+            symbol_id,
+            decay: exp(-core::f64::consts::LN_2 * config.decay_interval as f64 / config.half_life as f64),
+            origin: None,
+            longs: [0.0; LIQUIDATION_BUCKETS],
+            shorts: [0.0; LIQUIDATION_BUCKETS],
+            interval: Interval::default(),
+            last_vwap: None,
+            last_open_interest: None,
+            next_decay: None,
+        }
+    }
+
+    pub fn config(&self) -> &LiquidationConfig {
+        &self.config
+    }
+
+    /// Add a trade to the VWAP of the current interval and clear the buckets
+    /// it traded through: longs at or above its price, shorts at or below.
+    pub fn on_trade(&mut self, trade: &Trade, mut emit: impl FnMut(&LiquidationLevel)) {
+        if trade.symbol_id != self.symbol_id || self.exchange_id.is_some_and(|id| id != trade.exchange_id) {
+            return;
+        }
+        self.interval.notional += trade.price as f64 * trade.quantity as f64;
+        self.interval.volume += trade.quantity;
+        if trade.aggressor_side == Side::Buy {
+            self.interval.buy_volume += trade.quantity;
+        }
+
+        let Some(origin) = self.origin else { return };
+        let bucket = (trade.price - origin).div_euclid(self.config.bucket_size);
+        let from = bucket.clamp(0, LIQUIDATION_BUCKETS as i64) as usize;
+        let to = (bucket + 1).clamp(0, LIQUIDATION_BUCKETS as i64) as usize;
+        for i in from..LIQUIDATION_BUCKETS {
+            self.set(trade.timestamp, Side::Buy, i, 0.0, &mut emit);
+        }
+        for i in 0..to {
+            self.set(trade.timestamp, Side::Sell, i, 0.0, &mut emit);
+        }
+    }
+
+    /// New open-interest reading (quantity, 1e8). The change since the
+    /// previous reading is projected at the interval's VWAP.
+    pub fn on_open_interest(&mut self, now: Timestamp, open_interest: Quantity, mut emit: impl FnMut(&LiquidationLevel)) {
+        let interval = core::mem::take(&mut self.interval);
+        if interval.volume > 0 {
+            self.last_vwap = Some((interval.notional / interval.volume as f64) as Price);
+        }
+        let Some(previous) = self.last_open_interest.replace(open_interest) else { return };
+        let Some(vwap) = self.last_vwap else { return };
+
+        if open_interest < previous {
+            // Closed positions, wherever they were
+            let kept = open_interest as f64 / previous as f64;
+            self.scale(now, kept, &mut emit);
+            return;
+        }
+        let opened = (open_interest - previous) as f64;
+        if opened == 0.0 {
+            return;
+        }
+        let long_share = if interval.volume > 0 { interval.buy_volume as f64 / interval.volume as f64 } else { 0.5 };
+
+        let origin = self.recenter(now, vwap, &mut emit);
+        for (leverage, weight) in self.config.leverage.into_iter().zip(self.config.leverage_weights) {
+            let size = opened * weight;
+            let offset = vwap as f64 / leverage as f64;
+            for (side, price, share) in
+                [(Side::Buy, vwap as f64 - offset, long_share), (Side::Sell, vwap as f64 + offset, 1.0 - long_share)]
+            {
+                let bucket = (price as Price - origin).div_euclid(self.config.bucket_size);
+                if (0..LIQUIDATION_BUCKETS as i64).contains(&bucket) && share > 0.0 {
+                    let i = bucket as usize;
+                    let intensity = self.grid(side)[i] + size * share;
+                    self.set(now, side, i, intensity, &mut emit);
+                }
+            }
+        }
+    }
+
+    /// Apply the decay once per `decay_interval`, republishing every
+    /// non-empty bucket. Call from a timer.
+    pub fn on_timer(&mut self, now: Timestamp, mut emit: impl FnMut(&LiquidationLevel)) {
+        let next = *self.next_decay.get_or_insert(now + self.config.decay_interval);
+        if now < next {
+            return;
+        }
+        self.next_decay = Some(now + self.config.decay_interval);
+        self.scale(now, self.decay, &mut emit);
+    }
+
+    /// Estimated size in the bucket holding `price`, 0 off the grid
+    pub fn intensity(&self, side: Side, price: Price) -> f64 {
+        self.bucket(price).map_or(0.0, |i| self.grid_ref(side)[i])
+    }
+
+    /// Non-empty buckets of a side as (lower bound, intensity)
+    pub fn levels(&self, side: Side) -> impl Iterator<Item = (Price, f64)> + '_ {
+        let origin = self.origin.unwrap_or(0);
+        self.grid_ref(side)
+            .iter()
+            .enumerate()
+            .filter(|(_, &v)| v > 0.0)
+            .map(move |(i, &v)| (origin + i as Price * self.config.bucket_size, v))
+    }
+
+    fn bucket(&self, price: Price) -> Option<usize> {
+        let bucket = (price - self.origin?).div_euclid(self.config.bucket_size);
+        (0..LIQUIDATION_BUCKETS as i64).contains(&bucket).then_some(bucket as usize)
+    }
+
+    /// Keep `price` away from the edges of the grid, dropping what falls off
+    fn recenter(&mut self, now: Timestamp, price: Price, emit: &mut impl FnMut(&LiquidationLevel)) -> Price {
+        let size = self.config.bucket_size;
+        let centered = price.div_euclid(size) * size - (LIQUIDATION_BUCKETS / 2) as Price * size;
+        let Some(origin) = self.origin else {
+            self.origin = Some(centered);
+            return centered;
+        };
+        let bucket = (price - origin).div_euclid(size);
+        let margin = (LIQUIDATION_BUCKETS / 8) as i64;
+        if bucket >= margin && bucket < LIQUIDATION_BUCKETS as i64 - margin {
+            return origin;
+        }
+
+        let shift = (centered - origin) / size;
+        for side in [Side::Buy, Side::Sell] {
+            let mut moved = [0.0; LIQUIDATION_BUCKETS];
+            for i in 0..LIQUIDATION_BUCKETS {
+                let to = i as i64 - shift;
+                if (0..LIQUIDATION_BUCKETS as i64).contains(&to) {
+                    moved[to as usize] = self.grid(side)[i];
+                } else {
+                    self.set(now, side, i, 0.0, emit);
+                }
+            }
+            *self.grid(side) = moved;
+        }
+        self.origin = Some(centered);
+        centered
+    }
+
+    fn scale(&mut self, now: Timestamp, factor: f64, emit: &mut impl FnMut(&LiquidationLevel)) {
+        for side in [Side::Buy, Side::Sell] {
+            for i in 0..LIQUIDATION_BUCKETS {
+                let intensity = self.grid(side)[i];
+                if intensity > 0.0 {
+                    let scaled = intensity * factor;
+                    self.set(now, side, i, if scaled < MIN_INTENSITY { 0.0 } else { scaled }, emit);
+                }
+            }
+        }
+    }
+
+    /// Store a bucket and publish it if it changed
+    fn set(&mut self, now: Timestamp, side: Side, i: usize, intensity: f64, emit: &mut impl FnMut(&LiquidationLevel)) {
+        let cell = &mut self.grid(side)[i];
+        if *cell == intensity {
+            return;
+        }
+        *cell = intensity;
+        emit(&LiquidationLevel {
+            timestamp: now,
+            exchange_id: self.exchange_id,
+            symbol_id: self.symbol_id,
+            side,
+            price: self.origin.unwrap_or(0) + i as Price * self.config.bucket_size,
+            bucket_size: self.config.bucket_size,
+            intensity: intensity as u64,
+        });
+    }
+
+    fn grid(&mut self, side: Side) -> &mut [f64; LIQUIDATION_BUCKETS] {
+        match side {
+            Side::Buy => &mut self.longs,
+            Side::Sell => &mut self.shorts,
+        }
+    }
+
+    fn grid_ref(&self, side: Side) -> &[f64; LIQUIDATION_BUCKETS] {
+        match side {
+            Side::Buy => &self.longs,
+            Side::Sell => &self.shorts,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    const CONFIG: LiquidationConfig = LiquidationConfig {
+        bucket_size: 10,
+        leverage: [10, 25, 50, 100],
+        leverage_weights: [0.4, 0.3, 0.2, 0.1],
+        half_life: 1_000,
+        decay_interval: 500,
+    };
+
+    fn trade(timestamp: u64, aggressor_side: Side, price: Price, quantity: Quantity) -> Trade {
+        Trade { timestamp, exchange_id: ExchangeID::Binance, symbol_id: 1, trade_id: timestamp, aggressor_side, price, quantity }
+    }
+
+    #[test]
+    fn test_projects_new_interest_at_each_leverage() {
+        let mut estimator = LiquidationEstimator::new(None, 1, CONFIG);
+        let mut levels = Vec::new();
+        estimator.on_open_interest(0, 1_000_000_000, |l| levels.push(*l));
+
+        // VWAP 10000 with 3/4 of the volume bought
+        estimator.on_trade(&trade(1, Side::Buy, 9_900, 300), |l| levels.push(*l));
+        estimator.on_trade(&trade(2, Side::Sell, 10_300, 100), |l| levels.push(*l));
+        estimator.on_open_interest(10, 1_800_000_000, |l| levels.push(*l));
+        assert_eq!(levels.len(), 8);
+
+        // 10x: longs at 9000 get 8 * 0.4 * 0.75, shorts at 11000 the rest
+        assert_eq!(levels[0].intensity, 240_000_000);
+        assert_eq!(estimator.intensity(Side::Buy, 9_000), 240_000_000.0);
+        assert_eq!(estimator.intensity(Side::Sell, 11_000), 80_000_000.0);
+        // 100x: 9900 and 10100
+        assert_eq!(estimator.intensity(Side::Buy, 9_905), 60_000_000.0);
+        assert_eq!(estimator.intensity(Side::Sell, 10_100), 20_000_000.0);
+        assert!(levels.iter().all(|l| l.bucket_size == 10 && l.price % 10 == 0));
+
+        // Half the interest closes: everything halves
+        estimator.on_open_interest(20, 900_000_000, |_| {});
+        assert_eq!(estimator.intensity(Side::Buy, 9_000), 120_000_000.0);
+    }
+
+    #[test]
+    fn test_trading_through_clears_and_time_decays() {
+        let mut estimator = LiquidationEstimator::new(Some(ExchangeID::Binance), 1, CONFIG);
+        estimator.on_open_interest(0, 0, |_| {});
+        estimator.on_trade(&trade(1, Side::Buy, 10_000, 1), |_| {});
+        estimator.on_trade(&trade(2, Side::Sell, 10_000, 1), |_| {});
+        estimator.on_open_interest(10, 4_000_000, |_| {});
+
+        // Price falls to 9850: the 50x and 100x longs (9800 and 9900) are
+        // liquidated only if reached, so only 9900 clears
+        let mut cleared = Vec::new();
+        estimator.on_trade(&trade(20, Side::Sell, 9_850, 1), |l| cleared.push(*l));
+        assert_eq!(cleared.len(), 1);
+        assert_eq!((cleared[0].side, cleared[0].price, cleared[0].intensity), (Side::Buy, 9_900, 0));
+        assert_eq!(estimator.intensity(Side::Buy, 9_800), 400_000.0);
+
+        // One half-life is two decay steps
+        let mut refreshed = Vec::new();
+        estimator.on_timer(100, |l| refreshed.push(*l));
+        assert!(refreshed.is_empty());
+        estimator.on_timer(600, |l| refreshed.push(*l));
+        estimator.on_timer(1_100, |l| refreshed.push(*l));
+        assert_eq!(refreshed.len(), 14);
+        assert!((estimator.intensity(Side::Buy, 9_800) - 200_000.0).abs() < 1.0);
+        assert!((estimator.intensity(Side::Sell, 11_000) - 400_000.0).abs() < 1.0);
+        assert_eq!(estimator.levels(Side::Sell).count(), 4);
+    }
+}
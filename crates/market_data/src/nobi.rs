@@ -0,0 +1,366 @@
+use vibe_hft_core::Timestamp;
+use vibe_hft_sbe_messages::{ExchangeID, IndicatorKind, IndicatorUpdate, Side, Trade};
+
+use crate::ofi::RollingWindow;
+use crate::{venue_index, GlobalOrderBook, OrderBook, PriceLevel, VENUES};
+
+/// Buckets the 24h volume window is cut into
+pub const VOLUME_BUCKETS: usize = 288;
+
+/// Weights used until any venue has traded
+pub const EQUAL_WEIGHTS: [f64; VENUES] = [1.0 / 3.0; VENUES];
+
+/// Resolution of NOBI inside the time-weighting window (1e6 per unit), low
+/// enough that value times duration cannot overflow
+const NOBI_SCALE: f64 = 1_000_000.0;
+
+/// Part of the book counted as depth
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DepthBand {
+    /// The best `n` levels of each side
+    Levels(usize),
+    /// Levels within this many basis points of the venue's mid
+    Bps(u32),
+}
+
+impl DepthBand {
+    /// Bid and ask depth inside the band, `None` while a side is empty
+    pub fn depth(&self, book: &OrderBook) -> Option<(u64, u64)> {
+        let best_bid = book.next_level(Side::Buy, None)?;
+        let best_ask = book.next_level(Side::Sell, None)?;
+        match *self {
+            DepthBand::Levels(n) => {
+                let side_depth = |side: Side, best: PriceLevel| {
+                    let mut level = Some(best);
+                    let mut depth = 0;
+                    for _ in 0..n {
+                        let Some(l) = level else { break };
+                        depth += l.quantity;
+                        level = book.next_level(side, Some(l.price));
+                    }
+                    depth
+                };
+                Some((side_depth(Side::Buy, best_bid), side_depth(Side::Sell, best_ask)))
+            }
+            DepthBand::Bps(bps) => {
+                let mid = (best_bid.price as i128 + best_ask.price as i128) / 2;
+                let offset = mid * bps as i128 / 10_000;
+                let (floor, ceiling) = ((mid - offset) as i64, (mid + offset) as i64);
+                let bid = book.bids.iter().filter(|l| l.quantity > 0 && l.price >= floor).map(|l| l.quantity).sum();
+                let ask = book.asks.iter().filter(|l| l.quantity > 0 && l.price <= ceiling).map(|l| l.quantity).sum();
+                Some((bid, ask))
+            }
+        }
+    }
+}
+
+/// `sum w (bid - ask) / sum w (bid + ask)` over the venues with a book;
+/// 0 when there is no depth at all
+pub fn weighted_nobi(depths: &[Option<(u64, u64)>; VENUES], weights: &[f64; VENUES]) -> f64 {
+    let (mut net, mut total) = (0.0, 0.0);
+    for ((bid, ask), w) in depths.iter().zip(weights).filter_map(|(d, w)| Some(((*d)?, w))) {
+        net += w * (bid as f64 - ask as f64);
+        total += w * (bid as f64 + ask as f64);
+    }
+    if total > 0.0 {
+        net / total
+    } else {
+        0.0
+    }
+}
+
+/// Traded quantity per venue over a rolling window, for market-share weights.
+///
+/// The window is cut into `VOLUME_BUCKETS` fixed buckets: whole buckets
+/// leave the window at once, so a share is at most one bucket stale and
+/// updates are O(1) without storing trades.
+#[derive(Debug, Clone)]
+pub struct VolumeShare {
+    bucket_length: u64,
+    buckets: [[u64; VENUES]; VOLUME_BUCKETS],
+    /// Absolute index (time / bucket_length) of the newest bucket
+    current: u64,
+    totals: [u64; VENUES],
+}
+
+impl VolumeShare {
+    /// `window` is in the units of the trade timestamps, e.g. 24h
+    pub fn new(window: u64) -> Self {
+        assert!(window >= VOLUME_BUCKETS as u64);
+        Self {
+            bucket_length: window / VOLUME_BUCKETS as u64,
+            buckets: [[0; VENUES]; VOLUME_BUCKETS],
+            current: 0,
+            totals: [0; VENUES],
+        }
+    }
+
+    pub fn on_trade(&mut self, trade: &Trade) {
+        self.advance(trade.timestamp);
+        let venue = venue_index(trade.exchange_id);
+        self.buckets[(self.current % VOLUME_BUCKETS as u64) as usize][venue] += trade.quantity;
+        self.totals[venue] += trade.quantity;
+    }
+
+    /// Drop buckets that have left the window by `now`
+    pub fn advance(&mut self, now: Timestamp) {
+        let bucket = now / self.bucket_length;
+        if bucket <= self.current {
+            return;
+        }
+        if bucket - self.current >= VOLUME_BUCKETS as u64 {
+            self.buckets = [[0; VENUES]; VOLUME_BUCKETS];
+            self.totals = [0; VENUES];
+        } else {
+            for b in self.current + 1..=bucket {
+                let slot = &mut self.buckets[(b % VOLUME_BUCKETS as u64) as usize];
+                for (total, expired) in self.totals.iter_mut().zip(slot.iter()) {
+                    *total -= expired;
+                }
+                *slot = [0; VENUES];
+            }
+        }
+        self.current = bucket;
+    }
+
+    /// Volume traded on a venue within the window
+    pub fn volume(&self, exchange_id: ExchangeID) -> u64 {
+        self.totals[venue_index(exchange_id)]
+    }
+
+    /// Each venue's share of the volume in the window, `None` before any trade
+    pub fn weights(&self) -> Option<[f64; VENUES]> {
+        let total: u64 = self.totals.iter().sum();
+        (total > 0).then(|| self.totals.map(|v| v as f64 / total as f64))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NobiConfig {
+    pub band: DepthBand,
+    /// Span of the trade volume behind the venue weights
+    pub volume_window: u64,
+    /// Time between samples
+    pub interval: u64,
+    /// Span the published value is time-weighted over; at most one
+    /// `interval` publishes the latest sample as is
+    pub smoothing: u64,
+    /// Venue weights until any venue has traded
+    pub fallback_weights: [f64; VENUES],
+}
+
+impl Default for NobiConfig {
+    /// Top 20 levels, 24h weights, sampled every 100ms and averaged over 1s (ns)
+    fn default() -> Self {
+        Self {
+            band: DepthBand::Levels(20),
+            volume_window: 86_400_000_000_000,
+            interval: 100_000_000,
+            smoothing: 1_000_000_000,
+            fallback_weights: EQUAL_WEIGHTS,
+        }
+    }
+}
+
+/// Normalized order book imbalance per venue and across venues for one
+/// symbol.
+///
+/// Every `interval` the depth inside the band of each venue's book is
+/// sampled. A venue's NOBI is its own imbalance; the global one weights
+/// each venue by its share of traded volume over `volume_window`. Each
+/// sample counts for the time since the previous one and the published
+/// value is the time-weighted mean over `smoothing`.
+#[derive(Debug, Clone)]
+pub struct NobiEngine {
+    config: NobiConfig,
+    symbol_id: u32,
+    volumes: VolumeShare,
+    last_sample: Option<Timestamp>,
+    /// Per series: sum of value (1e6) times duration, and of durations
+    venues: [RollingWindow<2>; VENUES],
+    global: RollingWindow<2>,
+}
+
+impl NobiEngine {
+    pub fn new(symbol_id: u32, config: NobiConfig) -> Self {
+        assert!(config.interval > 0);
+        let window = RollingWindow::new(config.smoothing.max(config.interval));
+        Self {
+            config,
+            symbol_id,
+            volumes: VolumeShare::new(config.volume_window),
+            last_sample: None,
+            venues: [window.clone(), window.clone(), window.clone()],
+            global: window,
+        }
+    }
+
+    pub fn config(&self) -> &NobiConfig {
+        &self.config
+    }
+
+    /// Count a trade towards its venue's market share
+    pub fn on_trade(&mut self, trade: &Trade) {
+        if trade.symbol_id == self.symbol_id {
+            self.volumes.on_trade(trade);
+        }
+    }
+
+    /// Sample the books once `interval` has passed since the last sample.
+    /// `emit` receives the NOBI of every venue with a two-sided book, then
+    /// the global one.
+    pub fn on_timer(&mut self, now: Timestamp, books: &GlobalOrderBook, mut emit: impl FnMut(&IndicatorUpdate)) {
+        if self.last_sample.is_some_and(|last| now < last + self.config.interval) {
+            return;
+        }
+        // A sample stands for at most one smoothing window, e.g. after a gap
+        let dt = self.last_sample.map_or(self.config.interval, |last| now - last).min(self.venues[0].duration()) as i64;
+        self.last_sample = Some(now);
+
+        let ids = [ExchangeID::Binance, ExchangeID::Bybit, ExchangeID::Coinbase];
+        let depths = ids.map(|id| self.config.band.depth(books.book(id)));
+        for (i, id) in ids.into_iter().enumerate() {
+            let Some(depth) = depths[i] else { continue };
+            let mut only = [None; VENUES];
+            only[i] = Some(depth);
+            let nobi = weighted_nobi(&only, &EQUAL_WEIGHTS);
+            let value = Self::sample(&mut self.venues[i], now, dt, nobi);
+            emit(&self.indicator(now, Some(id), value));
+        }
+        if depths.iter().any(Option::is_some) {
+            let nobi = books.calculate_nobi(self.config.band, &self.weights(now));
+            let value = Self::sample(&mut self.global, now, dt, nobi);
+            emit(&self.indicator(now, None, value));
+        }
+    }
+
+    /// Venue weights in force at `now`
+    pub fn weights(&mut self, now: Timestamp) -> [f64; VENUES] {
+        self.volumes.advance(now);
+        self.volumes.weights().unwrap_or(self.config.fallback_weights)
+    }
+
+    /// Time-weighted NOBI of a venue, or across venues for `None`
+    pub fn nobi(&self, exchange_id: Option<ExchangeID>) -> f64 {
+        Self::mean(exchange_id.map_or(&self.global, |id| &self.venues[venue_index(id)]))
+    }
+
+    /// Forget the samples, keeping the volume shares
+    pub fn reset(&mut self) {
+        self.venues.iter_mut().for_each(RollingWindow::clear);
+        self.global.clear();
+        self.last_sample = None;
+    }
+
+    fn sample(window: &mut RollingWindow<2>, now: Timestamp, dt: i64, nobi: f64) -> f64 {
+        window.push(now, [(nobi * NOBI_SCALE) as i64 * dt, dt]);
+        Self::mean(window)
+    }
+
+    fn mean(window: &RollingWindow<2>) -> f64 {
+        let [weighted, time] = window.sum();
+        if time > 0 {
+            weighted as f64 / time as f64 / NOBI_SCALE
+        } else {
+            0.0
+        }
+    }
+
+    fn indicator(&self, now: Timestamp, exchange_id: Option<ExchangeID>, nobi: f64) -> IndicatorUpdate {
+        IndicatorUpdate {
+            timestamp: now,
+            exchange_id,
+            symbol_id: self.symbol_id,
+            kind: IndicatorKind::Nobi,
+            window: self.config.smoothing,
+            value: (nobi * 100_000_000.0) as i64,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+    use vibe_hft_sbe_messages::MarketDataUpdate;
+
+    fn level(books: &mut GlobalOrderBook, exchange_id: ExchangeID, side: Side, price: i64, quantity: u64) {
+        books.on_update(&MarketDataUpdate { timestamp: 0, exchange_id, symbol_id: 1, side, price, quantity, is_snapshot: 0 });
+    }
+
+    fn trade(timestamp: u64, exchange_id: ExchangeID, quantity: u64) -> Trade {
+        Trade { timestamp, exchange_id, symbol_id: 1, trade_id: timestamp, aggressor_side: Side::Buy, price: 100, quantity }
+    }
+
+    #[test]
+    fn test_depth_bands() {
+        let mut books = GlobalOrderBook::new();
+        for (price, quantity) in [(9_990, 1), (9_900, 2), (9_000, 4)] {
+            level(&mut books, ExchangeID::Binance, Side::Buy, price, quantity);
+        }
+        for (price, quantity) in [(10_010, 8), (10_100, 16), (11_000, 32)] {
+            level(&mut books, ExchangeID::Binance, Side::Sell, price, quantity);
+        }
+        let book = books.book(ExchangeID::Binance);
+        assert_eq!(DepthBand::Levels(2).depth(book), Some((3, 24)));
+        assert_eq!(DepthBand::Levels(10).depth(book), Some((7, 56)));
+        // Mid 10000: 100 bps reaches 9900 and 10100
+        assert_eq!(DepthBand::Bps(100).depth(book), Some((3, 24)));
+        assert_eq!(DepthBand::Bps(10).depth(book), Some((1, 8)));
+        assert_eq!(DepthBand::Levels(5).depth(books.book(ExchangeID::Bybit)), None);
+    }
+
+    #[test]
+    fn test_volume_share_rolls_over_the_window() {
+        let mut volumes = VolumeShare::new(VOLUME_BUCKETS as u64 * 10);
+        assert_eq!(volumes.weights(), None);
+        volumes.on_trade(&trade(5, ExchangeID::Binance, 30));
+        volumes.on_trade(&trade(500, ExchangeID::Bybit, 10));
+        assert_eq!(volumes.weights(), Some([0.75, 0.25, 0.0]));
+
+        // Bucket 0 leaves once the window has moved a full length past it
+        volumes.advance(VOLUME_BUCKETS as u64 * 10);
+        assert_eq!(volumes.weights(), Some([0.0, 1.0, 0.0]));
+        volumes.advance(VOLUME_BUCKETS as u64 * 100);
+        assert_eq!(volumes.weights(), None);
+    }
+
+    #[test]
+    fn test_engine_weights_by_volume_and_time() {
+        let config = NobiConfig {
+            band: DepthBand::Levels(5),
+            volume_window: VOLUME_BUCKETS as u64 * 1_000,
+            interval: 100,
+            smoothing: 400,
+            fallback_weights: EQUAL_WEIGHTS,
+        };
+        let mut engine = NobiEngine::new(1, config);
+        let mut books = GlobalOrderBook::new();
+        // Binance all bids, Bybit all asks
+        level(&mut books, ExchangeID::Binance, Side::Buy, 99, 10);
+        level(&mut books, ExchangeID::Binance, Side::Sell, 101, 0);
+        level(&mut books, ExchangeID::Bybit, Side::Buy, 99, 5);
+        level(&mut books, ExchangeID::Bybit, Side::Sell, 101, 15);
+        level(&mut books, ExchangeID::Binance, Side::Sell, 102, 10);
+
+        let mut updates = Vec::new();
+        engine.on_timer(0, &books, |u| updates.push(*u));
+        assert_eq!(updates.len(), 3);
+        assert_eq!(updates[0].kind, IndicatorKind::Nobi);
+        // Equal weights: (10 - 10 + 5 - 15) / 40
+        assert_eq!(updates[2].exchange_id, None);
+        assert_eq!(updates[2].value, -25_000_000);
+
+        // Binance did 3/4 of the volume: 0.75 * 0 + 0.25 * -10 over 0.75 * 20 + 0.25 * 20
+        engine.on_trade(&trade(10, ExchangeID::Binance, 3));
+        engine.on_trade(&trade(20, ExchangeID::Bybit, 1));
+        updates.clear();
+        engine.on_timer(50, &books, |u| updates.push(*u));
+        assert!(updates.is_empty());
+        engine.on_timer(100, &books, |u| updates.push(*u));
+        assert_eq!(engine.weights(100), [0.75, 0.25, 0.0]);
+        // Time-weighted: two samples of 100 each, -0.25 and -0.125
+        assert!((engine.nobi(None) + 0.1875).abs() < 1e-9);
+        assert!((engine.nobi(Some(ExchangeID::Bybit)) + 0.5).abs() < 1e-9);
+    }
+}
@@ -1,123 +1,432 @@
-extern crate alloc;
-use alloc::collections::VecDeque;
+use vibe_hft_core::Timestamp;
+use vibe_hft_sbe_messages::{ExchangeID, IndicatorKind, IndicatorUpdate, Side};
 
-/// Order Flow Imbalance (OFI) Calculator
-/// Measures the net pressure on the order book by tracking changes
-/// in bid and ask volumes at the best price levels
+use crate::{venue_index, OrderBook, PriceLevel, VENUES};
+
+/// Horizons an `OfiEngine` publishes
+pub const OFI_HORIZONS: usize = 3;
+/// Events a window holds before the oldest are dropped early
+pub const OFI_WINDOW_CAPACITY: usize = 1024;
+/// Deepest level a `MultiLevelOfi` tracks
+pub const OFI_MAX_LEVELS: usize = 10;
+
+/// Running sum of timestamped values over a trailing time window.
+///
+/// Entries live in a fixed ring: a push adds to the sum and every expiry
+/// subtracts from it, so both are O(1) and nothing touches the heap. Values
+/// are integer (1e8) so the running sum never drifts. When more than
+/// `OFI_WINDOW_CAPACITY` events fall inside the window the oldest are
+/// dropped early, shortening the window for that burst.
 #[derive(Debug, Clone)]
-pub struct OFICalculator {
-    /// Window size for OFI calculation (number of events)
-    window_size: usize,
-    /// History of bid events
-    bid_events: VecDeque<f64>,
-    /// History of ask events
-    ask_events: VecDeque<f64>,
-    /// Last known best bid price and quantity
-    last_bid: Option<(i64, u64)>,
-    /// Last known best ask price and quantity
-    last_ask: Option<(i64, u64)>,
+pub struct RollingWindow<const N: usize> {
+    duration: u64,
+    entries: [(Timestamp, [i64; N]); OFI_WINDOW_CAPACITY],
+    /// Index of the oldest entry
+    head: usize,
+    len: usize,
+    sum: [i64; N],
 }
 
-impl OFICalculator {
-    pub fn new(window_size: usize) -> Self {
-        Self {
-            window_size,
-            bid_events: VecDeque::with_capacity(window_size),
-            ask_events: VecDeque::with_capacity(window_size),
-            last_bid: None,
-            last_ask: None,
-        }
+impl<const N: usize> RollingWindow<N> {
+    /// `duration` is in the units of the event timestamps
+    pub fn new(duration: u64) -> Self {
+        Self { duration, entries: [(0, [0; N]); OFI_WINDOW_CAPACITY], head: 0, len: 0, sum: [0; N] }
     }
 
-    /// Update the OFI with a new order book snapshot
-    /// Returns the current OFI value
-    pub fn update(&mut self, bid_price: i64, bid_qty: u64, ask_price: i64, ask_qty: u64) -> f64 {
-        // Calculate bid event
-        let bid_event = if let Some((last_price, last_qty)) = self.last_bid {
-            if bid_price > last_price {
-                // Price improvement (bid moved up) - strong buy pressure
-                bid_qty as f64
-            } else if bid_price < last_price {
-                // Price deterioration (bid moved down) - weak buy pressure
-                -(last_qty as f64)
-            } else {
-                // Same price, volume change
-                (bid_qty as f64) - (last_qty as f64)
-            }
-        } else {
-            0.0
-        };
+    pub fn duration(&self) -> u64 {
+        self.duration
+    }
 
-        // Calculate ask event
-        let ask_event = if let Some((last_price, last_qty)) = self.last_ask {
-            if ask_price < last_price {
-                // Price improvement (ask moved down) - strong sell pressure
-                ask_qty as f64
-            } else if ask_price > last_price {
-                // Price deterioration (ask moved up) - weak sell pressure
-                -(last_qty as f64)
-            } else {
-                // Same price, volume change
-                (ask_qty as f64) - (last_qty as f64)
-            }
-        } else {
-            0.0
-        };
+    pub fn len(&self) -> usize {
+        self.len
+    }
 
-        // Store events
-        self.bid_events.push_back(bid_event);
-        self.ask_events.push_back(ask_event);
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
 
-        // Maintain window size
-        if self.bid_events.len() > self.window_size {
-            self.bid_events.pop_front();
+    pub fn push(&mut self, now: Timestamp, values: [i64; N]) {
+        self.expire(now);
+        if self.len == OFI_WINDOW_CAPACITY {
+            self.pop();
         }
-        if self.ask_events.len() > self.window_size {
-            self.ask_events.pop_front();
+        self.entries[(self.head + self.len) % OFI_WINDOW_CAPACITY] = (now, values);
+        self.len += 1;
+        for (sum, value) in self.sum.iter_mut().zip(values) {
+            *sum += value;
         }
+    }
+
+    /// Drop entries that are `duration` or more older than `now`
+    pub fn expire(&mut self, now: Timestamp) {
+        while self.len > 0 && now.saturating_sub(self.entries[self.head].0) >= self.duration {
+            self.pop();
+        }
+    }
+
+    pub fn sum(&self) -> [i64; N] {
+        self.sum
+    }
+
+    pub fn clear(&mut self) {
+        self.head = 0;
+        self.len = 0;
+        self.sum = [0; N];
+    }
+
+    fn pop(&mut self) {
+        let (_, values) = self.entries[self.head];
+        for (sum, value) in self.sum.iter_mut().zip(values) {
+            *sum -= value;
+        }
+        self.head = (self.head + 1) % OFI_WINDOW_CAPACITY;
+        self.len -= 1;
+    }
+}
+
+/// Bid-side flow between two snapshots of the same level: a higher bid adds
+/// its whole size, a lower bid removes the old size, otherwise the size change
+fn bid_event(prev: PriceLevel, level: PriceLevel) -> i64 {
+    if level.price > prev.price {
+        level.quantity as i64
+    } else if level.price < prev.price {
+        -(prev.quantity as i64)
+    } else {
+        level.quantity as i64 - prev.quantity as i64
+    }
+}
+
+/// Ask-side counterpart of `bid_event`, positive when selling pressure grows
+fn ask_event(prev: PriceLevel, level: PriceLevel) -> i64 {
+    if level.price < prev.price {
+        level.quantity as i64
+    } else if level.price > prev.price {
+        -(prev.quantity as i64)
+    } else {
+        level.quantity as i64 - prev.quantity as i64
+    }
+}
+
+/// Order Flow Imbalance (OFI) Calculator
+/// Measures the net pressure on the order book by tracking changes
+/// in bid and ask volumes at the best price levels over a trailing time
+/// window (e.g. 1s, 10s or 60s; one calculator per horizon)
+#[derive(Debug, Clone)]
+pub struct OFICalculator {
+    window: RollingWindow<1>,
+    /// Last known best bid and ask
+    last: Option<(PriceLevel, PriceLevel)>,
+}
 
-        // Update last known values
-        self.last_bid = Some((bid_price, bid_qty));
-        self.last_ask = Some((ask_price, ask_qty));
+impl OFICalculator {
+    /// `window` is in the units of the timestamps passed to `update`
+    pub fn new(window: u64) -> Self {
+        Self { window: RollingWindow::new(window), last: None }
+    }
 
-        // Calculate OFI
-        self.calculate_ofi()
+    /// Update the OFI with a new best bid and ask
+    /// Returns the current OFI value
+    pub fn update(&mut self, now: Timestamp, bid_price: i64, bid_qty: u64, ask_price: i64, ask_qty: u64) -> f64 {
+        let bid = PriceLevel { price: bid_price, quantity: bid_qty };
+        let ask = PriceLevel { price: ask_price, quantity: ask_qty };
+        let event = self.last.map_or(0, |(last_bid, last_ask)| bid_event(last_bid, bid) - ask_event(last_ask, ask));
+        self.window.push(now, [event]);
+        self.last = Some((bid, ask));
+        self.get_ofi()
     }
 
-    /// Calculate the current OFI value
-    /// OFI = sum(bid_events) - sum(ask_events)
-    fn calculate_ofi(&self) -> f64 {
-        let bid_sum: f64 = self.bid_events.iter().sum();
-        let ask_sum: f64 = self.ask_events.iter().sum();
-        bid_sum - ask_sum
+    /// Drop transitions that have left the window without a new update, e.g. from a timer
+    pub fn expire(&mut self, now: Timestamp) -> f64 {
+        self.window.expire(now);
+        self.get_ofi()
     }
 
     /// Calculate Normalized Order Book Imbalance (NOBI)
     /// NOBI = OFI / (total_bid_depth + total_ask_depth)
     /// Returns a value between -1 and 1
     pub fn calculate_nobi(&self, total_bid_depth: u64, total_ask_depth: u64) -> f64 {
-        let ofi = self.calculate_ofi();
         let total_depth = (total_bid_depth + total_ask_depth) as f64;
-        
         if total_depth > 0.0 {
-            ofi / total_depth
+            self.get_ofi() / total_depth
         } else {
             0.0
         }
     }
 
     /// Get the current OFI value
+    /// OFI = sum(bid_events) - sum(ask_events) over the window
     pub fn get_ofi(&self) -> f64 {
-        self.calculate_ofi()
+        self.window.sum()[0] as f64
+    }
+
+    pub fn window(&self) -> u64 {
+        self.window.duration()
     }
 
     /// Reset the calculator
     pub fn reset(&mut self) {
-        self.bid_events.clear();
-        self.ask_events.clear();
-        self.last_bid = None;
-        self.last_ask = None;
+        self.window.clear();
+        self.last = None;
+    }
+}
+
+/// Multi-level OFI over the top `levels` price levels (Cont, Cucuringu &
+/// Zhang). Level `m` applies the best-level events to the m-th best bid and
+/// ask, so flow behind the touch counts too. A level missing from the book
+/// is taken as empty at the far end of its side.
+///
+/// Per-level sums are kept separately; `integrated` collapses them with
+/// caller-supplied weights (e.g. the first principal component).
+#[derive(Debug, Clone)]
+pub struct MultiLevelOfi {
+    levels: usize,
+    window: RollingWindow<OFI_MAX_LEVELS>,
+    last: Option<[(PriceLevel, PriceLevel); OFI_MAX_LEVELS]>,
+}
+
+const NO_BID: PriceLevel = PriceLevel { price: 0, quantity: 0 };
+const NO_ASK: PriceLevel = PriceLevel { price: i64::MAX, quantity: 0 };
+
+impl MultiLevelOfi {
+    pub fn new(levels: usize, window: u64) -> Self {
+        assert!((1..=OFI_MAX_LEVELS).contains(&levels));
+        Self { levels, window: RollingWindow::new(window), last: None }
+    }
+
+    pub fn levels(&self) -> usize {
+        self.levels
+    }
+
+    /// Update from the top levels of a book
+    pub fn update_from_book(&mut self, now: Timestamp, book: &OrderBook) {
+        let mut top = [(NO_BID, NO_ASK); OFI_MAX_LEVELS];
+        let mut bid = book.next_level(Side::Buy, None);
+        let mut ask = book.next_level(Side::Sell, None);
+        for slot in top.iter_mut().take(self.levels) {
+            *slot = (bid.unwrap_or(NO_BID), ask.unwrap_or(NO_ASK));
+            bid = bid.and_then(|l| book.next_level(Side::Buy, Some(l.price)));
+            ask = ask.and_then(|l| book.next_level(Side::Sell, Some(l.price)));
+        }
+        self.update(now, &top);
+    }
+
+    /// Update from `(bid, ask)` pairs, best first; levels past `levels()` are ignored
+    pub fn update(&mut self, now: Timestamp, top: &[(PriceLevel, PriceLevel)]) {
+        let mut current = [(NO_BID, NO_ASK); OFI_MAX_LEVELS];
+        for (slot, level) in current.iter_mut().zip(top).take(self.levels) {
+            *slot = *level;
+        }
+        let mut events = [0; OFI_MAX_LEVELS];
+        if let Some(last) = &self.last {
+            for m in 0..self.levels {
+                let ((prev_bid, prev_ask), (bid, ask)) = (last[m], current[m]);
+                events[m] = bid_event(prev_bid, bid) - ask_event(prev_ask, ask);
+            }
+        }
+        self.window.push(now, events);
+        self.last = Some(current);
+    }
+
+    pub fn expire(&mut self, now: Timestamp) {
+        self.window.expire(now);
+    }
+
+    /// OFI of level `m` (0 is the touch) over the window
+    pub fn level(&self, m: usize) -> f64 {
+        self.window.sum()[m] as f64
+    }
+
+    /// Weighted sum of the per-level OFIs; missing weights count as zero
+    pub fn integrated(&self, weights: &[f64]) -> f64 {
+        let sum = self.window.sum();
+        sum.iter().zip(weights).take(self.levels).map(|(&ofi, w)| ofi as f64 * w).sum()
+    }
+
+    /// Plain sum across the tracked levels
+    pub fn total(&self) -> f64 {
+        self.window.sum().iter().take(self.levels).map(|&ofi| ofi as f64).sum()
+    }
+
+    pub fn reset(&mut self) {
+        self.window.clear();
+        self.last = None;
+    }
+}
+
+/// Windows of the published OFI series, in the units of the feed timestamps
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OfiConfig {
+    pub windows: [u64; OFI_HORIZONS],
+}
+
+impl Default for OfiConfig {
+    /// 1s, 10s and 60s (ns)
+    fn default() -> Self {
+        Self { windows: [1_000_000_000, 10_000_000_000, 60_000_000_000] }
+    }
+}
+
+/// Best bid and ask of one venue, or the consolidated touch across venues
+type Touch = (PriceLevel, PriceLevel);
+
+#[derive(Debug, Clone)]
+struct OfiSeries {
+    horizons: [OFICalculator; OFI_HORIZONS],
+    /// Values last handed out, so timers only publish what expiry changed
+    published: [i64; OFI_HORIZONS],
+}
+
+impl OfiSeries {
+    fn new(config: &OfiConfig) -> Self {
+        Self { horizons: config.windows.map(OFICalculator::new), published: [0; OFI_HORIZONS] }
+    }
+}
+
+/// Best-level OFI per venue and across venues for one symbol, over each
+/// configured horizon.
+///
+/// Venues report their touch after every book change; only actual
+/// transitions feed the calculators. The global series runs on the
+/// consolidated touch: the best price across venues with the size of every
+/// venue quoting it. State is fixed-size and updates are handed to a
+/// callback, so nothing allocates per update.
+#[derive(Debug, Clone)]
+pub struct OfiEngine {
+    config: OfiConfig,
+    symbol_id: u32,
+    touches: [Option<Touch>; VENUES],
+    global_touch: Option<Touch>,
+    venues: [OfiSeries; VENUES],
+    global: OfiSeries,
+}
+
+impl OfiEngine {
+    pub fn new(symbol_id: u32, config: OfiConfig) -> Self {
+        let series = OfiSeries::new(&config);
+        Self {
+            config,
+            symbol_id,
+            touches: [None; VENUES],
+            global_touch: None,
+            venues: [series.clone(), series.clone(), series.clone()],
+            global: series,
+        }
+    }
+
+    pub fn config(&self) -> &OfiConfig {
+        &self.config
+    }
+
+    /// Read the touch of a venue's book after an update. A book missing
+    /// either side is skipped.
+    pub fn on_book(&mut self, now: Timestamp, exchange_id: ExchangeID, book: &OrderBook, emit: impl FnMut(&IndicatorUpdate)) {
+        if let (Some(bid), Some(ask)) = (book.next_level(Side::Buy, None), book.next_level(Side::Sell, None)) {
+            self.on_touch(now, exchange_id, bid, ask, emit);
+        }
+    }
+
+    /// Record a venue's best bid and ask. When they changed, `emit` receives
+    /// the venue's OFI for every horizon, then the global ones if the
+    /// consolidated touch moved too.
+    pub fn on_touch(
+        &mut self,
+        now: Timestamp,
+        exchange_id: ExchangeID,
+        bid: PriceLevel,
+        ask: PriceLevel,
+        mut emit: impl FnMut(&IndicatorUpdate),
+    ) {
+        let venue = venue_index(exchange_id);
+        let touch = (bid, ask);
+        if self.touches[venue].is_some_and(|t| same_touch(t, touch)) {
+            return;
+        }
+        self.touches[venue] = Some(touch);
+        self.update(now, Some(exchange_id), touch, &mut emit);
+
+        let Some(global) = self.consolidated() else {
+            return;
+        };
+        if self.global_touch.is_some_and(|t| same_touch(t, global)) {
+            return;
+        }
+        self.global_touch = Some(global);
+        self.update(now, None, global, &mut emit);
+    }
+
+    /// Expire transitions that have left their window. `emit` receives the
+    /// series whose value changed. Call from a timer.
+    pub fn on_timer(&mut self, now: Timestamp, mut emit: impl FnMut(&IndicatorUpdate)) {
+        for exchange_id in [None, Some(ExchangeID::Binance), Some(ExchangeID::Bybit), Some(ExchangeID::Coinbase)] {
+            for h in 0..OFI_HORIZONS {
+                let series = self.series_mut(exchange_id);
+                let value = series.horizons[h].expire(now) as i64;
+                if value != series.published[h] {
+                    series.published[h] = value;
+                    emit(&self.indicator(now, exchange_id, h, value));
+                }
+            }
+        }
+    }
+
+    /// OFI of a venue, or across venues for `None`, over horizon `h`
+    pub fn ofi(&self, exchange_id: Option<ExchangeID>, h: usize) -> f64 {
+        let series = exchange_id.map_or(&self.global, |id| &self.venues[venue_index(id)]);
+        series.horizons[h].get_ofi()
+    }
+
+    /// Forget all transitions, e.g. after a feed gap
+    pub fn reset(&mut self) {
+        *self = Self::new(self.symbol_id, self.config);
     }
+
+    fn update(&mut self, now: Timestamp, exchange_id: Option<ExchangeID>, touch: Touch, emit: &mut impl FnMut(&IndicatorUpdate)) {
+        let (bid, ask) = touch;
+        for h in 0..OFI_HORIZONS {
+            let series = self.series_mut(exchange_id);
+            let value = series.horizons[h].update(now, bid.price, bid.quantity, ask.price, ask.quantity) as i64;
+            series.published[h] = value;
+            emit(&self.indicator(now, exchange_id, h, value));
+        }
+    }
+
+    /// Best bid and ask across venues, each with the size of every venue at that price
+    fn consolidated(&self) -> Option<Touch> {
+        let mut bid: Option<PriceLevel> = None;
+        let mut ask: Option<PriceLevel> = None;
+        for &(b, a) in self.touches.iter().flatten() {
+            bid = Some(match bid {
+                Some(best) if best.price > b.price => best,
+                Some(best) if best.price == b.price => PriceLevel { price: b.price, quantity: best.quantity + b.quantity },
+                _ => b,
+            });
+            ask = Some(match ask {
+                Some(best) if best.price < a.price => best,
+                Some(best) if best.price == a.price => PriceLevel { price: a.price, quantity: best.quantity + a.quantity },
+                _ => a,
+            });
+        }
+        bid.zip(ask)
+    }
+
+    fn series_mut(&mut self, exchange_id: Option<ExchangeID>) -> &mut OfiSeries {
+        exchange_id.map_or(&mut self.global, |id| &mut self.venues[venue_index(id)])
+    }
+
+    fn indicator(&self, now: Timestamp, exchange_id: Option<ExchangeID>, h: usize, value: i64) -> IndicatorUpdate {
+        IndicatorUpdate {
+            timestamp: now,
+            exchange_id,
+            symbol_id: self.symbol_id,
+            kind: IndicatorKind::Ofi,
+            window: self.config.windows[h],
+            value,
+        }
+    }
+}
+
+fn same_touch(a: Touch, b: Touch) -> bool {
+    (a.0.price, a.0.quantity, a.1.price, a.1.quantity) == (b.0.price, b.0.quantity, b.1.price, b.1.quantity)
 }
 
 #[cfg(test)]
@@ -127,35 +436,123 @@ mod tests {
     #[test]
     fn test_ofi_price_improvement() {
         let mut ofi = OFICalculator::new(10);
-        
+
         // Initial state
-        ofi.update(100, 10, 101, 10);
-        
+        ofi.update(0, 100, 10, 101, 10);
+
         // Bid price improves (moves up) - buy pressure
-        let result = ofi.update(101, 15, 102, 10);
+        let result = ofi.update(1, 101, 15, 102, 10);
         assert!(result > 0.0, "OFI should be positive with bid improvement");
     }
 
     #[test]
     fn test_ofi_price_deterioration() {
         let mut ofi = OFICalculator::new(10);
-        
+
         // Initial state
-        ofi.update(100, 10, 101, 10);
-        
+        ofi.update(0, 100, 10, 101, 10);
+
         // Bid price deteriorates (moves down) - sell pressure
-        let result = ofi.update(99, 10, 100, 10);
+        let result = ofi.update(1, 99, 10, 100, 10);
         assert!(result < 0.0, "OFI should be negative with bid deterioration");
     }
 
     #[test]
     fn test_nobi_normalization() {
         let mut ofi = OFICalculator::new(10);
-        
-        ofi.update(100, 10, 101, 10);
-        ofi.update(101, 15, 102, 10);
-        
+
+        ofi.update(0, 100, 10, 101, 10);
+        ofi.update(1, 101, 15, 102, 10);
+
         let nobi = ofi.calculate_nobi(100, 100);
-        assert!(nobi >= -1.0 && nobi <= 1.0, "NOBI should be between -1 and 1");
+        assert!((-1.0..=1.0).contains(&nobi), "NOBI should be between -1 and 1");
+    }
+
+    #[test]
+    fn test_time_window_expires_old_flow() {
+        let mut ofi = OFICalculator::new(1_000);
+        ofi.update(0, 100, 10, 101, 10);
+        assert_eq!(ofi.update(100, 100, 15, 101, 10), 5.0);
+        assert_eq!(ofi.update(600, 100, 15, 101, 12), 3.0);
+        // The +5 at t=100 leaves the window at t=1100
+        assert_eq!(ofi.update(1_100, 100, 15, 101, 12), -2.0);
+        assert_eq!(ofi.expire(1_600), 0.0);
+    }
+
+    #[test]
+    fn test_full_ring_drops_oldest() {
+        let mut window = RollingWindow::<1>::new(u64::MAX);
+        for t in 0..OFI_WINDOW_CAPACITY as u64 + 10 {
+            window.push(t, [1]);
+        }
+        assert_eq!(window.len(), OFI_WINDOW_CAPACITY);
+        assert_eq!(window.sum(), [OFI_WINDOW_CAPACITY as i64]);
+    }
+
+    #[test]
+    fn test_multi_level_sees_flow_behind_the_touch() {
+        let level = |price, quantity| PriceLevel { price, quantity };
+        let mut ofi = MultiLevelOfi::new(2, 1_000);
+        ofi.update(0, &[(level(100, 10), level(101, 10)), (level(99, 20), level(102, 20))]);
+        // Touch unchanged, bids refilled and asks pulled one level behind
+        ofi.update(1, &[(level(100, 10), level(101, 10)), (level(99, 35), level(102, 5))]);
+        assert_eq!((ofi.level(0), ofi.level(1)), (0.0, 30.0));
+        assert_eq!(ofi.total(), 30.0);
+        assert_eq!(ofi.integrated(&[1.0, 0.5]), 15.0);
+
+        let mut book = OrderBook::new();
+        let mut ofi = MultiLevelOfi::new(3, 1_000);
+        ofi.update_from_book(0, &book);
+        for (side, price, quantity) in [(Side::Buy, 100, 10), (Side::Buy, 99, 20), (Side::Sell, 101, 5)] {
+            book.apply_update(&vibe_hft_sbe_messages::MarketDataUpdate {
+                timestamp: 1,
+                exchange_id: vibe_hft_sbe_messages::ExchangeID::Binance,
+                symbol_id: 1,
+                side,
+                price,
+                quantity,
+                is_snapshot: 0,
+            });
+        }
+        ofi.update_from_book(1, &book);
+        // New levels appearing count as added size; the missing third level stays flat
+        assert_eq!((ofi.level(0), ofi.level(1), ofi.level(2)), (5.0, 20.0, 0.0));
+    }
+
+    #[test]
+    fn test_engine_publishes_venue_and_global_transitions() {
+        let level = |price, quantity| PriceLevel { price, quantity };
+        let config = OfiConfig { windows: [10, 100, 1_000] };
+        let mut engine = OfiEngine::new(1, config);
+        let mut updates = alloc::vec::Vec::new();
+
+        engine.on_touch(0, ExchangeID::Binance, level(100, 10), level(101, 10), |u| updates.push(*u));
+        engine.on_touch(0, ExchangeID::Bybit, level(100, 5), level(102, 5), |u| updates.push(*u));
+        assert_eq!(updates.len(), 12);
+        updates.clear();
+
+        // Repeating a touch is not a transition
+        engine.on_touch(1, ExchangeID::Binance, level(100, 10), level(101, 10), |u| updates.push(*u));
+        assert!(updates.is_empty());
+
+        // Bybit adds 5 at the shared best bid: both series see +5, on top of
+        // the 5 Bybit brought to the consolidated bid when it first quoted
+        engine.on_touch(5, ExchangeID::Bybit, level(100, 10), level(102, 5), |u| updates.push(*u));
+        assert_eq!(updates.len(), 6);
+        assert!(updates.iter().all(|u| u.kind == IndicatorKind::Ofi));
+        assert_eq!((updates[0].exchange_id, updates[0].value), (Some(ExchangeID::Bybit), 5));
+        assert_eq!((updates[3].exchange_id, updates[3].value), (None, 10));
+        assert_eq!((engine.ofi(Some(ExchangeID::Bybit), 0), engine.ofi(None, 2)), (5.0, 10.0));
+
+        // Behind the consolidated ask: only the venue moves
+        updates.clear();
+        engine.on_touch(6, ExchangeID::Bybit, level(100, 10), level(102, 8), |u| updates.push(*u));
+        assert_eq!(updates.len(), 3);
+
+        // The shortest window expires on the timer
+        updates.clear();
+        engine.on_timer(16, |u| updates.push(*u));
+        assert_eq!(updates.len(), 2);
+        assert!(updates.iter().all(|u| u.window == 10 && u.value == 0));
     }
 }
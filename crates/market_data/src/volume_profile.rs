@@ -0,0 +1,531 @@
+use vibe_hft_core::{Price, Quantity, Timestamp};
+use vibe_hft_sbe_messages::{ExchangeID, ProfileSpan, Trade, VolumeNode, VolumeProfileLevel, VolumeProfileSummary};
+
+/// Price buckets of the profile grid
+pub const PROFILE_BUCKETS: usize = 256;
+/// Time slices of history kept for rolling and visible-range profiles
+pub const PROFILE_SLICES: usize = 48;
+
+/// Time span of a `VolumeProfile`, in the units of the trade timestamps.
+/// Volume is kept per `slice`, so spans are rounded out to whole slices and
+/// reach back at most `PROFILE_SLICES` of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProfileWindow {
+    /// Since the start of the current session
+    Session,
+    /// The trailing `window`
+    Rolling(u64),
+    /// `[from, to)`, e.g. the visible part of the chart
+    VisibleRange { from: Timestamp, to: Timestamp },
+}
+
+impl ProfileWindow {
+    pub fn span(&self) -> ProfileSpan {
+        match self {
+            ProfileWindow::Session => ProfileSpan::Session,
+            ProfileWindow::Rolling(_) => ProfileSpan::Rolling,
+            ProfileWindow::VisibleRange { .. } => ProfileSpan::VisibleRange,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VolumeProfileConfig {
+    /// Width of one price bucket (1e8)
+    pub bucket_size: Price,
+    /// Time granularity of the history; `PROFILE_SLICES` of them are kept
+    pub slice: u64,
+    /// Session profiles restart at every session boundary
+    pub session_length: u64,
+    pub session_offset: u64,
+    /// Share of the volume in the value area around the POC
+    pub value_area: f64,
+    /// Buckets averaged on each side before looking for nodes
+    pub smoothing: usize,
+    /// HVNs are peaks at or above this multiple of the mean bucket volume
+    pub hvn_ratio: f64,
+    /// LVNs are troughs at or below this multiple of the mean bucket volume
+    pub lvn_ratio: f64,
+    /// Time between published updates
+    pub interval: u64,
+}
+
+impl Default for VolumeProfileConfig {
+    /// 25 USDT buckets, 30-minute slices (24h of history), daily sessions
+    /// from UTC midnight, 70% value area, published every second (ns)
+    fn default() -> Self {
+        Self {
+            bucket_size: 2_500_000_000,
+            slice: 1_800_000_000_000,
+            session_length: 86_400_000_000_000,
+            session_offset: 0,
+            value_area: 0.7,
+            smoothing: 2,
+            hvn_ratio: 1.5,
+            lvn_ratio: 0.5,
+            interval: 1_000_000_000,
+        }
+    }
+}
+
+/// Point of control and value area of a profile, as bucket indices
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProfileStats {
+    pub poc: usize,
+    /// Lowest and highest bucket of the value area, inclusive
+    pub value_area: (usize, usize),
+    pub total: Quantity,
+}
+
+/// Traded range of a profile: first and last non-empty bucket
+fn traded_range(volume: &[Quantity]) -> Option<(usize, usize)> {
+    let first = volume.iter().position(|&v| v > 0)?;
+    let last = volume.iter().rposition(|&v| v > 0)?;
+    Some((first, last))
+}
+
+/// POC and value area of `volume`, `None` when empty.
+///
+/// The POC is the busiest bucket, ties going to the one nearest the middle
+/// of the traded range. The value area grows from it one bucket at a time
+/// towards the busier neighbour, the upper one on ties, until it holds
+/// `share` of the volume.
+pub fn profile_stats(volume: &[Quantity], share: f64) -> Option<ProfileStats> {
+    let (first, last) = traded_range(volume)?;
+    let total: Quantity = volume[first..=last].iter().sum();
+    let middle = first + last;
+    let poc = (first..=last)
+        .max_by(|&a, &b| volume[a].cmp(&volume[b]).then((2 * b).abs_diff(middle).cmp(&(2 * a).abs_diff(middle))))?;
+
+    let target = total as f64 * share;
+    let (mut low, mut high, mut inside) = (poc, poc, volume[poc]);
+    while (inside as f64) < target {
+        let up = (high < last).then(|| volume[high + 1]);
+        let down = (low > first).then(|| volume[low - 1]);
+        match (up, down) {
+            (Some(up), Some(down)) if down > up => {
+                low -= 1;
+                inside += down;
+            }
+            (Some(up), _) => {
+                high += 1;
+                inside += up;
+            }
+            (None, Some(down)) => {
+                low -= 1;
+                inside += down;
+            }
+            (None, None) => break,
+        }
+    }
+    Some(ProfileStats { poc, value_area: (low, high), total })
+}
+
+/// Flag the high- and low-volume nodes of `volume` into `nodes`.
+///
+/// Each bucket is averaged with `smoothing` neighbours on each side, within
+/// the traded range, so single-bucket noise does not make nodes. HVNs are
+/// local maxima at or above `hvn_ratio` times the mean bucket volume, LVNs
+/// local minima inside the range at or below `lvn_ratio` times it.
+pub fn volume_nodes(volume: &[Quantity], smoothing: usize, hvn_ratio: f64, lvn_ratio: f64, nodes: &mut [VolumeNode]) {
+    nodes.fill(VolumeNode::None);
+    let Some((first, last)) = traded_range(volume) else { return };
+    let total: Quantity = volume[first..=last].iter().sum();
+    let mean = total as f64 / (last - first + 1) as f64;
+    let smoothed = |i: usize| {
+        let (from, to) = (i.saturating_sub(smoothing).max(first), (i + smoothing).min(last));
+        volume[from..=to].iter().sum::<Quantity>() as f64 / (to - from + 1) as f64
+    };
+
+    for (i, node) in nodes.iter_mut().enumerate().take(last + 1).skip(first) {
+        let here = smoothed(i);
+        let below = (i > first).then(|| smoothed(i - 1));
+        let above = (i < last).then(|| smoothed(i + 1));
+        // Strict on one side only, so a plateau gets a single node
+        let peak = below.is_none_or(|b| here > b) && above.is_none_or(|a| here >= a);
+        let trough = matches!((below, above), (Some(b), Some(a)) if here < b && here <= a);
+        *node = if peak && here >= hvn_ratio * mean {
+            VolumeNode::High
+        } else if trough && here <= lvn_ratio * mean {
+            VolumeNode::Low
+        } else {
+            VolumeNode::None
+        };
+    }
+}
+
+/// A change to publish: a bucket, or the POC and value area
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProfileUpdate {
+    Level(VolumeProfileLevel),
+    Summary(VolumeProfileSummary),
+}
+
+/// Composite volume profile (VPVR) of one symbol, for one venue or across
+/// venues, over a session, a rolling window or a visible range.
+///
+/// Trade volume is bucketed by price on a fixed grid that follows the
+/// market, with one grid per time slice so the window can move. Every
+/// `interval` the profile of the window is rebuilt from the slices and
+/// compared with what was last published: only buckets whose volume or
+/// node changed go out, followed by the summary when the POC, value area or
+/// total moved. Nothing allocates.
+#[derive(Debug, Clone)]
+pub struct VolumeProfile {
+    config: VolumeProfileConfig,
+    exchange_id: Option<ExchangeID>,
+    symbol_id: u32,
+    window: ProfileWindow,
+    /// Lower bound of bucket 0, set around the first trade
+    origin: Option<Price>,
+    /// Volume per bucket of each slice, with the slice number it holds
+    slices: [(Option<u64>, [Quantity; PROFILE_BUCKETS]); PROFILE_SLICES],
+    /// Latest time seen, from trades or the timer
+    now: Timestamp,
+    next_publish: Option<Timestamp>,
+    /// What clients were last sent, on the grid at `published_origin`
+    published: [(Quantity, VolumeNode); PROFILE_BUCKETS],
+    published_origin: Option<Price>,
+    summary: Option<VolumeProfileSummary>,
+}
+
+impl VolumeProfile {
+    pub fn new(
+        exchange_id: Option<ExchangeID>,
+        symbol_id: u32,
+        window: ProfileWindow,
+        config: VolumeProfileConfig,
+    ) -> Self {
+        assert!(config.bucket_size > 0 && config.slice > 0);
+        assert!(config.session_length.is_multiple_of(config.slice) && config.session_offset.is_multiple_of(config.slice));
+        assert!(config.session_length <= PROFILE_SLICES as u64 * config.slice);
+        let mut profile = Self {
+            config,
+            exchange_id,
+            symbol_id,
+            window: ProfileWindow::Session,
+            origin: None,
+            slices: [(None, [0; PROFILE_BUCKETS]); PROFILE_SLICES],
+            now: 0,
+            next_publish: None,
+            published: [(0, VolumeNode::None); PROFILE_BUCKETS],
+            published_origin: None,
+            summary: None,
+        };
+        profile.set_window(window);
+        profile
+    }
+
+    pub fn config(&self) -> &VolumeProfileConfig {
+        &self.config
+    }
+
+    pub fn window(&self) -> ProfileWindow {
+        self.window
+    }
+
+    /// Change the span, e.g. when the chart scrolls. The next publish sends
+    /// the difference. Panics on a rolling window longer than the history.
+    pub fn set_window(&mut self, window: ProfileWindow) {
+        if let ProfileWindow::Rolling(length) = window {
+            assert!(length / self.config.slice < PROFILE_SLICES as u64, "rolling window longer than the history");
+        }
+        self.window = window;
+        self.next_publish = None;
+    }
+
+    pub fn on_trade(&mut self, trade: &Trade) {
+        if trade.symbol_id != self.symbol_id || self.exchange_id.is_some_and(|id| id != trade.exchange_id) {
+            return;
+        }
+        self.now = self.now.max(trade.timestamp);
+        let id = trade.timestamp / self.config.slice;
+        let (held, _) = self.slices[(id % PROFILE_SLICES as u64) as usize];
+        if held.is_some_and(|held| held > id) || self.now / self.config.slice >= id + PROFILE_SLICES as u64 {
+            // Older than the history
+            return;
+        }
+
+        let bucket = self.recenter(trade.price);
+        let (held, volume) = &mut self.slices[(id % PROFILE_SLICES as u64) as usize];
+        if *held != Some(id) {
+            *held = Some(id);
+            volume.fill(0);
+        }
+        volume[bucket] += trade.quantity;
+    }
+
+    /// Publish what changed, once per `interval`. Call from a timer; the
+    /// clock also moves a rolling window on when no trades come in.
+    pub fn on_timer(&mut self, now: Timestamp, mut emit: impl FnMut(&ProfileUpdate)) {
+        self.now = self.now.max(now);
+        let next = *self.next_publish.get_or_insert(self.now);
+        if self.now < next {
+            return;
+        }
+        self.next_publish = Some(self.now + self.config.interval);
+        self.publish(&mut emit);
+    }
+
+    /// Volume of the current window in the bucket holding `price`
+    pub fn volume(&self, price: Price) -> Quantity {
+        self.bucket(price).map_or(0, |i| self.profile()[i])
+    }
+
+    /// POC and value area of the current window
+    pub fn stats(&self) -> Option<ProfileStats> {
+        profile_stats(&self.profile(), self.config.value_area)
+    }
+
+    /// Lower bound of bucket `i`
+    pub fn price(&self, i: usize) -> Price {
+        self.origin.unwrap_or(0) + i as Price * self.config.bucket_size
+    }
+
+    pub fn reset(&mut self) {
+        self.slices = [(None, [0; PROFILE_BUCKETS]); PROFILE_SLICES];
+        self.next_publish = None;
+    }
+
+    fn bucket(&self, price: Price) -> Option<usize> {
+        let bucket = (price - self.origin?).div_euclid(self.config.bucket_size);
+        (0..PROFILE_BUCKETS as i64).contains(&bucket).then_some(bucket as usize)
+    }
+
+    /// Start of the current window, rounded down to a slice, and its end:
+    /// now for sessions and rolling windows, exclusive for a visible range
+    fn span(&self) -> (Timestamp, Timestamp) {
+        let slice = self.config.slice;
+        match self.window {
+            ProfileWindow::Session => {
+                let start = self.now - self.now.saturating_sub(self.config.session_offset) % self.config.session_length;
+                (start, self.now)
+            }
+            ProfileWindow::Rolling(length) => (self.now.saturating_sub(length) / slice * slice, self.now),
+            ProfileWindow::VisibleRange { from, to } => (from / slice * slice, to),
+        }
+    }
+
+    /// Volume per bucket over the current window
+    fn profile(&self) -> [Quantity; PROFILE_BUCKETS] {
+        let (start, end) = self.span();
+        let last = match self.window {
+            ProfileWindow::VisibleRange { .. } => end.saturating_sub(1),
+            // Up to and including now
+            _ => end,
+        };
+        let (first, last) = (start / self.config.slice, last / self.config.slice);
+        let mut profile = [0; PROFILE_BUCKETS];
+        for (held, volume) in &self.slices {
+            if held.is_some_and(|id| id >= first && id <= last) {
+                for (total, v) in profile.iter_mut().zip(volume) {
+                    *total += v;
+                }
+            }
+        }
+        profile
+    }
+
+    /// Bucket of `price`, moving the grid first if it is near an edge.
+    /// Volume that falls off the far side is dropped.
+    fn recenter(&mut self, price: Price) -> usize {
+        let size = self.config.bucket_size;
+        let centered = price.div_euclid(size) * size - (PROFILE_BUCKETS / 2) as Price * size;
+        let origin = *self.origin.get_or_insert(centered);
+        let bucket = (price - origin).div_euclid(size);
+        let margin = (PROFILE_BUCKETS / 8) as i64;
+        if bucket >= margin && bucket < PROFILE_BUCKETS as i64 - margin {
+            return bucket as usize;
+        }
+
+        let shift = (centered - origin) / size;
+        for (_, volume) in self.slices.iter_mut() {
+            let mut moved = [0; PROFILE_BUCKETS];
+            for (i, &v) in volume.iter().enumerate() {
+                let to = i as i64 - shift;
+                if (0..PROFILE_BUCKETS as i64).contains(&to) {
+                    moved[to as usize] = v;
+                }
+            }
+            *volume = moved;
+        }
+        self.origin = Some(centered);
+        (price - centered).div_euclid(size) as usize
+    }
+
+    fn publish(&mut self, emit: &mut impl FnMut(&ProfileUpdate)) {
+        let Some(origin) = self.origin else { return };
+        let (now, size) = (self.now, self.config.bucket_size);
+        let profile = self.profile();
+        let mut nodes = [VolumeNode::None; PROFILE_BUCKETS];
+        volume_nodes(&profile, self.config.smoothing, self.config.hvn_ratio, self.config.lvn_ratio, &mut nodes);
+
+        let level = |price: Price, volume: Quantity, node: VolumeNode| {
+            ProfileUpdate::Level(VolumeProfileLevel {
+                timestamp: now,
+                exchange_id: self.exchange_id,
+                symbol_id: self.symbol_id,
+                span: self.window.span(),
+                price,
+                bucket_size: size,
+                volume,
+                node,
+            })
+        };
+
+        // What clients hold, on the current grid; buckets that left it are cleared
+        let mut previous = [(0, VolumeNode::None); PROFILE_BUCKETS];
+        let published_origin = self.published_origin.unwrap_or(origin);
+        for (j, &sent) in self.published.iter().enumerate() {
+            if sent == (0, VolumeNode::None) {
+                continue;
+            }
+            let price = published_origin + j as Price * size;
+            match self.bucket(price) {
+                Some(i) => previous[i] = sent,
+                None => emit(&level(price, 0, VolumeNode::None)),
+            }
+        }
+        for i in 0..PROFILE_BUCKETS {
+            let current = (profile[i], nodes[i]);
+            if current != previous[i] {
+                emit(&level(origin + i as Price * size, current.0, current.1));
+            }
+            self.published[i] = current;
+        }
+        self.published_origin = Some(origin);
+
+        let (start, end) = self.span();
+        let stats = profile_stats(&profile, self.config.value_area);
+        let summary = VolumeProfileSummary {
+            timestamp: now,
+            exchange_id: self.exchange_id,
+            symbol_id: self.symbol_id,
+            span: self.window.span(),
+            start,
+            end,
+            poc: stats.map_or(0, |s| origin + s.poc as Price * size),
+            value_area_low: stats.map_or(0, |s| origin + s.value_area.0 as Price * size),
+            value_area_high: stats.map_or(0, |s| origin + (s.value_area.1 + 1) as Price * size),
+            total_volume: stats.map_or(0, |s| s.total),
+        };
+        let changed = self.summary.is_none_or(|last| {
+            (last.span, last.start, last.poc, last.value_area_low, last.value_area_high, last.total_volume)
+                != (summary.span, summary.start, summary.poc, summary.value_area_low, summary.value_area_high, summary.total_volume)
+        });
+        if changed && (stats.is_some() || self.summary.is_some()) {
+            self.summary = Some(summary);
+            emit(&ProfileUpdate::Summary(summary));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+    use vibe_hft_sbe_messages::Side;
+
+    const CONFIG: VolumeProfileConfig = VolumeProfileConfig {
+        bucket_size: 10,
+        slice: 100,
+        session_length: 1_000,
+        session_offset: 0,
+        value_area: 0.7,
+        smoothing: 0,
+        hvn_ratio: 1.5,
+        lvn_ratio: 0.5,
+        interval: 10,
+    };
+
+    fn trade(timestamp: u64, exchange_id: ExchangeID, price: Price, quantity: Quantity) -> Trade {
+        Trade { timestamp, exchange_id, symbol_id: 1, trade_id: timestamp, aggressor_side: Side::Buy, price, quantity }
+    }
+
+    #[test]
+    fn test_poc_value_area_and_nodes() {
+        let volume = [0, 2, 10, 30, 12, 1, 2, 25, 8, 0];
+        let stats = profile_stats(&volume, 0.7).unwrap();
+        assert_eq!(stats.poc, 3);
+        assert_eq!(stats.total, 90);
+        // 30, then 12 up, 10 and 2 down; with the low end reached it grows
+        // up through 1, 2 and 25 until 82 >= 63
+        assert_eq!(stats.value_area, (1, 7));
+
+        let mut nodes = [VolumeNode::None; 10];
+        volume_nodes(&volume, 0, 1.5, 0.5, &mut nodes);
+        assert_eq!(nodes[3], VolumeNode::High);
+        assert_eq!(nodes[7], VolumeNode::High);
+        assert_eq!(nodes[5], VolumeNode::Low);
+        assert_eq!(nodes.iter().filter(|n| **n != VolumeNode::None).count(), 3);
+
+        // Ties go to the middle of the range
+        assert_eq!(profile_stats(&[5, 1, 5, 1, 5], 0.7).unwrap().poc, 2);
+        assert_eq!(profile_stats(&[0, 0], 0.7), None);
+    }
+
+    #[test]
+    fn test_composite_profile_publishes_changes_only() {
+        let mut profile = VolumeProfile::new(None, 1, ProfileWindow::Session, CONFIG);
+        let mut updates = Vec::new();
+        // Two venues into one profile
+        profile.on_trade(&trade(0, ExchangeID::Binance, 1_000, 5));
+        profile.on_trade(&trade(5, ExchangeID::Bybit, 1_004, 3));
+        profile.on_trade(&trade(8, ExchangeID::Coinbase, 1_020, 2));
+        profile.on_timer(10, |u| updates.push(*u));
+        assert_eq!(profile.volume(1_000), 8);
+
+        let levels: Vec<_> = updates
+            .iter()
+            .filter_map(|u| match u {
+                ProfileUpdate::Level(l) => Some((l.price, l.volume, l.node)),
+                _ => None,
+            })
+            .collect();
+        // The empty bucket between the two is a low-volume node
+        assert_eq!(levels, [(1_000, 8, VolumeNode::High), (1_010, 0, VolumeNode::Low), (1_020, 2, VolumeNode::None)]);
+        let Some(ProfileUpdate::Summary(summary)) = updates.last() else { panic!("no summary") };
+        assert_eq!((summary.poc, summary.value_area_low, summary.value_area_high), (1_000, 1_000, 1_010));
+        assert_eq!((summary.total_volume, summary.start, summary.span), (10, 0, ProfileSpan::Session));
+
+        // Within the interval nothing goes out; after it only the changed bucket
+        profile.on_trade(&trade(12, ExchangeID::Binance, 1_020, 1));
+        updates.clear();
+        profile.on_timer(15, |u| updates.push(*u));
+        assert!(updates.is_empty());
+        profile.on_timer(20, |u| updates.push(*u));
+        assert!(matches!(updates[0], ProfileUpdate::Level(l) if (l.price, l.volume) == (1_020, 3)));
+        assert_eq!(updates.len(), 2);
+
+        // A new session clears every bucket
+        updates.clear();
+        profile.on_timer(1_000, |u| updates.push(*u));
+        assert!(updates.iter().all(|u| match u {
+            ProfileUpdate::Level(l) => l.volume == 0,
+            ProfileUpdate::Summary(s) => s.total_volume == 0 && s.start == 1_000,
+        }));
+        assert_eq!(updates.len(), 4);
+    }
+
+    #[test]
+    fn test_rolling_and_visible_range_windows() {
+        let mut profile = VolumeProfile::new(Some(ExchangeID::Binance), 1, ProfileWindow::Rolling(200), CONFIG);
+        for (t, price) in [(50, 1_000), (150, 1_010), (250, 1_020), (350, 1_030)] {
+            profile.on_trade(&trade(t, ExchangeID::Binance, price, 4));
+        }
+        // Other venues are not this profile's
+        profile.on_trade(&trade(360, ExchangeID::Bybit, 1_030, 100));
+
+        // Rolling 200 at 350 covers the slices from 100
+        assert_eq!((profile.volume(1_000), profile.volume(1_010), profile.volume(1_030)), (0, 4, 4));
+        profile.set_window(ProfileWindow::VisibleRange { from: 0, to: 200 });
+        assert_eq!((profile.volume(1_000), profile.volume(1_010), profile.volume(1_020)), (4, 4, 0));
+
+        // A move far away shifts the grid and drops what falls off
+        profile.set_window(ProfileWindow::Rolling(500));
+        profile.on_trade(&trade(400, ExchangeID::Binance, 3_000, 1));
+        assert_eq!(profile.volume(3_000), 1);
+        assert_eq!(profile.volume(1_000), 0);
+        assert_eq!(profile.stats().map(|s| s.total), Some(1));
+    }
+}
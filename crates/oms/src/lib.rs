@@ -0,0 +1,49 @@
+#![no_std]
+
+extern crate alloc;
+
+pub mod manager;
+pub mod order;
+pub mod position;
+
+pub use manager::OrderManager;
+pub use order::{ManagedOrder, OrderState};
+pub use position::{NetPosition, Position, PositionKeeper};
+
+use core::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OmsError {
+    UnknownOrder(u64),
+    /// The order is already done, or a cancel/replace is already in flight
+    NotCancelable(u64),
+    /// The venue reported a status the order cannot reach from its current state
+    InvalidTransition { cl_ord_id: u64, from: OrderState, to: OrderState },
+    /// Execution id already applied to this order
+    DuplicateExecution { cl_ord_id: u64, exec_id: u64 },
+    /// Fills exceed the order quantity
+    Overfill { cl_ord_id: u64, filled: u64, quantity: u64 },
+    /// Our leaves quantity disagrees with the venue's; the venue's value is adopted
+    LeavesMismatch { cl_ord_id: u64, expected: u64, reported: u64 },
+}
+
+impl fmt::Display for OmsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OmsError::UnknownOrder(id) => write!(f, "unknown order {}", id),
+            OmsError::NotCancelable(id) => write!(f, "order {} cannot be canceled or replaced", id),
+            OmsError::InvalidTransition { cl_ord_id, from, to } => {
+                write!(f, "order {}: invalid transition {:?} -> {:?}", cl_ord_id, from, to)
+            }
+            OmsError::DuplicateExecution { cl_ord_id, exec_id } => {
+                write!(f, "order {}: duplicate execution {}", cl_ord_id, exec_id)
+            }
+            OmsError::Overfill { cl_ord_id, filled, quantity } => {
+                write!(f, "order {}: filled {} of {}", cl_ord_id, filled, quantity)
+            }
+            OmsError::LeavesMismatch { cl_ord_id, expected, reported } => {
+                write!(f, "order {}: leaves {} but venue reports {}", cl_ord_id, expected, reported)
+            }
+        }
+    }
+}
@@ -0,0 +1,336 @@
+use alloc::vec::Vec;
+
+use vibe_hft_core::{Quantity, Timestamp};
+use vibe_hft_sbe_messages::{ExchangeID, ExecutionReport, OrdStatus, OrderCancel, OrderEntry, Side};
+
+use crate::order::{ManagedOrder, OrderState};
+use crate::OmsError;
+
+/// Tracks every order we send by `clOrdId` and reconciles it against the
+/// venue's execution reports.
+///
+/// Orders are kept in a pre-sized `Vec` and looked up linearly: a strategy only
+/// has a handful of live orders, and the storage is reused once completed
+/// orders are purged, so steady-state operation does not allocate.
+pub struct OrderManager {
+    orders: Vec<ManagedOrder>,
+    next_cl_ord_id: u64,
+}
+
+impl Default for OrderManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OrderManager {
+    pub fn new() -> Self {
+        Self::with_capacity(256)
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            orders: Vec::with_capacity(capacity),
+            next_cl_ord_id: 1,
+        }
+    }
+
+    /// Register a new order. The `cl_ord_id` of `entry` is ignored and a fresh
+    /// one assigned; the returned message is what should be sent to the venue.
+    pub fn create(&mut self, mut entry: OrderEntry) -> OrderEntry {
+        entry.cl_ord_id = self.next_cl_ord_id;
+        self.next_cl_ord_id += 1;
+
+        self.orders.push(ManagedOrder {
+            cl_ord_id: entry.cl_ord_id,
+            exchange_id: entry.exchange_id,
+            symbol_id: entry.symbol_id,
+            side: entry.side,
+            order_type: entry.order_type,
+            time_in_force: entry.time_in_force,
+            price: entry.price,
+            quantity: entry.quantity,
+            filled_quantity: 0,
+            state: OrderState::PendingNew,
+            cancel_pending: false,
+            replaces: None,
+            replaced_by: None,
+            created_at: entry.timestamp,
+            updated_at: entry.timestamp,
+            fill_notional: 0,
+            last_exec_id: 0,
+        });
+        entry
+    }
+
+    pub fn request_cancel(&mut self, cl_ord_id: u64, timestamp: Timestamp) -> Result<OrderCancel, OmsError> {
+        let order = self.cancelable(cl_ord_id)?;
+        order.cancel_pending = true;
+        order.updated_at = timestamp;
+        Ok(OrderCancel {
+            timestamp,
+            cl_ord_id,
+            exchange_id: order.exchange_id,
+            symbol_id: order.symbol_id,
+            side: order.side,
+        })
+    }
+
+    /// Cancel/replace an order with a new price and total quantity.
+    ///
+    /// Quantity already filled on the original counts towards `quantity`, so
+    /// the replacement is only sent for the remainder. Returns the cancel for
+    /// the original and, unless nothing remains, the replacement order.
+    pub fn request_replace(
+        &mut self,
+        cl_ord_id: u64,
+        timestamp: Timestamp,
+        price: i64,
+        quantity: Quantity,
+    ) -> Result<(OrderCancel, Option<OrderEntry>), OmsError> {
+        let cancel = self.request_cancel(cl_ord_id, timestamp)?;
+        let original = *self.get(cl_ord_id).ok_or(OmsError::UnknownOrder(cl_ord_id))?;
+
+        let remaining = quantity.saturating_sub(original.filled_quantity);
+        if remaining == 0 {
+            return Ok((cancel, None));
+        }
+
+        let replacement = self.create(OrderEntry {
+            timestamp,
+            cl_ord_id: 0,
+            exchange_id: original.exchange_id,
+            symbol_id: original.symbol_id,
+            side: original.side,
+            price,
+            quantity: remaining,
+            order_type: original.order_type,
+            time_in_force: original.time_in_force,
+        });
+        if let Some(order) = self.get_mut(replacement.cl_ord_id) {
+            order.replaces = Some(cl_ord_id);
+        }
+        if let Some(order) = self.get_mut(cl_ord_id) {
+            order.replaced_by = Some(replacement.cl_ord_id);
+        }
+        Ok((cancel, Some(replacement)))
+    }
+
+    /// Apply a venue execution report. Returns the order's new state.
+    ///
+    /// Fills and state are applied even when a reconciliation error is
+    /// returned, so the book of record follows the venue.
+    pub fn on_execution_report(&mut self, report: &ExecutionReport) -> Result<OrderState, OmsError> {
+        let order = self
+            .get_mut(report.cl_ord_id)
+            .ok_or(OmsError::UnknownOrder(report.cl_ord_id))?;
+        let cl_ord_id = order.cl_ord_id;
+
+        if report.exec_id != 0 {
+            if report.exec_id <= order.last_exec_id {
+                return Err(OmsError::DuplicateExecution { cl_ord_id, exec_id: report.exec_id });
+            }
+            order.last_exec_id = report.exec_id;
+        }
+        order.updated_at = report.timestamp;
+
+        // A reject while a cancel is in flight, or after the order is done,
+        // refuses the cancel rather than the order
+        let cancel_rejected = order.cancel_pending || order.state.is_terminal();
+        if report.status == OrdStatus::Rejected && cancel_rejected && order.state != OrderState::PendingNew {
+            order.cancel_pending = false;
+            return Ok(order.state);
+        }
+
+        let next = order.state.on_status(report.status).ok_or(OmsError::InvalidTransition {
+            cl_ord_id,
+            from: order.state,
+            to: state_for(report.status),
+        })?;
+
+        order.state = next;
+        if next.is_terminal() {
+            order.cancel_pending = false;
+        }
+
+        if report.filled_quantity > 0 {
+            order.filled_quantity += report.filled_quantity;
+            order.fill_notional += report.filled_price as i128 * report.filled_quantity as i128;
+            if order.filled_quantity > order.quantity {
+                return Err(OmsError::Overfill {
+                    cl_ord_id,
+                    filled: order.filled_quantity,
+                    quantity: order.quantity,
+                });
+            }
+        }
+
+        if !next.is_terminal() {
+            let expected = order.quantity - order.filled_quantity;
+            if report.leaves_quantity != expected {
+                order.quantity = order.filled_quantity + report.leaves_quantity;
+                return Err(OmsError::LeavesMismatch {
+                    cl_ord_id,
+                    expected,
+                    reported: report.leaves_quantity,
+                });
+            }
+        }
+        Ok(next)
+    }
+
+    pub fn get(&self, cl_ord_id: u64) -> Option<&ManagedOrder> {
+        self.orders.iter().find(|o| o.cl_ord_id == cl_ord_id)
+    }
+
+    fn get_mut(&mut self, cl_ord_id: u64) -> Option<&mut ManagedOrder> {
+        self.orders.iter_mut().find(|o| o.cl_ord_id == cl_ord_id)
+    }
+
+    fn cancelable(&mut self, cl_ord_id: u64) -> Result<&mut ManagedOrder, OmsError> {
+        let order = self.get_mut(cl_ord_id).ok_or(OmsError::UnknownOrder(cl_ord_id))?;
+        if !order.is_live() || order.cancel_pending {
+            return Err(OmsError::NotCancelable(cl_ord_id));
+        }
+        Ok(order)
+    }
+
+    pub fn live_orders(&self) -> impl Iterator<Item = &ManagedOrder> {
+        self.orders.iter().filter(|o| o.is_live())
+    }
+
+    pub fn live_orders_for(&self, exchange_id: ExchangeID, symbol_id: u32) -> impl Iterator<Item = &ManagedOrder> {
+        self.live_orders()
+            .filter(move |o| o.exchange_id == exchange_id && o.symbol_id == symbol_id)
+    }
+
+    /// Total leaves quantity working on one side of an instrument
+    pub fn outstanding_quantity(&self, exchange_id: ExchangeID, symbol_id: u32, side: Side) -> Quantity {
+        self.live_orders_for(exchange_id, symbol_id)
+            .filter(|o| o.side == side)
+            .map(|o| o.leaves_quantity())
+            .sum()
+    }
+
+    /// Drop filled, canceled and rejected orders, keeping the storage for reuse
+    pub fn purge_completed(&mut self) {
+        self.orders.retain(|o| o.is_live());
+    }
+}
+
+fn state_for(status: OrdStatus) -> OrderState {
+    match status {
+        OrdStatus::New => OrderState::Acked,
+        OrdStatus::PartiallyFilled => OrderState::PartiallyFilled,
+        OrdStatus::Filled => OrderState::Filled,
+        OrdStatus::Canceled => OrderState::Canceled,
+        OrdStatus::Rejected => OrderState::Rejected,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vibe_hft_sbe_messages::{OrdType, TimeInForce};
+
+    fn entry(side: Side, price: i64, quantity: u64) -> OrderEntry {
+        OrderEntry {
+            timestamp: 1,
+            cl_ord_id: 0,
+            exchange_id: ExchangeID::Binance,
+            symbol_id: 1,
+            side,
+            price,
+            quantity,
+            order_type: OrdType::Limit,
+            time_in_force: TimeInForce::Gtc,
+        }
+    }
+
+    fn report(cl_ord_id: u64, exec_id: u64, filled: u64, leaves: u64, status: OrdStatus) -> ExecutionReport {
+        ExecutionReport {
+            timestamp: 2,
+            cl_ord_id,
+            exec_id,
+            exchange_id: ExchangeID::Binance,
+            symbol_id: 1,
+            side: Side::Buy,
+            filled_quantity: filled,
+            filled_price: 100,
+            leaves_quantity: leaves,
+            fee: 0,
+            status,
+        }
+    }
+
+    #[test]
+    fn test_order_lifecycle() {
+        let mut oms = OrderManager::new();
+        let sent = oms.create(entry(Side::Buy, 100, 10));
+        assert_eq!(sent.cl_ord_id, 1);
+        assert_eq!(oms.outstanding_quantity(ExchangeID::Binance, 1, Side::Buy), 10);
+
+        assert_eq!(oms.on_execution_report(&report(1, 1, 0, 10, OrdStatus::New)), Ok(OrderState::Acked));
+        assert_eq!(
+            oms.on_execution_report(&report(1, 2, 4, 6, OrdStatus::PartiallyFilled)),
+            Ok(OrderState::PartiallyFilled)
+        );
+        assert_eq!(oms.outstanding_quantity(ExchangeID::Binance, 1, Side::Buy), 6);
+        assert_eq!(oms.on_execution_report(&report(1, 3, 6, 0, OrdStatus::Filled)), Ok(OrderState::Filled));
+        assert_eq!(oms.get(1).unwrap().avg_fill_price(), Some(100));
+
+        // Late or replayed reports are refused
+        assert_eq!(
+            oms.on_execution_report(&report(1, 3, 6, 0, OrdStatus::Filled)),
+            Err(OmsError::DuplicateExecution { cl_ord_id: 1, exec_id: 3 })
+        );
+        oms.purge_completed();
+        assert_eq!(oms.live_orders().count(), 0);
+        assert!(oms.get(1).is_none());
+    }
+
+    #[test]
+    fn test_cancel_reject_keeps_order_working() {
+        let mut oms = OrderManager::new();
+        oms.create(entry(Side::Sell, 101, 5));
+        oms.on_execution_report(&report(1, 1, 0, 5, OrdStatus::New)).unwrap();
+
+        oms.request_cancel(1, 3).unwrap();
+        assert_eq!(oms.request_cancel(1, 4).unwrap_err(), OmsError::NotCancelable(1));
+
+        assert_eq!(oms.on_execution_report(&report(1, 2, 0, 0, OrdStatus::Rejected)), Ok(OrderState::Acked));
+        assert!(!oms.get(1).unwrap().cancel_pending);
+        assert!(oms.request_cancel(1, 5).is_ok());
+    }
+
+    #[test]
+    fn test_replace_accounts_for_filled_quantity() {
+        let mut oms = OrderManager::new();
+        oms.create(entry(Side::Buy, 100, 10));
+        oms.on_execution_report(&report(1, 1, 3, 7, OrdStatus::PartiallyFilled)).unwrap();
+
+        let (cancel, replacement) = oms.request_replace(1, 5, 99, 10).unwrap();
+        let replacement = replacement.unwrap();
+        assert_eq!(cancel.cl_ord_id, 1);
+        assert_eq!((replacement.cl_ord_id, replacement.price, replacement.quantity), (2, 99, 7));
+        assert_eq!(oms.get(2).unwrap().replaces, Some(1));
+
+        oms.on_execution_report(&report(1, 2, 0, 0, OrdStatus::Canceled)).unwrap();
+        assert_eq!(oms.live_orders().map(|o| o.cl_ord_id).collect::<Vec<_>>(), [2]);
+    }
+
+    #[test]
+    fn test_reconciliation_adopts_venue_leaves() {
+        let mut oms = OrderManager::new();
+        oms.create(entry(Side::Buy, 100, 10));
+        assert_eq!(
+            oms.on_execution_report(&report(1, 1, 0, 8, OrdStatus::New)),
+            Err(OmsError::LeavesMismatch { cl_ord_id: 1, expected: 10, reported: 8 })
+        );
+        assert_eq!(oms.get(1).unwrap().leaves_quantity(), 8);
+        assert_eq!(
+            oms.on_execution_report(&report(1, 2, 9, 0, OrdStatus::Filled)),
+            Err(OmsError::Overfill { cl_ord_id: 1, filled: 9, quantity: 8 })
+        );
+    }
+}
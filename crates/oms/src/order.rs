@@ -0,0 +1,109 @@
+use vibe_hft_core::{Price, Quantity, Timestamp};
+use vibe_hft_sbe_messages::{ExchangeID, OrdStatus, OrdType, Side, TimeInForce};
+
+/// Lifecycle of a client order.
+///
+/// ```text
+/// PendingNew -> Acked -> PartiallyFilled -> Filled
+///     |           |            |
+///     +-----------+------------+-> Canceled
+///     +-> Rejected
+/// ```
+/// A fill may arrive before the ack on some venues, so `PendingNew` can move
+/// straight to `PartiallyFilled` or `Filled`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderState {
+    /// Sent, not yet acknowledged by the venue
+    PendingNew,
+    /// Acknowledged and resting
+    Acked,
+    PartiallyFilled,
+    Filled,
+    Canceled,
+    Rejected,
+}
+
+impl OrderState {
+    pub fn is_terminal(self) -> bool {
+        matches!(self, OrderState::Filled | OrderState::Canceled | OrderState::Rejected)
+    }
+
+    /// State reached when the venue reports `status`, or `None` if the
+    /// transition is not allowed from here.
+    pub fn on_status(self, status: OrdStatus) -> Option<OrderState> {
+        use OrderState::*;
+        match (self, status) {
+            (PendingNew, OrdStatus::New) => Some(Acked),
+            (PendingNew | Acked | PartiallyFilled, OrdStatus::PartiallyFilled) => Some(PartiallyFilled),
+            (PendingNew | Acked | PartiallyFilled, OrdStatus::Filled) => Some(Filled),
+            (PendingNew | Acked | PartiallyFilled, OrdStatus::Canceled) => Some(Canceled),
+            (PendingNew, OrdStatus::Rejected) => Some(Rejected),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ManagedOrder {
+    pub cl_ord_id: u64,
+    pub exchange_id: ExchangeID,
+    pub symbol_id: u32,
+    pub side: Side,
+    pub order_type: OrdType,
+    pub time_in_force: TimeInForce,
+    pub price: Price,
+    pub quantity: Quantity,
+    pub filled_quantity: Quantity,
+    pub state: OrderState,
+    /// A cancel (or the cancel leg of a replace) is in flight
+    pub cancel_pending: bool,
+    /// Order this one replaces, if it was created by a cancel/replace
+    pub replaces: Option<u64>,
+    /// Replacement order created for this one, if any
+    pub replaced_by: Option<u64>,
+    pub created_at: Timestamp,
+    pub updated_at: Timestamp,
+    pub(crate) fill_notional: i128,
+    pub(crate) last_exec_id: u64,
+}
+
+impl ManagedOrder {
+    /// Quantity still working at the venue
+    pub fn leaves_quantity(&self) -> Quantity {
+        if self.state.is_terminal() {
+            0
+        } else {
+            self.quantity.saturating_sub(self.filled_quantity)
+        }
+    }
+
+    pub fn is_live(&self) -> bool {
+        !self.state.is_terminal()
+    }
+
+    /// Volume-weighted fill price, `None` before the first fill
+    pub fn avg_fill_price(&self) -> Option<Price> {
+        if self.filled_quantity == 0 {
+            None
+        } else {
+            Some((self.fill_notional / self.filled_quantity as i128) as Price)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transitions() {
+        use OrderState::*;
+        assert_eq!(PendingNew.on_status(OrdStatus::New), Some(Acked));
+        assert_eq!(PendingNew.on_status(OrdStatus::Filled), Some(Filled));
+        assert_eq!(Acked.on_status(OrdStatus::PartiallyFilled), Some(PartiallyFilled));
+        assert_eq!(PartiallyFilled.on_status(OrdStatus::Canceled), Some(Canceled));
+        assert_eq!(Acked.on_status(OrdStatus::New), None);
+        assert_eq!(Acked.on_status(OrdStatus::Rejected), None);
+        assert_eq!(Filled.on_status(OrdStatus::Canceled), None);
+    }
+}
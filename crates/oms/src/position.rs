@@ -0,0 +1,324 @@
+use alloc::vec::Vec;
+
+use vibe_hft_core::{Price, Quantity, Timestamp};
+use vibe_hft_sbe_messages::{ExchangeID, ExecutionReport, PnlSnapshot, Side};
+
+const FIXED_POINT: i128 = 100_000_000;
+
+/// Position on one venue/symbol. Money fields are in quote units (1e8).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    pub exchange_id: ExchangeID,
+    pub symbol_id: u32,
+    /// Signed quantity, negative when short
+    pub quantity: i64,
+    /// Average price of the open quantity, 0 when flat
+    pub avg_entry_price: Price,
+    /// Latest mid, 0 until marked
+    pub mark_price: Price,
+    pub realized_pnl: i64,
+    /// Fees paid, negative for net rebates
+    pub fees: i64,
+    /// Funding received, negative when paid
+    pub funding: i64,
+}
+
+impl Position {
+    fn new(exchange_id: ExchangeID, symbol_id: u32) -> Self {
+        Self {
+            exchange_id,
+            symbol_id,
+            quantity: 0,
+            avg_entry_price: 0,
+            mark_price: 0,
+            realized_pnl: 0,
+            fees: 0,
+            funding: 0,
+        }
+    }
+
+    /// Open quantity valued at the mark, 0 until marked
+    pub fn unrealized_pnl(&self) -> i64 {
+        if self.mark_price == 0 {
+            return 0;
+        }
+        ((self.mark_price - self.avg_entry_price) as i128 * self.quantity as i128 / FIXED_POINT) as i64
+    }
+
+    /// Realized plus unrealized, after fees and funding
+    pub fn net_pnl(&self) -> i64 {
+        self.realized_pnl + self.unrealized_pnl() - self.fees + self.funding
+    }
+
+    pub fn snapshot(&self, timestamp: Timestamp) -> PnlSnapshot {
+        PnlSnapshot {
+            timestamp,
+            exchange_id: Some(self.exchange_id),
+            symbol_id: self.symbol_id,
+            position: self.quantity,
+            avg_entry_price: self.avg_entry_price,
+            mark_price: self.mark_price,
+            realized_pnl: self.realized_pnl,
+            unrealized_pnl: self.unrealized_pnl(),
+            fees: self.fees,
+            funding: self.funding,
+        }
+    }
+
+    /// Average-cost fill: adding moves the average entry, reducing realizes
+    /// against it, and flipping opens the remainder at the fill price.
+    fn apply_fill(&mut self, side: Side, price: Price, quantity: Quantity) {
+        let signed = match side {
+            Side::Buy => quantity as i64,
+            Side::Sell => -(quantity as i64),
+        };
+        let open = self.quantity.unsigned_abs();
+        if self.quantity == 0 || self.quantity.signum() == signed.signum() {
+            let cost = self.avg_entry_price as i128 * open as i128 + price as i128 * quantity as i128;
+            self.avg_entry_price = (cost / (open + quantity) as i128) as Price;
+            self.quantity += signed;
+            return;
+        }
+
+        let direction = self.quantity.signum();
+        let closed = quantity.min(open);
+        let pnl = (price - self.avg_entry_price) as i128 * closed as i128 * direction as i128 / FIXED_POINT;
+        self.realized_pnl += pnl as i64;
+        self.quantity += signed;
+        if self.quantity == 0 {
+            self.avg_entry_price = 0;
+        } else if self.quantity.signum() != direction {
+            self.avg_entry_price = price;
+        }
+    }
+}
+
+/// Positions on one symbol netted across venues.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NetPosition {
+    pub symbol_id: u32,
+    pub quantity: i64,
+    /// Net cost of the venue positions over the net quantity, 0 when flat
+    pub avg_entry_price: Price,
+    /// Venue marks weighted by the size of each venue's position
+    pub mark_price: Price,
+    pub realized_pnl: i64,
+    pub unrealized_pnl: i64,
+    pub fees: i64,
+    pub funding: i64,
+}
+
+impl NetPosition {
+    pub fn net_pnl(&self) -> i64 {
+        self.realized_pnl + self.unrealized_pnl - self.fees + self.funding
+    }
+
+    pub fn snapshot(&self, timestamp: Timestamp) -> PnlSnapshot {
+        PnlSnapshot {
+            timestamp,
+            exchange_id: None,
+            symbol_id: self.symbol_id,
+            position: self.quantity,
+            avg_entry_price: self.avg_entry_price,
+            mark_price: self.mark_price,
+            realized_pnl: self.realized_pnl,
+            unrealized_pnl: self.unrealized_pnl,
+            fees: self.fees,
+            funding: self.funding,
+        }
+    }
+}
+
+/// Book of record for positions and PnL, fed by execution reports.
+#[derive(Debug, Default)]
+pub struct PositionKeeper {
+    positions: Vec<Position>,
+}
+
+impl PositionKeeper {
+    pub fn new() -> Self {
+        Self { positions: Vec::with_capacity(16) }
+    }
+
+    /// Apply the fill and fee in `report`, if any
+    pub fn on_execution_report(&mut self, report: &ExecutionReport) {
+        if report.filled_quantity == 0 && report.fee == 0 {
+            return;
+        }
+        let position = self.get_or_insert(report.exchange_id, report.symbol_id);
+        if report.filled_quantity > 0 {
+            position.apply_fill(report.side, report.filled_price, report.filled_quantity);
+        }
+        position.fees += report.fee;
+    }
+
+    /// Mark a venue/symbol to `mid`; symbols never traded are ignored
+    pub fn mark(&mut self, exchange_id: ExchangeID, symbol_id: u32, mid: Price) {
+        if let Some(position) = self.positions.iter_mut().find(|p| p.exchange_id == exchange_id && p.symbol_id == symbol_id) {
+            position.mark_price = mid;
+        }
+    }
+
+    /// Book a perpetual funding payment at `rate` (1e8 fixed point, positive
+    /// when longs pay) on the position valued at `mark_price`. Returns the
+    /// amount received, negative when paid.
+    pub fn apply_funding(&mut self, exchange_id: ExchangeID, symbol_id: u32, rate: i64, mark_price: Price) -> i64 {
+        let Some(position) = self.positions.iter_mut().find(|p| p.exchange_id == exchange_id && p.symbol_id == symbol_id)
+        else {
+            return 0;
+        };
+        let notional = position.quantity as i128 * mark_price as i128 / FIXED_POINT;
+        let payment = (-notional * rate as i128 / FIXED_POINT) as i64;
+        position.funding += payment;
+        payment
+    }
+
+    pub fn get(&self, exchange_id: ExchangeID, symbol_id: u32) -> Option<&Position> {
+        self.positions.iter().find(|p| p.exchange_id == exchange_id && p.symbol_id == symbol_id)
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = &Position> {
+        self.positions.iter()
+    }
+
+    /// `symbol_id` netted across venues, `None` if it was never traded
+    pub fn net(&self, symbol_id: u32) -> Option<NetPosition> {
+        let mut venues = self.positions.iter().filter(|p| p.symbol_id == symbol_id).peekable();
+        venues.peek()?;
+
+        let mut net = NetPosition {
+            symbol_id,
+            quantity: 0,
+            avg_entry_price: 0,
+            mark_price: 0,
+            realized_pnl: 0,
+            unrealized_pnl: 0,
+            fees: 0,
+            funding: 0,
+        };
+        let (mut cost, mut marked, mut weight) = (0i128, 0i128, 0i128);
+        for position in venues {
+            net.quantity += position.quantity;
+            net.realized_pnl += position.realized_pnl;
+            net.unrealized_pnl += position.unrealized_pnl();
+            net.fees += position.fees;
+            net.funding += position.funding;
+            cost += position.avg_entry_price as i128 * position.quantity as i128;
+            if position.mark_price != 0 {
+                let size = position.quantity.unsigned_abs().max(1) as i128;
+                marked += position.mark_price as i128 * size;
+                weight += size;
+            }
+        }
+        if net.quantity != 0 {
+            net.avg_entry_price = (cost / net.quantity as i128) as Price;
+        }
+        if weight != 0 {
+            net.mark_price = (marked / weight) as Price;
+        }
+        Some(net)
+    }
+
+    /// One snapshot per venue/symbol, followed by the netted snapshot of
+    /// every symbol held on more than one venue
+    pub fn snapshots(&self, timestamp: Timestamp) -> impl Iterator<Item = PnlSnapshot> + '_ {
+        let venues = self.positions.iter().map(move |p| p.snapshot(timestamp));
+        let netted = self.positions.iter().enumerate().filter_map(move |(i, p)| {
+            let first = self.positions[..i].iter().all(|q| q.symbol_id != p.symbol_id);
+            let shared = self.positions.iter().filter(|q| q.symbol_id == p.symbol_id).count() > 1;
+            if first && shared {
+                self.net(p.symbol_id).map(|net| net.snapshot(timestamp))
+            } else {
+                None
+            }
+        });
+        venues.chain(netted)
+    }
+
+    fn get_or_insert(&mut self, exchange_id: ExchangeID, symbol_id: u32) -> &mut Position {
+        let index = match self.positions.iter().position(|p| p.exchange_id == exchange_id && p.symbol_id == symbol_id) {
+            Some(index) => index,
+            None => {
+                self.positions.push(Position::new(exchange_id, symbol_id));
+                self.positions.len() - 1
+            }
+        };
+        &mut self.positions[index]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vibe_hft_sbe_messages::OrdStatus;
+
+    const ONE: i64 = 100_000_000;
+
+    fn fill(exchange_id: ExchangeID, side: Side, price: i64, quantity: i64, fee: i64) -> ExecutionReport {
+        ExecutionReport {
+            timestamp: 0,
+            cl_ord_id: 1,
+            exec_id: 1,
+            exchange_id,
+            symbol_id: 1,
+            side,
+            filled_quantity: (quantity * ONE) as u64,
+            filled_price: price * ONE,
+            leaves_quantity: 0,
+            fee,
+            status: OrdStatus::Filled,
+        }
+    }
+
+    #[test]
+    fn test_average_cost_realizes_on_reduce_and_flip() {
+        let mut keeper = PositionKeeper::new();
+        keeper.on_execution_report(&fill(ExchangeID::Binance, Side::Buy, 100, 2, 5));
+        keeper.on_execution_report(&fill(ExchangeID::Binance, Side::Buy, 106, 1, 5));
+        let position = *keeper.get(ExchangeID::Binance, 1).unwrap();
+        assert_eq!((position.quantity, position.avg_entry_price), (3 * ONE, 102 * ONE));
+
+        keeper.mark(ExchangeID::Binance, 1, 104 * ONE);
+        assert_eq!(keeper.get(ExchangeID::Binance, 1).unwrap().unrealized_pnl(), 6 * ONE);
+
+        // Sell 4: realize 3 × 8, open 1 short at 110
+        keeper.on_execution_report(&fill(ExchangeID::Binance, Side::Sell, 110, 4, 5));
+        let position = *keeper.get(ExchangeID::Binance, 1).unwrap();
+        assert_eq!((position.quantity, position.avg_entry_price), (-ONE, 110 * ONE));
+        assert_eq!(position.realized_pnl, 24 * ONE);
+        assert_eq!(position.unrealized_pnl(), 6 * ONE);
+        assert_eq!(position.net_pnl(), 30 * ONE - 15);
+    }
+
+    #[test]
+    fn test_funding_is_paid_by_longs_on_positive_rate() {
+        let mut keeper = PositionKeeper::new();
+        keeper.on_execution_report(&fill(ExchangeID::Bybit, Side::Buy, 100, 10, 0));
+        // 0.01% on 1_000 of notional
+        assert_eq!(keeper.apply_funding(ExchangeID::Bybit, 1, 10_000, 100 * ONE), -ONE / 10);
+        assert_eq!(keeper.get(ExchangeID::Bybit, 1).unwrap().funding, -ONE / 10);
+        assert_eq!(keeper.apply_funding(ExchangeID::Coinbase, 1, 10_000, 100 * ONE), 0);
+    }
+
+    #[test]
+    fn test_netting_across_venues() {
+        let mut keeper = PositionKeeper::new();
+        keeper.on_execution_report(&fill(ExchangeID::Binance, Side::Buy, 100, 2, 1));
+        keeper.on_execution_report(&fill(ExchangeID::Bybit, Side::Sell, 110, 1, 2));
+        keeper.mark(ExchangeID::Binance, 1, 105 * ONE);
+        keeper.mark(ExchangeID::Bybit, 1, 105 * ONE);
+
+        let net = keeper.net(1).unwrap();
+        assert_eq!(net.quantity, ONE);
+        assert_eq!(net.avg_entry_price, 90 * ONE);
+        assert_eq!(net.mark_price, 105 * ONE);
+        // +10 on the long leg, +5 on the short leg
+        assert_eq!(net.unrealized_pnl, 15 * ONE);
+        assert_eq!(net.fees, 3);
+
+        let snapshots: Vec<_> = keeper.snapshots(7).collect();
+        assert_eq!(snapshots.len(), 3);
+        assert_eq!(snapshots[2].exchange_id, None);
+        assert_eq!(snapshots[2].position, ONE);
+    }
+}
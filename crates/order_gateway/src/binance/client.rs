@@ -0,0 +1,410 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use futures_util::{SinkExt, StreamExt};
+use log::{info, warn};
+use serde::Deserialize;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio::time::{interval_at, Instant};
+use tokio_tungstenite::{connect_async, tungstenite::protocol::Message};
+use vibe_hft_core::{format_fixed, from_millis};
+use vibe_hft_sbe_messages::{ExecutionReport, OrdStatus, OrdType, OrderCancel, OrderEntry, Side, TimeInForce};
+use vibe_hft_strategy::OrderAction;
+
+use crate::error::GatewayError;
+use crate::OrderGateway;
+
+use super::credentials::Credentials;
+use super::http::{self, Endpoint, Response};
+use super::rate_limit::WeightTracker;
+use super::user_stream::parse_user_event;
+
+const ORDER_WEIGHT: u32 = 1;
+const LISTEN_KEY_WEIGHT: u32 = 2;
+const TIME_WEIGHT: u32 = 1;
+/// Listen keys expire after 60 minutes without a keepalive
+const KEEPALIVE_PERIOD: Duration = Duration::from_secs(30 * 60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinanceMarket {
+    Spot,
+    /// USDⓈ-M perpetual futures
+    UsdmFutures,
+}
+
+impl BinanceMarket {
+    fn order_path(self) -> &'static str {
+        match self {
+            BinanceMarket::Spot => "/api/v3/order",
+            BinanceMarket::UsdmFutures => "/fapi/v1/order",
+        }
+    }
+
+    fn listen_key_path(self) -> &'static str {
+        match self {
+            BinanceMarket::Spot => "/api/v3/userDataStream",
+            BinanceMarket::UsdmFutures => "/fapi/v1/listenKey",
+        }
+    }
+
+    fn time_path(self) -> &'static str {
+        match self {
+            BinanceMarket::Spot => "/api/v3/time",
+            BinanceMarket::UsdmFutures => "/fapi/v1/time",
+        }
+    }
+}
+
+/// A tradable symbol: our `symbol_id` and the venue's name and assets
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BinanceSymbol {
+    pub symbol_id: u32,
+    pub name: String,
+    pub base: String,
+    pub quote: String,
+}
+
+impl BinanceSymbol {
+    pub fn new(symbol_id: u32, name: &str, base: &str, quote: &str) -> Self {
+        Self { symbol_id, name: name.to_string(), base: base.to_string(), quote: quote.to_string() }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BinanceConfig {
+    pub market: BinanceMarket,
+    /// REST base URL, `http://` only for local stubs
+    pub rest_url: String,
+    /// WebSocket base URL; the user stream connects to `{ws_url}/ws/{listenKey}`
+    pub ws_url: String,
+    /// How long after its timestamp the venue still accepts a request (ms)
+    pub recv_window: u64,
+    /// Request weight allowed per minute
+    pub weight_limit: u32,
+    pub symbols: Vec<BinanceSymbol>,
+}
+
+impl BinanceConfig {
+    pub fn spot(symbols: Vec<BinanceSymbol>) -> Self {
+        Self {
+            market: BinanceMarket::Spot,
+            rest_url: "https://api.binance.com".to_string(),
+            ws_url: "wss://stream.binance.com:9443".to_string(),
+            recv_window: 5_000,
+            weight_limit: 6_000,
+            symbols,
+        }
+    }
+
+    pub fn usdm_futures(symbols: Vec<BinanceSymbol>) -> Self {
+        Self {
+            market: BinanceMarket::UsdmFutures,
+            rest_url: "https://fapi.binance.com".to_string(),
+            ws_url: "wss://fstream.binance.com".to_string(),
+            recv_window: 5_000,
+            weight_limit: 2_400,
+            symbols,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ErrorBody {
+    code: i64,
+    msg: String,
+}
+
+#[derive(Deserialize)]
+struct ListenKey {
+    #[serde(rename = "listenKey")]
+    listen_key: String,
+}
+
+#[derive(Deserialize)]
+struct ServerTime {
+    #[serde(rename = "serverTime")]
+    server_time: u64,
+}
+
+/// Order entry on Binance spot or USDⓈ-M futures.
+///
+/// Orders go over signed REST requests with `newOrderRespType=ACK`; every
+/// status change, fills included, comes back through the user-data stream.
+/// Orders the venue refuses synchronously, or that the weight limit keeps
+/// us from sending, are reported as local `Rejected` reports so the OMS
+/// sees them the same way.
+pub struct BinanceClient {
+    config: BinanceConfig,
+    credentials: Credentials,
+    endpoint: Endpoint,
+    weights: WeightTracker,
+    /// Venue clock minus local clock (ms)
+    time_offset: i64,
+    reports_tx: mpsc::UnboundedSender<Result<ExecutionReport, GatewayError>>,
+    reports_rx: mpsc::UnboundedReceiver<Result<ExecutionReport, GatewayError>>,
+    user_stream: Option<JoinHandle<()>>,
+}
+
+impl BinanceClient {
+    pub fn new(config: BinanceConfig, credentials: Credentials) -> Result<Self, GatewayError> {
+        let endpoint = Endpoint::parse(&config.rest_url)?;
+        let (reports_tx, reports_rx) = mpsc::unbounded_channel();
+        Ok(Self {
+            weights: WeightTracker::new(config.weight_limit),
+            config,
+            credentials,
+            endpoint,
+            time_offset: 0,
+            reports_tx,
+            reports_rx,
+            user_stream: None,
+        })
+    }
+
+    pub fn config(&self) -> &BinanceConfig {
+        &self.config
+    }
+
+    pub fn weights(&self) -> &WeightTracker {
+        &self.weights
+    }
+
+    /// Measure the venue clock so request timestamps land inside `recv_window`
+    pub async fn sync_time(&mut self) -> Result<i64, GatewayError> {
+        self.weights.acquire(local_ms(), TIME_WEIGHT)?;
+        let sent = local_ms();
+        let response = self.call("GET", self.config.market.time_path()).await?;
+        let received = local_ms();
+        let time: ServerTime = parse_body(&response)?;
+        self.time_offset = time.server_time as i64 - ((sent + received) / 2) as i64;
+        Ok(self.time_offset)
+    }
+
+    pub async fn place(&mut self, entry: &OrderEntry) -> Result<(), GatewayError> {
+        let params = format!("symbol={}&{}", self.symbol_name(entry.symbol_id)?, order_params(entry));
+        let result = self.signed("POST", self.config.market.order_path(), &params).await;
+        if let Err(e) = &result {
+            self.reject(entry, e);
+        }
+        result.map(|_| ())
+    }
+
+    pub async fn cancel(&mut self, cancel: &OrderCancel) -> Result<(), GatewayError> {
+        let params = format!("symbol={}&origClientOrderId={}", self.symbol_name(cancel.symbol_id)?, cancel.cl_ord_id);
+        self.signed("DELETE", self.config.market.order_path(), &params).await.map(|_| ())
+    }
+
+    /// Spot uses the atomic cancel/replace endpoint; futures only modify in
+    /// place under the same client id, so there the cancel and the new order
+    /// are sent one after the other. Either way the replacement is rejected
+    /// locally if the cancel fails.
+    pub async fn amend(&mut self, cancel: &OrderCancel, replacement: Option<&OrderEntry>) -> Result<(), GatewayError> {
+        let Some(entry) = replacement else {
+            return self.cancel(cancel).await;
+        };
+        if self.config.market == BinanceMarket::Spot && entry.symbol_id == cancel.symbol_id {
+            let params = format!(
+                "symbol={}&cancelReplaceMode=STOP_ON_FAILURE&cancelOrigClientOrderId={}&{}",
+                self.symbol_name(entry.symbol_id)?,
+                cancel.cl_ord_id,
+                order_params(entry)
+            );
+            let result = self.signed("POST", "/api/v3/order/cancelReplace", &params).await;
+            if let Err(e) = &result {
+                self.reject(entry, e);
+            }
+            return result.map(|_| ());
+        }
+        if let Err(e) = self.cancel(cancel).await {
+            self.reject(entry, &e);
+            return Err(e);
+        }
+        self.place(entry).await
+    }
+
+    /// Open a listen key and forward the user-data stream's order events as
+    /// execution reports. The stream task keeps the key alive.
+    pub async fn start_user_stream(&mut self) -> Result<(), GatewayError> {
+        self.weights.acquire(local_ms(), LISTEN_KEY_WEIGHT)?;
+        let path = self.config.market.listen_key_path();
+        let key: ListenKey = parse_body(&self.call("POST", path).await?)?;
+        let url = format!("{}/ws/{}", self.config.ws_url.trim_end_matches('/'), key.listen_key);
+        let (ws_stream, _) = connect_async(url.as_str())
+            .await
+            .map_err(|e| GatewayError::Protocol(format!("user stream connect failed: {}", e)))?;
+        info!("Binance user stream connected");
+
+        let keepalive_target = match self.config.market {
+            BinanceMarket::Spot => format!("{}?listenKey={}", path, key.listen_key),
+            BinanceMarket::UsdmFutures => path.to_string(),
+        };
+        let endpoint = self.endpoint.clone();
+        let api_key = self.credentials.api_key().to_string();
+        let symbols = self.config.symbols.clone();
+        let reports = self.reports_tx.clone();
+
+        if let Some(old) = self.user_stream.take() {
+            old.abort();
+        }
+        self.user_stream = Some(tokio::spawn(async move {
+            let (mut write, mut read) = ws_stream.split();
+            let mut keepalive = interval_at(Instant::now() + KEEPALIVE_PERIOD, KEEPALIVE_PERIOD);
+            loop {
+                tokio::select! {
+                    msg = read.next() => match msg {
+                        Some(Ok(Message::Text(text))) => {
+                            if let Some(report) = parse_user_event(&text, &symbols) {
+                                if reports.send(Ok(report)).is_err() {
+                                    break;
+                                }
+                            }
+                        }
+                        Some(Ok(Message::Ping(payload))) => {
+                            let _ = write.send(Message::Pong(payload)).await;
+                        }
+                        Some(Ok(Message::Close(_))) | None => {
+                            warn!("Binance user stream closed");
+                            let _ = reports.send(Err(GatewayError::Closed));
+                            break;
+                        }
+                        Some(Ok(_)) => {}
+                        Some(Err(e)) => {
+                            warn!("Binance user stream error: {}", e);
+                            let _ = reports.send(Err(GatewayError::Closed));
+                            break;
+                        }
+                    },
+                    _ = keepalive.tick() => {
+                        let headers = [("X-MBX-APIKEY", api_key.as_str())];
+                        match http::request(&endpoint, "PUT", &keepalive_target, &headers).await {
+                            Ok(response) if response.status < 400 => {}
+                            Ok(response) => warn!("Listen key keepalive refused: HTTP {}", response.status),
+                            Err(e) => warn!("Listen key keepalive failed: {}", e),
+                        }
+                    }
+                }
+            }
+        }));
+        Ok(())
+    }
+
+    fn symbol_name(&self, symbol_id: u32) -> Result<&str, GatewayError> {
+        self.config
+            .symbols
+            .iter()
+            .find(|s| s.symbol_id == symbol_id)
+            .map(|s| s.name.as_str())
+            .ok_or_else(|| GatewayError::Config(format!("no Binance symbol for symbol id {}", symbol_id)))
+    }
+
+    /// Signed request: `timestamp` and `recvWindow` appended to `params`,
+    /// then the HMAC-SHA256 of the whole query string as `signature`
+    async fn signed(&mut self, method: &str, path: &str, params: &str) -> Result<Response, GatewayError> {
+        let now = local_ms();
+        self.weights.acquire(now, ORDER_WEIGHT)?;
+        let timestamp = (now as i64 + self.time_offset) as u64;
+        let query = format!("{}&recvWindow={}&timestamp={}", params, self.config.recv_window, timestamp);
+        let signature = self.credentials.sign(&query);
+        self.call(method, &format!("{}?{}&signature={}", path, query, signature)).await
+    }
+
+    async fn call(&mut self, method: &str, target: &str) -> Result<Response, GatewayError> {
+        let headers = [("X-MBX-APIKEY", self.credentials.api_key())];
+        let response = http::request(&self.endpoint, method, target, &headers).await?;
+        let used = response.header("X-MBX-USED-WEIGHT-1M").and_then(|v| v.parse().ok());
+        let retry_after = response.header("Retry-After").and_then(|v| v.parse().ok());
+        self.weights.on_response(local_ms(), response.status, used, retry_after);
+
+        match response.status {
+            429 | 418 => Err(GatewayError::RateLimited { retry_after_ms: retry_after.map_or(60_000, |s| s * 1_000) }),
+            status if status >= 400 => {
+                let error: ErrorBody = serde_json::from_str(&response.body)
+                    .unwrap_or(ErrorBody { code: 0, msg: response.body.clone() });
+                Err(GatewayError::Venue { status, code: error.code, message: error.msg })
+            }
+            _ => Ok(response),
+        }
+    }
+
+    /// Report an order that never reached the book. Transport errors are
+    /// left alone: the order may or may not be live.
+    fn reject(&self, entry: &OrderEntry, error: &GatewayError) {
+        if !matches!(error, GatewayError::Venue { .. } | GatewayError::RateLimited { .. } | GatewayError::Config(_)) {
+            return;
+        }
+        warn!("Binance refused order {}: {}", entry.cl_ord_id, error);
+        let _ = self.reports_tx.send(Ok(ExecutionReport {
+            timestamp: from_millis(local_ms()),
+            cl_ord_id: entry.cl_ord_id,
+            exec_id: 0,
+            exchange_id: entry.exchange_id,
+            symbol_id: entry.symbol_id,
+            side: entry.side,
+            filled_quantity: 0,
+            filled_price: 0,
+            leaves_quantity: 0,
+            fee: 0,
+            status: OrdStatus::Rejected,
+        }));
+    }
+}
+
+impl Drop for BinanceClient {
+    fn drop(&mut self) {
+        if let Some(task) = self.user_stream.take() {
+            task.abort();
+        }
+    }
+}
+
+impl OrderGateway for BinanceClient {
+    async fn send(&mut self, action: &OrderAction) -> Result<(), GatewayError> {
+        match action {
+            OrderAction::Submit(entry) => self.place(entry).await,
+            OrderAction::Cancel(cancel) => self.cancel(cancel).await,
+            OrderAction::Amend { cancel, replacement } => self.amend(cancel, replacement.as_ref()).await,
+        }
+    }
+
+    async fn next_report(&mut self) -> Result<ExecutionReport, GatewayError> {
+        self.reports_rx.recv().await.unwrap_or(Err(GatewayError::Closed))
+    }
+}
+
+/// Order fields after `symbol`, shared by new orders and cancel/replace
+fn order_params(entry: &OrderEntry) -> String {
+    let side = match entry.side {
+        Side::Buy => "BUY",
+        Side::Sell => "SELL",
+    };
+    let mut params = match entry.order_type {
+        OrdType::Market => format!("side={}&type=MARKET", side),
+        OrdType::Limit => {
+            let time_in_force = match entry.time_in_force {
+                TimeInForce::Gtc => "GTC",
+                TimeInForce::Ioc => "IOC",
+            };
+            format!(
+                "side={}&type=LIMIT&timeInForce={}&price={}",
+                side,
+                time_in_force,
+                format_fixed(entry.price)
+            )
+        }
+    };
+    params.push_str(&format!(
+        "&quantity={}&newClientOrderId={}&newOrderRespType=ACK",
+        format_fixed(entry.quantity as i64),
+        entry.cl_ord_id
+    ));
+    params
+}
+
+fn parse_body<'a, T: Deserialize<'a>>(response: &'a Response) -> Result<T, GatewayError> {
+    serde_json::from_str(&response.body)
+        .map_err(|e| GatewayError::Protocol(format!("unexpected response {}: {}", response.body, e)))
+}
+
+fn local_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_millis() as u64)
+}
@@ -0,0 +1,95 @@
+use std::fmt;
+use std::path::Path;
+
+use crate::error::GatewayError;
+
+use super::sign::sign_query;
+
+/// API key and secret, loaded at startup from the environment or a key file.
+/// Never compiled in, and never printed: `Debug` redacts the secret.
+#[derive(Clone)]
+pub struct Credentials {
+    api_key: String,
+    secret: String,
+}
+
+impl Credentials {
+    pub const API_KEY_VAR: &'static str = "BINANCE_API_KEY";
+    pub const SECRET_VAR: &'static str = "BINANCE_API_SECRET";
+    /// Path of a key file, used when the key variables are not set
+    pub const FILE_VAR: &'static str = "BINANCE_CREDENTIALS_FILE";
+
+    pub fn new(api_key: impl Into<String>, secret: impl Into<String>) -> Self {
+        Self { api_key: api_key.into(), secret: secret.into() }
+    }
+
+    /// `BINANCE_API_KEY`/`BINANCE_API_SECRET`, or else the file named by
+    /// `BINANCE_CREDENTIALS_FILE`
+    pub fn load() -> Result<Self, GatewayError> {
+        if let (Ok(api_key), Ok(secret)) = (std::env::var(Self::API_KEY_VAR), std::env::var(Self::SECRET_VAR)) {
+            return Ok(Self::new(api_key, secret));
+        }
+        match std::env::var(Self::FILE_VAR) {
+            Ok(path) => Self::from_file(path),
+            Err(_) => Err(GatewayError::Config(format!(
+                "set {} and {}, or {}",
+                Self::API_KEY_VAR,
+                Self::SECRET_VAR,
+                Self::FILE_VAR
+            ))),
+        }
+    }
+
+    /// Key file with `api_key = ...` and `secret = ...` lines; blank lines and
+    /// `#` comments are ignored
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, GatewayError> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| GatewayError::Config(format!("cannot read {}: {}", path.display(), e)))?;
+        Self::parse(&text).ok_or_else(|| {
+            GatewayError::Config(format!("{} must define api_key and secret", path.display()))
+        })
+    }
+
+    fn parse(text: &str) -> Option<Self> {
+        let (mut api_key, mut secret) = (None, None);
+        for line in text.lines().map(str::trim).filter(|l| !l.is_empty() && !l.starts_with('#')) {
+            let (name, value) = line.split_once('=')?;
+            let value = value.trim().trim_matches('"').to_string();
+            match name.trim() {
+                "api_key" => api_key = Some(value),
+                "secret" => secret = Some(value),
+                _ => {}
+            }
+        }
+        Some(Self::new(api_key.filter(|k| !k.is_empty())?, secret.filter(|s| !s.is_empty())?))
+    }
+
+    pub fn api_key(&self) -> &str {
+        &self.api_key
+    }
+
+    pub(crate) fn sign(&self, query: &str) -> String {
+        sign_query(&self.secret, query)
+    }
+}
+
+impl fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Credentials").field("api_key", &self.api_key).field("secret", &"<redacted>").finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_key_file() {
+        let credentials = Credentials::parse("# testnet\napi_key = abc\n\nsecret = \"s3cr3t\"\n").unwrap();
+        assert_eq!(credentials.api_key(), "abc");
+        assert_eq!(credentials.secret, "s3cr3t");
+        assert!(!format!("{:?}", credentials).contains("s3cr3t"));
+        assert!(Credentials::parse("api_key = abc\n").is_none());
+    }
+}
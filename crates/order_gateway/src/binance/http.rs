@@ -0,0 +1,74 @@
+//! REST transport: a pooled `reqwest` client bound to the API base URL.
+
+use reqwest::header::HeaderMap;
+use reqwest::{Client, Method, Url};
+
+use crate::error::GatewayError;
+
+/// Base URL of the REST API, such as `https://api.binance.com`, with the
+/// client whose connections are reused across requests. Clones share the pool.
+#[derive(Debug, Clone)]
+pub(crate) struct Endpoint {
+    base: Url,
+    client: Client,
+}
+
+impl Endpoint {
+    pub fn parse(url: &str) -> Result<Self, GatewayError> {
+        let base = Url::parse(url).map_err(|e| GatewayError::Config(format!("bad REST url {}: {}", url, e)))?;
+        if !matches!(base.scheme(), "http" | "https") {
+            return Err(GatewayError::Config(format!("unsupported REST url {}", url)));
+        }
+        let client = Client::builder().tcp_nodelay(true).build().map_err(|e| GatewayError::Config(e.to_string()))?;
+        Ok(Self { base, client })
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct Response {
+    pub status: u16,
+    pub headers: HeaderMap,
+    pub body: String,
+}
+
+impl Response {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).and_then(|v| v.to_str().ok())
+    }
+}
+
+/// Send `method path_and_query` with an empty body
+pub(crate) async fn request(
+    endpoint: &Endpoint,
+    method: &str,
+    path_and_query: &str,
+    headers: &[(&str, &str)],
+) -> Result<Response, GatewayError> {
+    let method = Method::from_bytes(method.as_bytes()).map_err(|e| GatewayError::Config(e.to_string()))?;
+    let url = endpoint
+        .base
+        .join(path_and_query)
+        .map_err(|e| GatewayError::Config(format!("bad request target {}: {}", path_and_query, e)))?;
+    let mut request = endpoint.client.request(method, url);
+    for (name, value) in headers {
+        request = request.header(*name, *value);
+    }
+
+    let response = request.send().await?;
+    let status = response.status().as_u16();
+    let headers = response.headers().clone();
+    let body = response.text().await?;
+    Ok(Response { status, headers, body })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_endpoint_accepts_http_only() {
+        assert!(Endpoint::parse("http://127.0.0.1:8081/").is_ok());
+        assert!(Endpoint::parse("https://api.binance.com").is_ok());
+        assert!(matches!(Endpoint::parse("wss://stream.binance.com"), Err(GatewayError::Config(_))));
+    }
+}
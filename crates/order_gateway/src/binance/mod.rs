@@ -0,0 +1,15 @@
+//! Binance spot and USDⓈ-M futures order entry over signed REST, with
+//! execution reports from the user-data stream.
+
+mod client;
+mod credentials;
+mod http;
+mod rate_limit;
+mod sign;
+mod user_stream;
+
+pub use client::{BinanceClient, BinanceConfig, BinanceMarket, BinanceSymbol};
+pub use credentials::Credentials;
+pub use rate_limit::WeightTracker;
+pub use sign::{hmac_sha256, sign_query};
+pub use user_stream::parse_user_event;
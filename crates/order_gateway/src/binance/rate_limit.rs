@@ -0,0 +1,85 @@
+use crate::error::GatewayError;
+
+const MINUTE_MS: u64 = 60_000;
+
+/// Request weight used in the venue's current one-minute window.
+///
+/// Binance counts weight per IP over calendar minutes and reports the total
+/// in `X-MBX-USED-WEIGHT-1M`. We add each request's weight before sending,
+/// resync on every response, and refuse to send once the limit would be
+/// exceeded or while a 429/418 back-off is in force.
+#[derive(Debug, Clone)]
+pub struct WeightTracker {
+    limit: u32,
+    used: u32,
+    minute: u64,
+    blocked_until: u64,
+}
+
+impl WeightTracker {
+    pub fn new(limit: u32) -> Self {
+        Self { limit, used: 0, minute: 0, blocked_until: 0 }
+    }
+
+    pub fn limit(&self) -> u32 {
+        self.limit
+    }
+
+    /// Weight used in the minute of the last request or response
+    pub fn used(&self) -> u32 {
+        self.used
+    }
+
+    /// Reserve `weight` for a request sent at `now` (ms)
+    pub fn acquire(&mut self, now: u64, weight: u32) -> Result<(), GatewayError> {
+        if now < self.blocked_until {
+            return Err(GatewayError::RateLimited { retry_after_ms: self.blocked_until - now });
+        }
+        self.roll(now);
+        if self.used + weight > self.limit {
+            let retry_after_ms = (self.minute + 1) * MINUTE_MS - now;
+            return Err(GatewayError::RateLimited { retry_after_ms });
+        }
+        self.used += weight;
+        Ok(())
+    }
+
+    /// Resync from a response: the venue's own count, and the back-off it
+    /// asks for on 429 (too many requests) or 418 (IP banned)
+    pub fn on_response(&mut self, now: u64, status: u16, used: Option<u32>, retry_after_secs: Option<u64>) {
+        self.roll(now);
+        if let Some(used) = used {
+            self.used = used;
+        }
+        if status == 429 || status == 418 {
+            let back_off = retry_after_secs.map_or((self.minute + 1) * MINUTE_MS - now, |secs| secs * 1_000);
+            self.blocked_until = self.blocked_until.max(now + back_off);
+        }
+    }
+
+    fn roll(&mut self, now: u64) {
+        let minute = now / MINUTE_MS;
+        if minute != self.minute {
+            self.minute = minute;
+            self.used = 0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_limit_and_back_off() {
+        let mut weights = WeightTracker::new(10);
+        assert!(weights.acquire(1_000, 4).is_ok());
+        weights.on_response(1_010, 200, Some(9), None);
+        assert!(matches!(weights.acquire(1_020, 2), Err(GatewayError::RateLimited { retry_after_ms: 58_980 })));
+        assert!(weights.acquire(60_000, 2).is_ok());
+
+        weights.on_response(61_000, 429, Some(10), Some(3));
+        assert!(matches!(weights.acquire(62_000, 1), Err(GatewayError::RateLimited { retry_after_ms: 2_000 })));
+        assert!(weights.acquire(124_000, 1).is_ok());
+    }
+}
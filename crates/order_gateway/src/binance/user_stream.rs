@@ -0,0 +1,151 @@
+//! User-data stream events mapped onto `ExecutionReport`.
+
+use serde::Deserialize;
+use vibe_hft_core::{from_millis, parse_fixed};
+use vibe_hft_sbe_messages::{ExchangeID, ExecutionReport, OrdStatus, Side};
+
+use super::client::BinanceSymbol;
+
+/// Order fields shared by spot `executionReport` and the `o` object of
+/// futures `ORDER_TRADE_UPDATE`
+#[derive(Debug, Deserialize)]
+struct OrderUpdate {
+    #[serde(rename = "s")]
+    symbol: String,
+    #[serde(rename = "c")]
+    client_order_id: String,
+    /// Spot only: the original order's id when `c` belongs to a cancel request
+    #[serde(rename = "C", default)]
+    orig_client_order_id: String,
+    #[serde(rename = "S")]
+    side: String,
+    #[serde(rename = "x")]
+    execution_type: String,
+    #[serde(rename = "X")]
+    status: String,
+    #[serde(rename = "q")]
+    quantity: String,
+    #[serde(rename = "l")]
+    last_quantity: String,
+    #[serde(rename = "z")]
+    cumulative_quantity: String,
+    #[serde(rename = "L")]
+    last_price: String,
+    #[serde(rename = "n", default)]
+    commission: Option<String>,
+    #[serde(rename = "N", default)]
+    commission_asset: Option<String>,
+    #[serde(rename = "t")]
+    trade_id: i64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "e")]
+enum UserEvent {
+    #[serde(rename = "executionReport")]
+    Spot {
+        #[serde(rename = "E")]
+        event_time: u64,
+        #[serde(flatten)]
+        order: OrderUpdate,
+    },
+    #[serde(rename = "ORDER_TRADE_UPDATE")]
+    Futures {
+        #[serde(rename = "E")]
+        event_time: u64,
+        #[serde(rename = "o")]
+        order: OrderUpdate,
+    },
+    #[serde(other)]
+    Other,
+}
+
+/// Map one user-data message to a report. Account and balance events, orders
+/// on unknown symbols and orders whose client id is not one of ours (a
+/// `cl_ord_id`) give `None`.
+///
+/// The report timestamp is the venue event time in ns, like the gateway's
+/// market data. Fees are converted to quote units when charged in the
+/// symbol's base or quote asset; other assets (BNB discounts) report 0.
+pub fn parse_user_event(text: &str, symbols: &[BinanceSymbol]) -> Option<ExecutionReport> {
+    let (event_time, order) = match serde_json::from_str(text).ok()? {
+        UserEvent::Spot { event_time, order } | UserEvent::Futures { event_time, order } => (event_time, order),
+        UserEvent::Other => return None,
+    };
+    let symbol = symbols.iter().find(|s| s.name == order.symbol)?;
+    let client_order_id =
+        if order.orig_client_order_id.is_empty() { &order.client_order_id } else { &order.orig_client_order_id };
+    let cl_ord_id = client_order_id.parse().ok()?;
+
+    let status = match order.status.as_str() {
+        "NEW" => OrdStatus::New,
+        "PARTIALLY_FILLED" => OrdStatus::PartiallyFilled,
+        "FILLED" => OrdStatus::Filled,
+        "CANCELED" | "EXPIRED" | "EXPIRED_IN_MATCH" => OrdStatus::Canceled,
+        "REJECTED" => OrdStatus::Rejected,
+        _ => return None,
+    };
+    let side = match order.side.as_str() {
+        "BUY" => Side::Buy,
+        "SELL" => Side::Sell,
+        _ => return None,
+    };
+
+    let filled_quantity = parse_fixed(&order.last_quantity)? as u64;
+    let filled_price = parse_fixed(&order.last_price)?;
+    let leaves_quantity = match status {
+        OrdStatus::New | OrdStatus::PartiallyFilled => {
+            (parse_fixed(&order.quantity)? - parse_fixed(&order.cumulative_quantity)?).max(0) as u64
+        }
+        _ => 0,
+    };
+    let commission = order.commission.as_deref().and_then(parse_fixed).unwrap_or(0);
+    let fee = match order.commission_asset.as_deref() {
+        Some(asset) if asset == symbol.quote => commission,
+        Some(asset) if asset == symbol.base => (commission as i128 * filled_price as i128 / 100_000_000) as i64,
+        _ => 0,
+    };
+    let is_trade = order.execution_type == "TRADE" && order.trade_id >= 0;
+
+    Some(ExecutionReport {
+        timestamp: from_millis(event_time),
+        cl_ord_id,
+        exec_id: if is_trade { order.trade_id as u64 } else { 0 },
+        exchange_id: ExchangeID::Binance,
+        symbol_id: symbol.symbol_id,
+        side,
+        filled_quantity,
+        filled_price,
+        leaves_quantity,
+        fee,
+        status,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn symbols() -> Vec<BinanceSymbol> {
+        vec![BinanceSymbol::new(1, "BTCUSDT", "BTC", "USDT")]
+    }
+
+    #[test]
+    fn test_spot_fill_and_cancel() {
+        let fill = r#"{"e":"executionReport","E":1700000000123,"s":"BTCUSDT","c":"42","S":"BUY","o":"LIMIT","f":"GTC","q":"0.50000000","p":"30000.00000000","C":"","x":"TRADE","X":"PARTIALLY_FILLED","r":"NONE","i":8,"l":"0.20000000","z":"0.20000000","L":"30000.00000000","n":"0.00020000","N":"BTC","T":1700000000120,"t":77,"w":false,"m":true}"#;
+        let report = parse_user_event(fill, &symbols()).unwrap();
+        assert_eq!(report.cl_ord_id, 42);
+        assert_eq!(report.exec_id, 77);
+        assert_eq!((report.filled_quantity, report.leaves_quantity), (20_000_000, 30_000_000));
+        // 0.0002 BTC at 30000 is 6 USDT
+        assert_eq!(report.fee, 600_000_000);
+        assert_eq!(report.status, OrdStatus::PartiallyFilled);
+
+        let cancel = r#"{"e":"executionReport","E":1700000000200,"s":"BTCUSDT","c":"web_x1","S":"BUY","q":"0.50000000","C":"42","x":"CANCELED","X":"CANCELED","l":"0.00000000","z":"0.20000000","L":"0.00000000","n":"0","N":null,"t":-1}"#;
+        let report = parse_user_event(cancel, &symbols()).unwrap();
+        assert_eq!((report.cl_ord_id, report.exec_id, report.leaves_quantity), (42, 0, 0));
+        assert_eq!(report.status, OrdStatus::Canceled);
+
+        assert!(parse_user_event(r#"{"e":"outboundAccountPosition","E":1}"#, &symbols()).is_none());
+    }
+}
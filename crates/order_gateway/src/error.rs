@@ -0,0 +1,52 @@
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+pub enum GatewayError {
+    Io(io::Error),
+    /// A REST request that did not complete: connect, TLS or transfer
+    Http(reqwest::Error),
+    /// The venue closed the session
+    Closed,
+    /// A frame that is not from our schema, or does not decode
+    Malformed { template_id: u16 },
+    /// The venue refused a request with an error code
+    Venue { status: u16, code: i64, message: String },
+    /// Request weight exhausted, or the venue asked us to back off
+    RateLimited { retry_after_ms: u64 },
+    /// An HTTP response or stream message we could not make sense of
+    Protocol(String),
+    /// Missing or invalid credentials or endpoint configuration
+    Config(String),
+}
+
+impl fmt::Display for GatewayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GatewayError::Io(e) => write!(f, "i/o error: {}", e),
+            GatewayError::Http(e) => write!(f, "http error: {}", e),
+            GatewayError::Closed => write!(f, "session closed"),
+            GatewayError::Malformed { template_id } => write!(f, "malformed frame (template {})", template_id),
+            GatewayError::Venue { status, code, message } => {
+                write!(f, "venue error {} (HTTP {}): {}", code, status, message)
+            }
+            GatewayError::RateLimited { retry_after_ms } => write!(f, "rate limited, retry in {} ms", retry_after_ms),
+            GatewayError::Protocol(message) => write!(f, "protocol error: {}", message),
+            GatewayError::Config(message) => write!(f, "configuration error: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for GatewayError {}
+
+impl From<io::Error> for GatewayError {
+    fn from(e: io::Error) -> Self {
+        GatewayError::Io(e)
+    }
+}
+
+impl From<reqwest::Error> for GatewayError {
+    fn from(e: reqwest::Error) -> Self {
+        GatewayError::Http(e)
+    }
+}
@@ -0,0 +1,59 @@
+use tokio::io::{AsyncRead, AsyncReadExt};
+use vibe_hft_sbe_messages::{ExecutionReport, MarketDataUpdate, MessageHeader, Trade};
+
+use crate::error::GatewayError;
+
+/// Reads SBE frames off a byte stream.
+///
+/// Bytes are buffered until a whole frame is available, so `next` is cancel
+/// safe and can sit in a `select!` next to other sources.
+pub struct FrameReader<R> {
+    inner: R,
+    buf: Vec<u8>,
+}
+
+impl<R: AsyncRead + Unpin> FrameReader<R> {
+    pub fn new(inner: R) -> Self {
+        Self { inner, buf: Vec::with_capacity(4096) }
+    }
+
+    /// Next frame, decoded by `decode`. `GatewayError::Closed` once the peer
+    /// has closed the stream between frames.
+    pub async fn next<T>(&mut self, decode: impl Fn(&[u8]) -> Option<T>) -> Result<T, GatewayError> {
+        loop {
+            if self.buf.len() >= MessageHeader::ENCODED_LENGTH {
+                let block_length = u16::from_le_bytes([self.buf[0], self.buf[1]]) as usize;
+                let frame_length = MessageHeader::ENCODED_LENGTH + block_length;
+                if self.buf.len() >= frame_length {
+                    let frame = &self.buf[..frame_length];
+                    let template_id = u16::from_le_bytes([frame[2], frame[3]]);
+                    let decoded = decode(frame);
+                    self.buf.drain(..frame_length);
+                    return decoded.ok_or(GatewayError::Malformed { template_id });
+                }
+            }
+            if self.inner.read_buf(&mut self.buf).await? == 0 {
+                return Err(GatewayError::Closed);
+            }
+        }
+    }
+}
+
+/// Everything a venue session can send us
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VenueEvent {
+    Book(MarketDataUpdate),
+    Trade(Trade),
+    Report(ExecutionReport),
+}
+
+impl VenueEvent {
+    pub fn decode(frame: &[u8]) -> Option<Self> {
+        match MessageHeader::read(frame)?.template_id {
+            MarketDataUpdate::TEMPLATE_ID => MarketDataUpdate::from_frame(frame).map(VenueEvent::Book),
+            Trade::TEMPLATE_ID => Trade::from_bytes(frame).map(VenueEvent::Trade),
+            ExecutionReport::TEMPLATE_ID => ExecutionReport::from_bytes(frame).map(VenueEvent::Report),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,32 @@
+// Order entry: the adapter trait between the order path and a venue
+// protocol, a local mock venue to run the full order lifecycle against, and
+// the Binance REST/user-data stream client.
+// Network code, so std and tokio are allowed here.
+
+use std::future::Future;
+
+use vibe_hft_sbe_messages::ExecutionReport;
+use vibe_hft_strategy::OrderAction;
+
+pub mod binance;
+pub mod error;
+pub mod frame;
+pub mod mock;
+
+pub use error::GatewayError;
+pub use frame::{FrameReader, VenueEvent};
+pub use mock::{MockExchange, MockExchangeConfig, MockExchangeHandle, MockGateway, ReplayPace};
+
+/// Order entry session with one venue.
+///
+/// Implementations translate our `OrderAction`s into the venue's protocol and
+/// the venue's responses back into `ExecutionReport`s, so the host and OMS
+/// never see venue-specific messages.
+pub trait OrderGateway {
+    /// Send an order, cancel or cancel/replace. Venues without a native
+    /// amend receive the cancel followed by the replacement.
+    fn send(&mut self, action: &OrderAction) -> impl Future<Output = Result<(), GatewayError>> + Send;
+
+    /// Next execution report from the venue
+    fn next_report(&mut self) -> impl Future<Output = Result<ExecutionReport, GatewayError>> + Send;
+}
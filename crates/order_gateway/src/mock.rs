@@ -0,0 +1,213 @@
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use log::{info, warn};
+use tokio::io::AsyncWriteExt;
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::{TcpListener, TcpStream, ToSocketAddrs};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio::time::{sleep_until, Instant};
+use vibe_hft_backtest::{ExchangeConfig, SimulatedExchange, TapeEvent};
+use vibe_hft_sbe_messages::{ExchangeID, ExecutionReport, MessageHeader, OrderCancel, OrderEntry};
+use vibe_hft_strategy::OrderAction;
+
+use crate::error::GatewayError;
+use crate::frame::{FrameReader, VenueEvent};
+use crate::OrderGateway;
+
+/// How fast the mock exchange replays its tape
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReplayPace {
+    /// Send the whole tape at once, then trade against the resulting book
+    Instant,
+    /// Follow the tape's timestamps, `speed` times faster than recorded
+    RealTime { speed: f64 },
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct MockExchangeConfig {
+    pub exchange_id: ExchangeID,
+    pub symbol_id: u32,
+    pub exchange: ExchangeConfig,
+    pub pace: ReplayPace,
+}
+
+/// Local venue for integration tests of the order path.
+///
+/// Each TCP session gets a fresh `SimulatedExchange` that replays the tape,
+/// forwards every book update and trade to the client, and matches the
+/// client's orders against the replayed book. Everything on the wire is SBE
+/// framed: `OrderEntry` and `OrderCancel` in, `MarketDataUpdate`, `Trade`
+/// and `ExecutionReport` out. Sessions are served one at a time.
+pub struct MockExchange;
+
+impl MockExchange {
+    /// Listen on `addr` (port 0 picks a free one) and serve in the background
+    pub async fn start(
+        addr: impl ToSocketAddrs,
+        config: MockExchangeConfig,
+        tape: Vec<TapeEvent>,
+    ) -> Result<MockExchangeHandle, GatewayError> {
+        let listener = TcpListener::bind(addr).await?;
+        let addr = listener.local_addr()?;
+        let (inject, mut injected) = mpsc::unbounded_channel();
+
+        let task = tokio::spawn(async move {
+            while let Ok((stream, peer)) = listener.accept().await {
+                info!("Mock exchange session from {}", peer);
+                match session(stream, &config, &tape, &mut injected).await {
+                    Ok(()) | Err(GatewayError::Closed) => info!("Mock exchange session from {} closed", peer),
+                    Err(e) => warn!("Mock exchange session from {} failed: {}", peer, e),
+                }
+            }
+        });
+        Ok(MockExchangeHandle { addr, inject, task })
+    }
+}
+
+/// Running mock exchange; stops when dropped.
+pub struct MockExchangeHandle {
+    addr: SocketAddr,
+    inject: mpsc::UnboundedSender<TapeEvent>,
+    task: JoinHandle<()>,
+}
+
+impl MockExchangeHandle {
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Play one more market event into the current session, after the tape
+    pub fn inject(&self, event: TapeEvent) {
+        let _ = self.inject.send(event);
+    }
+}
+
+impl Drop for MockExchangeHandle {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+enum ClientMessage {
+    Order(OrderEntry),
+    Cancel(OrderCancel),
+}
+
+impl ClientMessage {
+    fn decode(frame: &[u8]) -> Option<Self> {
+        match MessageHeader::read(frame)?.template_id {
+            OrderEntry::TEMPLATE_ID => OrderEntry::from_bytes(frame).map(ClientMessage::Order),
+            OrderCancel::TEMPLATE_ID => OrderCancel::from_bytes(frame).map(ClientMessage::Cancel),
+            _ => None,
+        }
+    }
+}
+
+async fn session(
+    stream: TcpStream,
+    config: &MockExchangeConfig,
+    tape: &[TapeEvent],
+    injected: &mut mpsc::UnboundedReceiver<TapeEvent>,
+) -> Result<(), GatewayError> {
+    stream.set_nodelay(true)?;
+    let (read, mut write) = stream.into_split();
+    let mut reader = FrameReader::new(read);
+    let mut exchange = SimulatedExchange::new(config.exchange_id, config.symbol_id, config.exchange);
+
+    let started = Instant::now();
+    let tape_start = tape.first().map_or(0, TapeEvent::timestamp);
+    let mut replay = tape.iter();
+    let mut pending = replay.next();
+
+    loop {
+        let due = match (config.pace, pending) {
+            (ReplayPace::RealTime { speed }, Some(event)) => {
+                let elapsed = (event.timestamp() - tape_start) as f64 / speed;
+                started + Duration::from_nanos(elapsed as u64)
+            }
+            _ => Instant::now(),
+        };
+
+        tokio::select! {
+            biased;
+            message = reader.next(ClientMessage::decode) => match message? {
+                ClientMessage::Order(entry) => exchange.submit(&entry),
+                ClientMessage::Cancel(cancel) => exchange.cancel(&cancel),
+            },
+            Some(event) = injected.recv() => play(&mut exchange, &mut write, &event).await?,
+            _ = sleep_until(due), if pending.is_some() => {
+                if let Some(event) = pending {
+                    play(&mut exchange, &mut write, event).await?;
+                }
+                pending = replay.next();
+            }
+        }
+
+        for report in exchange.drain_reports() {
+            write.write_all(&report.to_bytes()).await?;
+        }
+    }
+}
+
+/// Apply a market event to the venue and forward it to the client
+async fn play(exchange: &mut SimulatedExchange, write: &mut OwnedWriteHalf, event: &TapeEvent) -> Result<(), GatewayError> {
+    match event {
+        TapeEvent::Book(update) => {
+            exchange.on_market_data(update);
+            write.write_all(&update.to_frame()).await?;
+        }
+        TapeEvent::Trade(trade) => {
+            exchange.on_trade(trade);
+            write.write_all(&trade.to_bytes()).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Client side of a mock exchange session. Besides order entry it carries
+/// the venue's market data, read with `next_event`.
+pub struct MockGateway {
+    reader: FrameReader<OwnedReadHalf>,
+    writer: OwnedWriteHalf,
+}
+
+impl MockGateway {
+    pub async fn connect(addr: impl ToSocketAddrs) -> Result<Self, GatewayError> {
+        let stream = TcpStream::connect(addr).await?;
+        stream.set_nodelay(true)?;
+        let (read, writer) = stream.into_split();
+        Ok(Self { reader: FrameReader::new(read), writer })
+    }
+
+    /// Next book update, trade or execution report, in the order the venue sent them
+    pub async fn next_event(&mut self) -> Result<VenueEvent, GatewayError> {
+        self.reader.next(VenueEvent::decode).await
+    }
+}
+
+impl OrderGateway for MockGateway {
+    async fn send(&mut self, action: &OrderAction) -> Result<(), GatewayError> {
+        match action {
+            OrderAction::Submit(entry) => self.writer.write_all(&entry.to_bytes()).await?,
+            OrderAction::Cancel(cancel) => self.writer.write_all(&cancel.to_bytes()).await?,
+            OrderAction::Amend { cancel, replacement } => {
+                self.writer.write_all(&cancel.to_bytes()).await?;
+                if let Some(entry) = replacement {
+                    self.writer.write_all(&entry.to_bytes()).await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Skips the market data interleaved with the reports
+    async fn next_report(&mut self) -> Result<ExecutionReport, GatewayError> {
+        loop {
+            if let VenueEvent::Report(report) = self.next_event().await? {
+                return Ok(report);
+            }
+        }
+    }
+}
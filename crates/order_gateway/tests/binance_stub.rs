@@ -0,0 +1,284 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use futures_util::SinkExt;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tokio_tungstenite::tungstenite::protocol::Message;
+use vibe_hft_order_gateway::binance::{sign_query, BinanceClient, BinanceConfig, BinanceSymbol, Credentials};
+use vibe_hft_order_gateway::{GatewayError, OrderGateway};
+use vibe_hft_sbe_messages::{ExchangeID, OrdStatus, OrdType, OrderCancel, OrderEntry, Side, TimeInForce};
+use vibe_hft_strategy::OrderAction;
+
+const API_KEY: &str = "stub-api-key";
+const SECRET: &str = "stub-secret";
+const LISTEN_KEY: &str = "pqia91ma19a5s61cv6a81va65sdf19v8a65a1a5s61cv6a81va65sdf19v8a65a1";
+
+/// Recorded response for one request: status, used weight header, body
+struct Reply {
+    status: u16,
+    used_weight: u32,
+    body: &'static str,
+    chunked: bool,
+}
+
+impl Reply {
+    fn ok(used_weight: u32, body: &'static str) -> Self {
+        Self { status: 200, used_weight, body, chunked: false }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Recorded {
+    method: String,
+    path: String,
+    query: String,
+    api_key: Option<String>,
+}
+
+impl Recorded {
+    fn param(&self, name: &str) -> Option<&str> {
+        self.query.split('&').filter_map(|p| p.split_once('=')).find(|(n, _)| *n == name).map(|(_, v)| v)
+    }
+
+    /// The signature must be the HMAC of everything before it
+    fn assert_signed(&self) {
+        let (payload, signature) = self.query.rsplit_once("&signature=").expect("unsigned request");
+        assert_eq!(signature, sign_query(SECRET, payload));
+        assert!(self.param("timestamp").is_some());
+        assert_eq!(self.param("recvWindow"), Some("5000"));
+    }
+}
+
+type Log = Arc<Mutex<Vec<Recorded>>>;
+
+/// Local REST stub: replies in order to each request, and records them
+async fn start_rest(replies: Vec<Reply>) -> (String, Log) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let log: Log = Arc::default();
+    let recorded = log.clone();
+    let mut replies = VecDeque::from(replies);
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let mut raw = Vec::new();
+            let mut buf = [0u8; 1024];
+            while !raw.windows(4).any(|w| w == b"\r\n\r\n") {
+                let n = stream.read(&mut buf).await.unwrap();
+                raw.extend_from_slice(&buf[..n]);
+            }
+            let head = String::from_utf8(raw).unwrap();
+            let mut lines = head.lines();
+            let mut request_line = lines.next().unwrap().split(' ');
+            let method = request_line.next().unwrap().to_string();
+            let target = request_line.next().unwrap();
+            let (path, query) = target.split_once('?').unwrap_or((target, ""));
+            let api_key = lines
+                .filter_map(|l| l.split_once(": "))
+                .find(|(n, _)| n.eq_ignore_ascii_case("X-MBX-APIKEY"))
+                .map(|(_, v)| v.to_string());
+            recorded.lock().unwrap().push(Recorded {
+                method,
+                path: path.to_string(),
+                query: query.to_string(),
+                api_key,
+            });
+
+            let reply = replies.pop_front().expect("unexpected request");
+            let mut response = format!(
+                "HTTP/1.1 {} Stub\r\nConnection: close\r\nContent-Type: application/json\r\nX-MBX-USED-WEIGHT-1M: {}\r\n",
+                reply.status, reply.used_weight
+            );
+            if reply.status == 429 {
+                response.push_str("Retry-After: 30\r\n");
+            }
+            if reply.chunked {
+                let (a, b) = reply.body.split_at(reply.body.len() / 2);
+                response.push_str(&format!(
+                    "Transfer-Encoding: chunked\r\n\r\n{:x}\r\n{}\r\n{:x}\r\n{}\r\n0\r\n\r\n",
+                    a.len(),
+                    a,
+                    b.len(),
+                    b
+                ));
+            } else {
+                response.push_str(&format!("Content-Length: {}\r\n\r\n{}", reply.body.len(), reply.body));
+            }
+            stream.write_all(response.as_bytes()).await.unwrap();
+        }
+    });
+    (url, log)
+}
+
+#[allow(clippy::result_large_err)]
+fn check_path(request: &Request, response: Response) -> Result<Response, ErrorResponse> {
+    assert_eq!(request.uri().path(), format!("/ws/{}", LISTEN_KEY));
+    Ok(response)
+}
+
+/// Local user-data stream: checks the listen key in the path, then plays
+/// the recorded events and closes
+async fn start_ws(events: &'static str) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut ws = tokio_tungstenite::accept_hdr_async(stream, check_path).await.unwrap();
+        for line in events.lines() {
+            ws.send(Message::Text(line.to_string())).await.unwrap();
+        }
+        ws.close(None).await.unwrap();
+    });
+    url
+}
+
+fn symbols() -> Vec<BinanceSymbol> {
+    vec![BinanceSymbol::new(1, "BTCUSDT", "BTC", "USDT")]
+}
+
+fn client(mut config: BinanceConfig, rest_url: String, ws_url: String) -> BinanceClient {
+    config.rest_url = rest_url;
+    config.ws_url = ws_url;
+    BinanceClient::new(config, Credentials::new(API_KEY, SECRET)).unwrap()
+}
+
+fn order(cl_ord_id: u64, price: i64, quantity: u64) -> OrderEntry {
+    OrderEntry {
+        timestamp: 0,
+        cl_ord_id,
+        exchange_id: ExchangeID::Binance,
+        symbol_id: 1,
+        side: Side::Buy,
+        price,
+        quantity,
+        order_type: OrdType::Limit,
+        time_in_force: TimeInForce::Gtc,
+    }
+}
+
+fn cancel(cl_ord_id: u64) -> OrderCancel {
+    OrderCancel { timestamp: 0, cl_ord_id, exchange_id: ExchangeID::Binance, symbol_id: 1, side: Side::Buy }
+}
+
+#[tokio::test]
+async fn test_orders_are_signed_and_weight_tracked() {
+    let (rest, log) = start_rest(vec![
+        Reply::ok(3, include_str!("fixtures/binance/order_ack.json")),
+        Reply::ok(4, include_str!("fixtures/binance/cancel.json")),
+    ])
+    .await;
+    let mut client = client(BinanceConfig::spot(symbols()), rest, String::new());
+
+    client.send(&OrderAction::Submit(order(7, 3_000_050_000_000, 1_000_000))).await.unwrap();
+    assert_eq!(client.weights().used(), 3);
+    client.send(&OrderAction::Cancel(cancel(7))).await.unwrap();
+    assert_eq!(client.weights().used(), 4);
+
+    let log = log.lock().unwrap();
+    let (place, cancel) = (&log[0], &log[1]);
+    for request in [place, cancel] {
+        request.assert_signed();
+        assert_eq!(request.api_key.as_deref(), Some(API_KEY));
+        assert_eq!(request.path, "/api/v3/order");
+        assert_eq!(request.param("symbol"), Some("BTCUSDT"));
+    }
+    assert_eq!(place.method, "POST");
+    assert_eq!(place.param("price"), Some("30000.5"));
+    assert_eq!(place.param("quantity"), Some("0.01"));
+    assert_eq!(place.param("timeInForce"), Some("GTC"));
+    assert_eq!(place.param("newClientOrderId"), Some("7"));
+    assert_eq!(cancel.method, "DELETE");
+    assert_eq!(cancel.param("origClientOrderId"), Some("7"));
+}
+
+#[tokio::test]
+async fn test_refused_orders_come_back_as_rejects() {
+    let (rest, log) = start_rest(vec![
+        Reply { status: 400, used_weight: 1, body: include_str!("fixtures/binance/insufficient_balance.json"), chunked: false },
+        Reply { status: 429, used_weight: 6000, body: include_str!("fixtures/binance/too_many_requests.json"), chunked: false },
+    ])
+    .await;
+    let mut client = client(BinanceConfig::spot(symbols()), rest, String::new());
+
+    let result = client.send(&OrderAction::Submit(order(1, 3_000_000_000_000, 1_000_000))).await;
+    assert!(matches!(result, Err(GatewayError::Venue { status: 400, code: -2010, .. })), "{:?}", result);
+    let report = client.next_report().await.unwrap();
+    assert_eq!((report.cl_ord_id, report.status), (1, OrdStatus::Rejected));
+
+    let result = client.send(&OrderAction::Submit(order(2, 3_000_000_000_000, 1_000_000))).await;
+    assert!(matches!(result, Err(GatewayError::RateLimited { retry_after_ms: 30_000 })), "{:?}", result);
+    assert_eq!(client.next_report().await.unwrap().cl_ord_id, 2);
+
+    // Backing off: refused locally without reaching the venue
+    let result = client.send(&OrderAction::Submit(order(3, 3_000_000_000_000, 1_000_000))).await;
+    assert!(matches!(result, Err(GatewayError::RateLimited { .. })));
+    assert_eq!(client.next_report().await.unwrap().cl_ord_id, 3);
+    assert_eq!(log.lock().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_amend_uses_cancel_replace_on_spot_only() {
+    let amend = OrderAction::Amend { cancel: cancel(7), replacement: Some(order(8, 3_000_100_000_000, 2_000_000)) };
+
+    let (rest, log) = start_rest(vec![Reply::ok(2, include_str!("fixtures/binance/cancel_replace.json"))]).await;
+    let mut spot = client(BinanceConfig::spot(symbols()), rest, String::new());
+    spot.send(&amend).await.unwrap();
+    let request = log.lock().unwrap()[0].clone();
+    assert_eq!((request.method.as_str(), request.path.as_str()), ("POST", "/api/v3/order/cancelReplace"));
+    request.assert_signed();
+    assert_eq!(request.param("cancelReplaceMode"), Some("STOP_ON_FAILURE"));
+    assert_eq!(request.param("cancelOrigClientOrderId"), Some("7"));
+    assert_eq!(request.param("newClientOrderId"), Some("8"));
+    assert_eq!(request.param("price"), Some("30001"));
+
+    let (rest, log) = start_rest(vec![
+        Reply::ok(1, include_str!("fixtures/binance/cancel.json")),
+        Reply::ok(2, include_str!("fixtures/binance/futures_order_ack.json")),
+    ])
+    .await;
+    let mut futures = client(BinanceConfig::usdm_futures(symbols()), rest, String::new());
+    futures.send(&amend).await.unwrap();
+    let log = log.lock().unwrap();
+    assert_eq!((log[0].method.as_str(), log[0].path.as_str()), ("DELETE", "/fapi/v1/order"));
+    assert_eq!((log[1].method.as_str(), log[1].path.as_str()), ("POST", "/fapi/v1/order"));
+    assert_eq!(log[1].param("newClientOrderId"), Some("8"));
+}
+
+#[tokio::test]
+async fn test_spot_user_stream_reports() {
+    let listen_key = Reply { chunked: true, ..Reply::ok(2, include_str!("fixtures/binance/listen_key.json")) };
+    let (rest, log) = start_rest(vec![listen_key]).await;
+    let ws = start_ws(include_str!("fixtures/binance/spot_user_stream.jsonl")).await;
+    let mut client = client(BinanceConfig::spot(symbols()), rest, ws);
+    client.start_user_stream().await.unwrap();
+    assert_eq!(log.lock().unwrap()[0].path, "/api/v3/userDataStream");
+
+    // Account updates, other symbols and orders placed elsewhere are skipped
+    let new = client.next_report().await.unwrap();
+    assert_eq!((new.cl_ord_id, new.status, new.leaves_quantity), (7, OrdStatus::New, 1_000_000));
+    let fill = client.next_report().await.unwrap();
+    assert_eq!((fill.exec_id, fill.status), (912, OrdStatus::PartiallyFilled));
+    assert_eq!((fill.filled_quantity, fill.filled_price, fill.leaves_quantity), (400_000, 3_000_050_000_000, 600_000));
+    assert_eq!(fill.fee, 12_000_200);
+    assert_eq!(fill.timestamp, 1_700_000_000_150_000_000);
+    let canceled = client.next_report().await.unwrap();
+    assert_eq!((canceled.cl_ord_id, canceled.status), (7, OrdStatus::Canceled));
+    assert!(matches!(client.next_report().await, Err(GatewayError::Closed)));
+}
+
+#[tokio::test]
+async fn test_futures_user_stream_reports() {
+    let (rest, log) = start_rest(vec![Reply::ok(1, include_str!("fixtures/binance/listen_key.json"))]).await;
+    let ws = start_ws(include_str!("fixtures/binance/futures_user_stream.jsonl")).await;
+    let mut client = client(BinanceConfig::usdm_futures(symbols()), rest, ws);
+    client.start_user_stream().await.unwrap();
+    assert_eq!(log.lock().unwrap()[0].path, "/fapi/v1/listenKey");
+
+    let new = client.next_report().await.unwrap();
+    assert_eq!((new.cl_ord_id, new.exec_id, new.status), (8, 0, OrdStatus::New));
+    let fill = client.next_report().await.unwrap();
+    assert_eq!((fill.exec_id, fill.status, fill.leaves_quantity), (316_559, OrdStatus::Filled, 0));
+    assert_eq!((fill.filled_quantity, fill.filled_price), (2_000_000, 3_000_100_000_000));
+    assert!(matches!(client.next_report().await, Err(GatewayError::Closed)));
+}
@@ -0,0 +1,122 @@
+use vibe_hft_backtest::testing::{book, print, CENT, ONE};
+use vibe_hft_backtest::ExchangeConfig;
+use vibe_hft_order_gateway::{GatewayError, MockExchange, MockExchangeConfig, MockGateway, OrderGateway, ReplayPace, VenueEvent};
+use vibe_hft_sbe_messages::{
+    ExchangeID, ExecutionReport, MarketDataUpdate, OrdStatus, OrdType, OrderCancel, OrderEntry, Side, TimeInForce,
+};
+use vibe_hft_strategy::{OrderAction, Strategy, StrategyContext, StrategyHost};
+
+fn order(cl_ord_id: u64, side: Side, cents: i64, quantity: u64, time_in_force: TimeInForce) -> OrderEntry {
+    OrderEntry {
+        timestamp: 2_000,
+        cl_ord_id,
+        exchange_id: ExchangeID::Binance,
+        symbol_id: 1,
+        side,
+        price: cents * CENT,
+        quantity,
+        order_type: OrdType::Limit,
+        time_in_force,
+    }
+}
+
+async fn start() -> (vibe_hft_order_gateway::MockExchangeHandle, MockGateway) {
+    let config = MockExchangeConfig {
+        exchange_id: ExchangeID::Binance,
+        symbol_id: 1,
+        exchange: ExchangeConfig::default(),
+        pace: ReplayPace::Instant,
+    };
+    let tape = vec![book(1_000, Side::Buy, 100, 5 * ONE), book(1_000, Side::Sell, 101, 5 * ONE)];
+    let exchange = MockExchange::start("127.0.0.1:0", config, tape).await.unwrap();
+    let gateway = MockGateway::connect(exchange.local_addr()).await.unwrap();
+    (exchange, gateway)
+}
+
+async fn expect_report(gateway: &mut MockGateway, cl_ord_id: u64, status: OrdStatus) -> ExecutionReport {
+    let report = gateway.next_report().await.unwrap();
+    assert_eq!((report.cl_ord_id, report.status), (cl_ord_id, status), "{:?}", report);
+    report
+}
+
+#[tokio::test]
+async fn test_order_lifecycle_against_replayed_book() {
+    let (exchange, mut gateway) = start().await;
+    for _ in 0..2 {
+        assert!(matches!(gateway.next_event().await.unwrap(), VenueEvent::Book(_)));
+    }
+
+    // Aggressive IOC takes the offer
+    gateway.send(&OrderAction::Submit(order(1, Side::Buy, 101, 2 * ONE, TimeInForce::Ioc))).await.unwrap();
+    expect_report(&mut gateway, 1, OrdStatus::New).await;
+    let fill = expect_report(&mut gateway, 1, OrdStatus::Filled).await;
+    assert_eq!((fill.filled_price, fill.filled_quantity), (101 * CENT, 2 * ONE));
+
+    // Passive bid joins behind 5 and fills once 6 trade through the level
+    gateway.send(&OrderAction::Submit(order(2, Side::Buy, 100, ONE, TimeInForce::Gtc))).await.unwrap();
+    expect_report(&mut gateway, 2, OrdStatus::New).await;
+    exchange.inject(print(3_000, Side::Sell, 100, 6 * ONE));
+    assert!(matches!(gateway.next_event().await.unwrap(), VenueEvent::Trade(_)));
+    let fill = expect_report(&mut gateway, 2, OrdStatus::Filled).await;
+    assert_eq!(fill.filled_quantity, ONE);
+
+    // Resting offer, then canceled; a second cancel is rejected
+    gateway.send(&OrderAction::Submit(order(3, Side::Sell, 105, ONE, TimeInForce::Gtc))).await.unwrap();
+    expect_report(&mut gateway, 3, OrdStatus::New).await;
+    let cancel = OrderCancel { timestamp: 4_000, cl_ord_id: 3, exchange_id: ExchangeID::Binance, symbol_id: 1, side: Side::Sell };
+    gateway.send(&OrderAction::Cancel(cancel)).await.unwrap();
+    expect_report(&mut gateway, 3, OrdStatus::Canceled).await;
+    gateway.send(&OrderAction::Cancel(cancel)).await.unwrap();
+    let reject = expect_report(&mut gateway, 3, OrdStatus::Rejected).await;
+    assert_eq!(reject.side, Side::Sell);
+}
+
+#[tokio::test]
+async fn test_session_closes_when_venue_stops() {
+    let (exchange, mut gateway) = start().await;
+    drop(exchange);
+    let mut result = gateway.next_event().await;
+    while result.is_ok() {
+        result = gateway.next_event().await;
+    }
+    assert!(matches!(result, Err(GatewayError::Closed) | Err(GatewayError::Io(_))));
+}
+
+/// Lifts the offer once the book is two-sided
+#[derive(Default)]
+struct TakeOffer {
+    sent: bool,
+}
+
+impl Strategy for TakeOffer {
+    fn on_book_update(&mut self, ctx: &mut StrategyContext<'_>, update: &MarketDataUpdate) {
+        let book = ctx.book(update.exchange_id);
+        let (Some(_), Some(ask)) = (book.best_bid(), book.best_ask()) else { return };
+        if !self.sent {
+            self.sent = true;
+            ctx.submit_ioc(update.exchange_id, update.symbol_id, Side::Buy, ask.price, ONE);
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_host_trades_through_gateway() {
+    let (_exchange, mut gateway) = start().await;
+    let mut host = StrategyHost::new(TakeOffer::default());
+
+    while host.positions().get(ExchangeID::Binance, 1).is_none() {
+        match gateway.next_event().await.unwrap() {
+            VenueEvent::Book(update) => host.on_market_data(update.timestamp, &update),
+            VenueEvent::Trade(trade) => host.on_trade(trade.timestamp, &trade),
+            VenueEvent::Report(report) => host.on_execution_report(report.timestamp, &report),
+        }
+        let actions: Vec<_> = host.drain_actions().collect();
+        for action in &actions {
+            gateway.send(action).await.unwrap();
+        }
+    }
+
+    let position = host.positions().get(ExchangeID::Binance, 1).unwrap();
+    assert_eq!((position.quantity, position.avg_entry_price), (ONE as i64, 101 * CENT));
+    assert_eq!(host.orders().live_orders().count(), 0);
+}
@@ -0,0 +1,485 @@
+use vibe_hft_core::{Price, Quantity, Timestamp};
+use vibe_hft_oms::OrderManager;
+use vibe_hft_sbe_messages::{ExchangeID, ExecutionReport, OrdStatus, OrdType, OrderEntry, Side};
+
+use crate::kill_switch::{KillReason, KillSwitch};
+use crate::limits::RiskLimits;
+use crate::throttle::RateWindow;
+use crate::{venue_index, RiskReject};
+
+/// Venue/symbol pairs the engine can hold a position for
+pub const MAX_INSTRUMENTS: usize = 32;
+
+const FIXED_POINT: i128 = 100_000_000;
+
+/// Position and cash on one venue/symbol
+#[derive(Debug, Clone, Copy)]
+struct Slot {
+    exchange_id: ExchangeID,
+    symbol_id: u32,
+    position: i64,
+    /// Quote units (1e8) paid out or received, fees included
+    cash: i128,
+    /// Latest mid, or the last fill price before any mid was seen
+    mark: Price,
+}
+
+impl Slot {
+    fn pnl(&self) -> i128 {
+        self.cash + self.position as i128 * self.mark as i128 / FIXED_POINT
+    }
+}
+
+/// Pre-trade checks that sit between strategies and order entry.
+///
+/// Every check works on fixed-size state (position slots and per-venue rate
+/// windows) and the order manager the host already keeps, so nothing
+/// allocates on the order path. Positions and PnL follow the execution
+/// reports fed to `on_execution_report`.
+///
+/// The engine also owns the kill switch: while it is tripped every order is
+/// refused. Cancelling and flattening on a trip is left to the caller, which
+/// owns the order path.
+#[derive(Debug, Clone)]
+pub struct RiskEngine {
+    limits: RiskLimits,
+    kill: KillSwitch,
+    slots: [Option<Slot>; MAX_INSTRUMENTS],
+    rates: [RateWindow; 3],
+    day: u64,
+    day_start_pnl: i128,
+    rejected: u64,
+}
+
+impl Default for RiskEngine {
+    fn default() -> Self {
+        Self::new(RiskLimits::default())
+    }
+}
+
+impl RiskEngine {
+    pub fn new(limits: RiskLimits) -> Self {
+        Self {
+            limits,
+            kill: KillSwitch::new(limits.breakers),
+            slots: [None; MAX_INSTRUMENTS],
+            rates: [RateWindow::new(), RateWindow::new(), RateWindow::new()],
+            day: 0,
+            day_start_pnl: 0,
+            rejected: 0,
+        }
+    }
+
+    pub fn limits(&self) -> &RiskLimits {
+        &self.limits
+    }
+
+    pub fn set_limits(&mut self, limits: RiskLimits) {
+        self.limits = limits;
+        self.kill.set_breakers(limits.breakers);
+    }
+
+    pub fn kill_switch(&self) -> &KillSwitch {
+        &self.kill
+    }
+
+    pub fn kill_switch_mut(&mut self) -> &mut KillSwitch {
+        &mut self.kill
+    }
+
+    /// Trip the kill switch. Returns `false` if it already was.
+    pub fn halt(&mut self, now: Timestamp, reason: KillReason) -> bool {
+        self.kill.trip(now, reason)
+    }
+
+    /// Re-arm the kill switch, taking the current PnL as the new high-water mark
+    pub fn rearm(&mut self) -> bool {
+        let pnl = self.pnl();
+        self.kill.rearm(pnl)
+    }
+
+    /// Trip on any venue we have live orders on whose book has gone stale
+    pub fn check_books(&mut self, now: Timestamp, orders: &OrderManager) {
+        for exchange_id in [ExchangeID::Binance, ExchangeID::Bybit, ExchangeID::Coinbase] {
+            if orders.live_orders().any(|o| o.exchange_id == exchange_id) {
+                self.kill.check_book(exchange_id, now);
+            }
+        }
+    }
+
+    /// Non-zero positions as `(exchange_id, symbol_id, position)`
+    pub fn positions(&self) -> impl Iterator<Item = (ExchangeID, u32, i64)> + '_ {
+        self.slots.iter().flatten().filter(|s| s.position != 0).map(|s| (s.exchange_id, s.symbol_id, s.position))
+    }
+
+    pub fn position(&self, exchange_id: ExchangeID, symbol_id: u32) -> i64 {
+        self.slot(exchange_id, symbol_id).map_or(0, |s| s.position)
+    }
+
+    /// Net position on `symbol_id` across venues
+    pub fn total_position(&self, symbol_id: u32) -> i64 {
+        self.slots.iter().flatten().filter(|s| s.symbol_id == symbol_id).map(|s| s.position).sum()
+    }
+
+    /// Realized plus marked-to-mid PnL since start, in quote units (1e8)
+    pub fn pnl(&self) -> Price {
+        self.total_pnl() as Price
+    }
+
+    /// PnL since the start of the current day of `RiskLimits::day_length`
+    /// (the UTC day by default), as of the latest mark, fill or check
+    pub fn daily_pnl(&self) -> Price {
+        (self.total_pnl() - self.day_start_pnl) as Price
+    }
+
+    /// Orders refused so far
+    pub fn rejected(&self) -> u64 {
+        self.rejected
+    }
+
+    /// Mark open positions on a venue/symbol to `mid`
+    pub fn mark(&mut self, now: Timestamp, exchange_id: ExchangeID, symbol_id: u32, mid: Price) {
+        self.roll_day(now);
+        let Some(slot) = self.slot_mut(exchange_id, symbol_id) else { return };
+        let changed = slot.position != 0 && slot.mark != mid;
+        slot.mark = mid;
+        if changed {
+            self.kill.on_pnl(now, self.pnl());
+        }
+    }
+
+    /// Run every pre-trade check on `order` against the venue `mid` and the
+    /// orders already working in `orders`. `order` itself is skipped if it is
+    /// already in `orders`, as the replacement of a cancel/replace is.
+    pub fn check_order(
+        &mut self,
+        now: Timestamp,
+        order: &OrderEntry,
+        mid: Option<Price>,
+        orders: &OrderManager,
+    ) -> Result<(), RiskReject> {
+        let result = self.evaluate(now, order, mid, orders);
+        if result.is_err() {
+            self.rejected += 1;
+        }
+        result
+    }
+
+    /// Count a message sent to `exchange_id` against its rate window
+    pub fn record_message(&mut self, exchange_id: ExchangeID, now: Timestamp) {
+        self.rates[venue_index(exchange_id)].record(now);
+    }
+
+    /// Apply the fill in `report`, if any, to the position and cash. Every
+    /// report goes through here, local rejects included, so rejects can be
+    /// counted towards the reject breaker.
+    pub fn on_execution_report(&mut self, now: Timestamp, report: &ExecutionReport) {
+        self.roll_day(now);
+        if report.status == OrdStatus::Rejected {
+            self.kill.on_reject(now);
+        }
+        if report.filled_quantity == 0 {
+            return;
+        }
+        let Ok(slot) = self.slot_or_insert(report.exchange_id, report.symbol_id) else {
+            return;
+        };
+        let quantity = report.filled_quantity as i64;
+        let notional = report.filled_price as i128 * report.filled_quantity as i128 / FIXED_POINT;
+        match report.side {
+            Side::Buy => {
+                slot.position += quantity;
+                slot.cash -= notional;
+            }
+            Side::Sell => {
+                slot.position -= quantity;
+                slot.cash += notional;
+            }
+        }
+        slot.cash -= report.fee as i128;
+        if slot.mark == 0 {
+            slot.mark = report.filled_price;
+        }
+        self.kill.on_pnl(now, self.pnl());
+    }
+
+    /// Start a new day at the first event past midnight, from the PnL at
+    /// the marks the previous day ended on
+    fn roll_day(&mut self, now: Timestamp) {
+        let day = now / self.limits.day_length;
+        if day != self.day {
+            self.day = day;
+            self.day_start_pnl = self.total_pnl();
+        }
+    }
+
+    fn evaluate(
+        &mut self,
+        now: Timestamp,
+        order: &OrderEntry,
+        mid: Option<Price>,
+        orders: &OrderManager,
+    ) -> Result<(), RiskReject> {
+        let limits = self.limits;
+
+        if let Some((reason, _)) = self.kill.tripped() {
+            return Err(RiskReject::Halted { reason });
+        }
+
+        self.roll_day(now);
+        let pnl = self.daily_pnl();
+        if pnl < 0 && -pnl > limits.max_daily_loss {
+            return Err(RiskReject::DailyLoss { pnl, limit: limits.max_daily_loss });
+        }
+
+        if !self.rates[venue_index(order.exchange_id)].allows(now, limits.rate_window, limits.max_messages) {
+            return Err(RiskReject::RateLimit { exchange_id: order.exchange_id });
+        }
+
+        if order.quantity > limits.max_order_quantity {
+            return Err(RiskReject::OrderQuantity { quantity: order.quantity, limit: limits.max_order_quantity });
+        }
+
+        let is_market = order.order_type == OrdType::Market;
+        if let (Some(band_bps), false) = (limits.price_band_bps, is_market) {
+            let mid = mid.ok_or(RiskReject::NoReferencePrice)?;
+            let distance = (order.price as i128 - mid as i128).abs() * 10_000;
+            if distance > band_bps as i128 * mid as i128 {
+                return Err(RiskReject::PriceBand { price: order.price, mid });
+            }
+        }
+
+        if limits.max_order_notional != Price::MAX {
+            let price = if is_market { mid.ok_or(RiskReject::NoReferencePrice)? } else { order.price };
+            let notional = price as i128 * order.quantity as i128 / FIXED_POINT;
+            if notional > limits.max_order_notional as i128 {
+                let notional = notional.min(Price::MAX as i128) as Price;
+                return Err(RiskReject::OrderNotional { notional, limit: limits.max_order_notional });
+            }
+        }
+
+        let others = || {
+            orders
+                .live_orders()
+                .filter(|o| !o.cancel_pending && o.cl_ord_id != order.cl_ord_id)
+        };
+        if others().count() >= limits.max_open_orders {
+            return Err(RiskReject::OpenOrders { limit: limits.max_open_orders });
+        }
+
+        // Working orders on the same side count as filled
+        let sign = match order.side {
+            Side::Buy => 1,
+            Side::Sell => -1,
+        };
+        let working = |venue: Option<ExchangeID>| -> Quantity {
+            others()
+                .filter(|o| o.symbol_id == order.symbol_id && o.side == order.side)
+                .filter(|o| venue.is_none_or(|v| o.exchange_id == v))
+                .map(|o| o.leaves_quantity())
+                .sum()
+        };
+
+        let venue_working = working(Some(order.exchange_id));
+        let total_working = working(None);
+        let total_position = self.total_position(order.symbol_id);
+        let slot = self.slot_or_insert(order.exchange_id, order.symbol_id)?;
+
+        let projected = slot.position + sign * (venue_working + order.quantity) as i64;
+        if projected.unsigned_abs() > limits.max_position {
+            return Err(RiskReject::Position { projected, limit: limits.max_position });
+        }
+        let projected = total_position + sign * (total_working + order.quantity) as i64;
+        if projected.unsigned_abs() > limits.max_total_position {
+            return Err(RiskReject::TotalPosition { projected, limit: limits.max_total_position });
+        }
+        Ok(())
+    }
+
+    fn total_pnl(&self) -> i128 {
+        self.slots.iter().flatten().map(Slot::pnl).sum()
+    }
+
+    fn slot(&self, exchange_id: ExchangeID, symbol_id: u32) -> Option<&Slot> {
+        self.slots.iter().flatten().find(|s| s.exchange_id == exchange_id && s.symbol_id == symbol_id)
+    }
+
+    fn slot_mut(&mut self, exchange_id: ExchangeID, symbol_id: u32) -> Option<&mut Slot> {
+        self.slots.iter_mut().flatten().find(|s| s.exchange_id == exchange_id && s.symbol_id == symbol_id)
+    }
+
+    fn slot_or_insert(&mut self, exchange_id: ExchangeID, symbol_id: u32) -> Result<&mut Slot, RiskReject> {
+        let index = self
+            .slots
+            .iter()
+            .position(|s| s.is_some_and(|s| s.exchange_id == exchange_id && s.symbol_id == symbol_id))
+            .or_else(|| self.slots.iter().position(Option::is_none))
+            .ok_or(RiskReject::TooManyInstruments)?;
+        Ok(self.slots[index].get_or_insert(Slot { exchange_id, symbol_id, position: 0, cash: 0, mark: 0 }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vibe_hft_sbe_messages::TimeInForce;
+
+    const ONE: u64 = 100_000_000;
+
+    fn order(side: Side, price: Price, quantity: Quantity) -> OrderEntry {
+        OrderEntry {
+            timestamp: 0,
+            cl_ord_id: 0,
+            exchange_id: ExchangeID::Binance,
+            symbol_id: 1,
+            side,
+            price,
+            quantity,
+            order_type: OrdType::Limit,
+            time_in_force: TimeInForce::Gtc,
+        }
+    }
+
+    fn fill(side: Side, price: Price, quantity: Quantity) -> ExecutionReport {
+        ExecutionReport {
+            timestamp: 0,
+            cl_ord_id: 1,
+            exec_id: 1,
+            exchange_id: ExchangeID::Binance,
+            symbol_id: 1,
+            side,
+            filled_quantity: quantity,
+            filled_price: price,
+            leaves_quantity: 0,
+            fee: 0,
+            status: OrdStatus::Filled,
+        }
+    }
+
+    #[test]
+    fn test_size_notional_and_price_band() {
+        let mut risk = RiskEngine::new(RiskLimits {
+            max_order_quantity: 5 * ONE,
+            max_order_notional: 400 * ONE as Price,
+            price_band_bps: Some(100),
+            ..RiskLimits::default()
+        });
+        let oms = OrderManager::new();
+        let mid = Some(100 * ONE as Price);
+
+        assert_eq!(risk.check_order(0, &order(Side::Buy, 100 * ONE as Price, 3 * ONE), mid, &oms), Ok(()));
+        assert!(matches!(
+            risk.check_order(0, &order(Side::Buy, 100 * ONE as Price, 6 * ONE), mid, &oms),
+            Err(RiskReject::OrderQuantity { .. })
+        ));
+        assert!(matches!(
+            risk.check_order(0, &order(Side::Buy, 100 * ONE as Price, 5 * ONE), mid, &oms),
+            Err(RiskReject::OrderNotional { .. })
+        ));
+        // 1% band: 101 is fine, 102 is a fat finger
+        assert_eq!(risk.check_order(0, &order(Side::Sell, 101 * ONE as Price, ONE), mid, &oms), Ok(()));
+        assert!(matches!(
+            risk.check_order(0, &order(Side::Sell, 102 * ONE as Price, ONE), mid, &oms),
+            Err(RiskReject::PriceBand { .. })
+        ));
+        assert_eq!(
+            risk.check_order(0, &order(Side::Sell, 100 * ONE as Price, ONE), None, &oms),
+            Err(RiskReject::NoReferencePrice)
+        );
+        assert_eq!(risk.rejected(), 4);
+    }
+
+    #[test]
+    fn test_open_orders_and_working_quantity_count_towards_position() {
+        let mut risk = RiskEngine::new(RiskLimits {
+            max_open_orders: 2,
+            max_position: 4 * ONE,
+            max_total_position: 5 * ONE,
+            ..RiskLimits::default()
+        });
+        let mut oms = OrderManager::new();
+        oms.create(order(Side::Buy, 100, 3 * ONE));
+
+        assert!(matches!(
+            risk.check_order(0, &order(Side::Buy, 100, 2 * ONE), None, &oms),
+            Err(RiskReject::Position { projected, .. }) if projected == 5 * ONE as i64
+        ));
+        // Selling against the working bid is fine
+        assert_eq!(risk.check_order(0, &order(Side::Sell, 100, 2 * ONE), None, &oms), Ok(()));
+
+        // A long on another venue counts towards the total
+        risk.on_execution_report(0, &ExecutionReport { exchange_id: ExchangeID::Bybit, ..fill(Side::Buy, 100, 2 * ONE) });
+        assert!(matches!(
+            risk.check_order(0, &order(Side::Buy, 100, ONE), None, &oms),
+            Err(RiskReject::TotalPosition { projected, .. }) if projected == 6 * ONE as i64
+        ));
+
+        oms.create(order(Side::Sell, 100, ONE));
+        assert_eq!(
+            risk.check_order(0, &order(Side::Sell, 100, ONE), None, &oms),
+            Err(RiskReject::OpenOrders { limit: 2 })
+        );
+    }
+
+    #[test]
+    fn test_message_rate_per_venue() {
+        let mut risk = RiskEngine::new(RiskLimits { max_messages: 2, rate_window: 1_000, ..RiskLimits::default() });
+        let oms = OrderManager::new();
+        risk.record_message(ExchangeID::Binance, 100);
+        risk.record_message(ExchangeID::Binance, 200);
+
+        assert_eq!(
+            risk.check_order(500, &order(Side::Buy, 100, ONE), None, &oms),
+            Err(RiskReject::RateLimit { exchange_id: ExchangeID::Binance })
+        );
+        let bybit = OrderEntry { exchange_id: ExchangeID::Bybit, ..order(Side::Buy, 100, ONE) };
+        assert_eq!(risk.check_order(500, &bybit, None, &oms), Ok(()));
+        assert_eq!(risk.check_order(1_100, &order(Side::Buy, 100, ONE), None, &oms), Ok(()));
+    }
+
+    #[test]
+    fn test_daily_loss_marks_to_mid_and_resets_next_day() {
+        // A day of millisecond timestamps
+        let limits = RiskLimits { max_daily_loss: 50 * ONE as Price, day_length: 86_400_000, ..RiskLimits::default() };
+        let mut risk = RiskEngine::new(limits);
+        let oms = OrderManager::new();
+        let buy = order(Side::Buy, 100 * ONE as Price, ONE);
+
+        risk.on_execution_report(0, &fill(Side::Buy, 100 * ONE as Price, 10 * ONE));
+        risk.mark(0, ExchangeID::Binance, 1, 96 * ONE as Price);
+        assert_eq!(risk.pnl(), -40 * ONE as Price);
+        assert_eq!(risk.check_order(0, &buy, None, &oms), Ok(()));
+
+        risk.mark(0, ExchangeID::Binance, 1, 94 * ONE as Price);
+        assert!(matches!(risk.check_order(0, &buy, None, &oms), Err(RiskReject::DailyLoss { .. })));
+
+        // The loss is carried into the next day's baseline
+        assert_eq!(risk.check_order(86_400_000, &buy, None, &oms), Ok(()));
+        assert_eq!(risk.daily_pnl(), 0);
+    }
+
+    #[test]
+    fn test_day_rolls_on_marks_without_orders() {
+        let limits = RiskLimits { day_length: 86_400_000, ..RiskLimits::default() };
+        let mut risk = RiskEngine::new(limits);
+        risk.on_execution_report(0, &fill(Side::Buy, 100 * ONE as Price, ONE));
+        risk.mark(0, ExchangeID::Binance, 1, 90 * ONE as Price);
+        assert_eq!(risk.daily_pnl(), -10 * ONE as Price);
+
+        // Only moves after midnight count towards the new day
+        risk.mark(86_400_000, ExchangeID::Binance, 1, 92 * ONE as Price);
+        assert_eq!((risk.pnl(), risk.daily_pnl()), (-8 * ONE as Price, 2 * ONE as Price));
+    }
+
+    #[test]
+    fn test_halt_refuses_everything_until_rearmed() {
+        let mut risk = RiskEngine::default();
+        let oms = OrderManager::new();
+        let buy = order(Side::Buy, 100, ONE);
+
+        assert!(risk.halt(10, KillReason::Operator));
+        assert_eq!(risk.check_order(20, &buy, None, &oms), Err(RiskReject::Halted { reason: KillReason::Operator }));
+        assert!(risk.rearm());
+        assert_eq!(risk.check_order(30, &buy, None, &oms), Ok(()));
+    }
+}
@@ -0,0 +1,191 @@
+use core::fmt;
+
+use vibe_hft_core::{Price, Timestamp};
+use vibe_hft_sbe_messages::ExchangeID;
+
+use crate::throttle::{RateWindow, RATE_WINDOW_CAPACITY};
+
+/// Why trading was halted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KillReason {
+    Operator,
+    /// The market data feed of a venue disconnected or went stale
+    FeedLoss(ExchangeID),
+    /// PnL fell this far below its high-water mark (quote units, 1e8)
+    Drawdown { drawdown: Price },
+    /// This many orders were rejected within the reject window
+    RejectBurst { rejects: usize },
+    /// A venue we have live orders on stopped updating its book
+    StaleBook(ExchangeID),
+}
+
+impl fmt::Display for KillReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KillReason::Operator => write!(f, "operator"),
+            KillReason::FeedLoss(exchange_id) => write!(f, "feed lost on {:?}", exchange_id),
+            KillReason::Drawdown { drawdown } => write!(f, "drawdown {}", drawdown),
+            KillReason::RejectBurst { rejects } => write!(f, "{} rejects in window", rejects),
+            KillReason::StaleBook(exchange_id) => write!(f, "stale book on {:?}", exchange_id),
+        }
+    }
+}
+
+/// Automatic kill switch triggers. `Default` disables all of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CircuitBreakers {
+    /// Halt when a venue's feed disconnects or is reported stale
+    pub halt_on_feed_loss: bool,
+    /// Largest fall from the PnL high-water mark (quote units, 1e8)
+    pub max_drawdown: Price,
+    /// Rejects, from the venue or the risk checks, tolerated within
+    /// `reject_window` (capped at `RATE_WINDOW_CAPACITY`)
+    pub max_rejects: usize,
+    pub reject_window: Timestamp,
+    /// Longest a venue's book may go without an update while we have live
+    /// orders on it
+    pub max_book_age: Timestamp,
+    /// Close out every position at market once tripped
+    pub flatten: bool,
+}
+
+impl Default for CircuitBreakers {
+    fn default() -> Self {
+        Self {
+            halt_on_feed_loss: false,
+            max_drawdown: Price::MAX,
+            max_rejects: usize::MAX,
+            reject_window: 1_000_000_000,
+            max_book_age: Timestamp::MAX,
+            flatten: false,
+        }
+    }
+}
+
+/// Latched halt. Once tripped, by the operator or a circuit breaker, it
+/// stays tripped until `rearm` is called explicitly.
+#[derive(Debug, Clone)]
+pub struct KillSwitch {
+    breakers: CircuitBreakers,
+    tripped: Option<(KillReason, Timestamp)>,
+    rejects: RateWindow,
+    peak_pnl: Price,
+    book_updates: [Option<Timestamp>; 3],
+}
+
+impl KillSwitch {
+    pub fn new(breakers: CircuitBreakers) -> Self {
+        Self { breakers, tripped: None, rejects: RateWindow::new(), peak_pnl: 0, book_updates: [None; 3] }
+    }
+
+    pub fn breakers(&self) -> &CircuitBreakers {
+        &self.breakers
+    }
+
+    pub fn set_breakers(&mut self, breakers: CircuitBreakers) {
+        self.breakers = breakers;
+    }
+
+    pub fn is_tripped(&self) -> bool {
+        self.tripped.is_some()
+    }
+
+    /// Reason and time of the trip, while tripped
+    pub fn tripped(&self) -> Option<(KillReason, Timestamp)> {
+        self.tripped
+    }
+
+    /// Halt trading. Returns `false` if already tripped; the first reason is kept.
+    pub fn trip(&mut self, now: Timestamp, reason: KillReason) -> bool {
+        if self.tripped.is_some() {
+            return false;
+        }
+        self.tripped = Some((reason, now));
+        true
+    }
+
+    /// Allow trading again. `pnl` becomes the new high-water mark and past
+    /// rejects are forgotten, so the same breakers do not fire again at once.
+    /// Returns `false` if it was not tripped.
+    pub fn rearm(&mut self, pnl: Price) -> bool {
+        self.peak_pnl = pnl;
+        self.rejects = RateWindow::new();
+        self.tripped.take().is_some()
+    }
+
+    pub fn on_reject(&mut self, now: Timestamp) {
+        self.rejects.record(now);
+        let rejects = self.rejects.count(now, self.breakers.reject_window);
+        if rejects >= self.breakers.max_rejects.min(RATE_WINDOW_CAPACITY) {
+            self.trip(now, KillReason::RejectBurst { rejects });
+        }
+    }
+
+    pub fn on_pnl(&mut self, now: Timestamp, pnl: Price) {
+        self.peak_pnl = self.peak_pnl.max(pnl);
+        let drawdown = self.peak_pnl.saturating_sub(pnl);
+        if drawdown > self.breakers.max_drawdown {
+            self.trip(now, KillReason::Drawdown { drawdown });
+        }
+    }
+
+    pub fn on_book_update(&mut self, exchange_id: ExchangeID, now: Timestamp) {
+        self.book_updates[crate::venue_index(exchange_id)] = Some(now);
+    }
+
+    /// Trip if `exchange_id`'s book is older than `max_book_age`. Callers
+    /// only ask for venues they have live orders on.
+    pub fn check_book(&mut self, exchange_id: ExchangeID, now: Timestamp) {
+        let last = self.book_updates[crate::venue_index(exchange_id)];
+        if last.is_some_and(|at| now.saturating_sub(at) > self.breakers.max_book_age) {
+            self.trip(now, KillReason::StaleBook(exchange_id));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latches_until_rearmed() {
+        let mut kill = KillSwitch::new(CircuitBreakers { max_rejects: 3, reject_window: 100, ..Default::default() });
+        kill.on_reject(0);
+        kill.on_reject(50);
+        assert!(!kill.is_tripped());
+        kill.on_reject(90);
+        assert_eq!(kill.tripped(), Some((KillReason::RejectBurst { rejects: 3 }, 90)));
+
+        // A later reason does not overwrite the first
+        assert!(!kill.trip(95, KillReason::Operator));
+        assert!(kill.rearm(0));
+        assert!(!kill.is_tripped());
+        kill.on_reject(200);
+        assert!(!kill.is_tripped());
+    }
+
+    #[test]
+    fn test_drawdown_from_high_water_mark() {
+        let mut kill = KillSwitch::new(CircuitBreakers { max_drawdown: 100, ..Default::default() });
+        kill.on_pnl(0, 500);
+        kill.on_pnl(1, 410);
+        assert!(!kill.is_tripped());
+        kill.on_pnl(2, 399);
+        assert_eq!(kill.tripped(), Some((KillReason::Drawdown { drawdown: 101 }, 2)));
+        kill.rearm(399);
+        kill.on_pnl(3, 350);
+        assert!(!kill.is_tripped());
+    }
+
+    #[test]
+    fn test_stale_book() {
+        let mut kill = KillSwitch::new(CircuitBreakers { max_book_age: 1_000, ..Default::default() });
+        // No book yet: nothing to be stale
+        kill.check_book(ExchangeID::Bybit, 5_000);
+        kill.on_book_update(ExchangeID::Bybit, 5_000);
+        kill.check_book(ExchangeID::Bybit, 6_000);
+        assert!(!kill.is_tripped());
+        kill.check_book(ExchangeID::Bybit, 6_001);
+        assert_eq!(kill.tripped(), Some((KillReason::StaleBook(ExchangeID::Bybit), 6_001)));
+    }
+}
@@ -0,0 +1,67 @@
+#![no_std]
+
+pub mod engine;
+pub mod kill_switch;
+pub mod limits;
+pub mod throttle;
+
+pub use engine::{RiskEngine, MAX_INSTRUMENTS};
+pub use kill_switch::{CircuitBreakers, KillReason, KillSwitch};
+pub use limits::RiskLimits;
+pub use throttle::{RateWindow, RATE_WINDOW_CAPACITY};
+
+use core::fmt;
+use vibe_hft_core::{Price, Quantity};
+use vibe_hft_sbe_messages::ExchangeID;
+
+/// Why a pre-trade check refused an order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RiskReject {
+    OrderQuantity { quantity: Quantity, limit: Quantity },
+    /// Notional in quote units (1e8)
+    OrderNotional { notional: Price, limit: Price },
+    /// Limit price too far from the venue mid (fat finger)
+    PriceBand { price: Price, mid: Price },
+    /// The venue has no two-sided book to check the price or notional against
+    NoReferencePrice,
+    OpenOrders { limit: usize },
+    /// Position on the venue, including working orders on the same side
+    Position { projected: i64, limit: Quantity },
+    /// Position on the symbol summed across venues
+    TotalPosition { projected: i64, limit: Quantity },
+    RateLimit { exchange_id: ExchangeID },
+    /// Loss since the start of the day, in quote units (1e8)
+    DailyLoss { pnl: Price, limit: Price },
+    /// Every position slot is taken by another venue/symbol
+    TooManyInstruments,
+    /// The kill switch is tripped
+    Halted { reason: KillReason },
+}
+
+impl fmt::Display for RiskReject {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RiskReject::OrderQuantity { quantity, limit } => write!(f, "quantity {} above limit {}", quantity, limit),
+            RiskReject::OrderNotional { notional, limit } => write!(f, "notional {} above limit {}", notional, limit),
+            RiskReject::PriceBand { price, mid } => write!(f, "price {} outside band around mid {}", price, mid),
+            RiskReject::NoReferencePrice => write!(f, "no mid to check the order against"),
+            RiskReject::OpenOrders { limit } => write!(f, "already {} open orders", limit),
+            RiskReject::Position { projected, limit } => write!(f, "venue position {} above limit {}", projected, limit),
+            RiskReject::TotalPosition { projected, limit } => {
+                write!(f, "position across venues {} above limit {}", projected, limit)
+            }
+            RiskReject::RateLimit { exchange_id } => write!(f, "message rate limit reached on {:?}", exchange_id),
+            RiskReject::DailyLoss { pnl, limit } => write!(f, "daily pnl {} beyond loss limit {}", pnl, limit),
+            RiskReject::TooManyInstruments => write!(f, "no position slot left for a new instrument"),
+            RiskReject::Halted { reason } => write!(f, "trading halted ({})", reason),
+        }
+    }
+}
+
+pub(crate) fn venue_index(exchange_id: ExchangeID) -> usize {
+    match exchange_id {
+        ExchangeID::Binance => 0,
+        ExchangeID::Bybit => 1,
+        ExchangeID::Coinbase => 2,
+    }
+}
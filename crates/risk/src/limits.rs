@@ -0,0 +1,51 @@
+use vibe_hft_core::{Price, Quantity, Timestamp};
+
+use crate::kill_switch::CircuitBreakers;
+
+/// Pre-trade limits. `Default` is unlimited, so every check has to be opted
+/// into explicitly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RiskLimits {
+    pub max_order_quantity: Quantity,
+    /// Price × quantity in quote units (1e8); market orders are valued at mid
+    pub max_order_notional: Price,
+    /// Furthest a limit price may sit from the venue mid, in bps. Orders on a
+    /// venue without a two-sided book are refused while this is set.
+    pub price_band_bps: Option<u32>,
+    /// Live orders across all venues, not counting ones being canceled
+    pub max_open_orders: usize,
+    /// Absolute position per venue and symbol, counting working orders on the
+    /// side of the new order as if they filled
+    pub max_position: Quantity,
+    /// Absolute position per symbol summed over venues, counted the same way
+    pub max_total_position: Quantity,
+    /// Messages (orders and cancels) per venue within `rate_window`, capped
+    /// at `RATE_WINDOW_CAPACITY`. Cancels are counted but never refused.
+    pub max_messages: usize,
+    pub rate_window: Timestamp,
+    /// Loss, realized plus marked to mid, tolerated since the start of the
+    /// day before new orders are refused (quote units, positive)
+    pub max_daily_loss: Price,
+    /// Length of that day in timestamp units; days start at multiples of it,
+    /// so the UTC midnight for epoch timestamps
+    pub day_length: Timestamp,
+    pub breakers: CircuitBreakers,
+}
+
+impl Default for RiskLimits {
+    fn default() -> Self {
+        Self {
+            max_order_quantity: Quantity::MAX,
+            max_order_notional: Price::MAX,
+            price_band_bps: None,
+            max_open_orders: usize::MAX,
+            max_position: Quantity::MAX,
+            max_total_position: Quantity::MAX,
+            max_messages: usize::MAX,
+            rate_window: 1_000_000_000,
+            max_daily_loss: Price::MAX,
+            day_length: 86_400 * 1_000_000_000,
+            breakers: CircuitBreakers::default(),
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use vibe_hft_core::Timestamp;
+
+/// Most messages a `RateWindow` remembers; larger limits are clamped to it
+pub const RATE_WINDOW_CAPACITY: usize = 128;
+
+/// Sliding window of message timestamps over a fixed ring buffer.
+#[derive(Debug, Clone)]
+pub struct RateWindow {
+    sent: [Timestamp; RATE_WINDOW_CAPACITY],
+    head: usize,
+    len: usize,
+}
+
+impl Default for RateWindow {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RateWindow {
+    pub const fn new() -> Self {
+        Self { sent: [0; RATE_WINDOW_CAPACITY], head: 0, len: 0 }
+    }
+
+    /// Messages sent in `(now - window, now]`
+    pub fn count(&mut self, now: Timestamp, window: Timestamp) -> usize {
+        while self.len > 0 && self.sent[self.head] + window <= now {
+            self.head = (self.head + 1) % RATE_WINDOW_CAPACITY;
+            self.len -= 1;
+        }
+        self.len
+    }
+
+    /// Whether one more message fits under `max` within `window`
+    pub fn allows(&mut self, now: Timestamp, window: Timestamp, max: usize) -> bool {
+        self.count(now, window) < max.min(RATE_WINDOW_CAPACITY)
+    }
+
+    /// Record a message; once full the oldest one is forgotten
+    pub fn record(&mut self, now: Timestamp) {
+        if self.len == RATE_WINDOW_CAPACITY {
+            self.head = (self.head + 1) % RATE_WINDOW_CAPACITY;
+            self.len -= 1;
+        }
+        self.sent[(self.head + self.len) % RATE_WINDOW_CAPACITY] = now;
+        self.len += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_window_slides() {
+        let mut window = RateWindow::new();
+        window.record(100);
+        window.record(150);
+        assert!(!window.allows(160, 100, 2));
+        // The message at 100 leaves the window at 200
+        assert_eq!(window.count(199, 100), 2);
+        assert!(window.allows(200, 100, 2));
+        assert_eq!(window.count(250, 100), 0);
+    }
+
+    #[test]
+    fn test_full_ring_drops_oldest() {
+        let mut window = RateWindow::new();
+        for t in 0..RATE_WINDOW_CAPACITY as u64 + 5 {
+            window.record(t);
+        }
+        assert_eq!(window.count(200, 1_000), RATE_WINDOW_CAPACITY);
+        assert!(!window.allows(200, 1_000, usize::MAX));
+    }
+}
@@ -1,6 +1,3 @@
-use std::env;
-use std::path::Path;
-
 fn main() {
     // In a real scenario, we would use rustysbe to generate the code here.
     // For now, we will assume the code is generated or we are just setting up the build script.
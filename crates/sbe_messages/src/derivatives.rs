@@ -0,0 +1,164 @@
+//! Perpetual swap data from the venues' derivatives feeds, all framed.
+
+use crate::framing::{Reader, Writer};
+use crate::{ExchangeID, MessageHeader, Side};
+
+/// Open interest of a perpetual on one venue
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OpenInterest {
+    pub timestamp: u64,
+    pub exchange_id: ExchangeID,
+    pub symbol_id: u32,
+    /// Open positions in base units (1e8), one side counted
+    pub open_interest: u64,
+}
+
+impl OpenInterest {
+    pub const TEMPLATE_ID: u16 = 12;
+    pub const BLOCK_LENGTH: usize = 21;
+    pub const ENCODED_LENGTH: usize = MessageHeader::ENCODED_LENGTH + Self::BLOCK_LENGTH;
+
+    pub fn to_bytes(&self) -> [u8; Self::ENCODED_LENGTH] {
+        let mut buf = [0u8; Self::ENCODED_LENGTH];
+        Writer::framed(&mut buf, Self::TEMPLATE_ID, Self::BLOCK_LENGTH)
+            .u64(self.timestamp)
+            .u8(self.exchange_id as u8)
+            .u32(self.symbol_id)
+            .u64(self.open_interest);
+        buf
+    }
+
+    pub fn from_bytes(buf: &[u8]) -> Option<Self> {
+        let mut r = Reader::framed(buf, Self::TEMPLATE_ID)?;
+        Some(Self {
+            timestamp: r.u64(),
+            exchange_id: ExchangeID::from_u8(r.u8())?,
+            symbol_id: r.u32(),
+            open_interest: r.u64(),
+        })
+    }
+}
+
+/// Mark and index price of a perpetual with its current funding rate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MarkPriceUpdate {
+    pub timestamp: u64,
+    pub exchange_id: ExchangeID,
+    pub symbol_id: u32,
+    pub mark_price: i64,
+    pub index_price: i64,
+    /// Rate for the current funding interval (1e8), paid by longs when positive
+    pub funding_rate: i64,
+    pub next_funding_time: u64,
+}
+
+impl MarkPriceUpdate {
+    pub const TEMPLATE_ID: u16 = 13;
+    pub const BLOCK_LENGTH: usize = 45;
+    pub const ENCODED_LENGTH: usize = MessageHeader::ENCODED_LENGTH + Self::BLOCK_LENGTH;
+
+    pub fn to_bytes(&self) -> [u8; Self::ENCODED_LENGTH] {
+        let mut buf = [0u8; Self::ENCODED_LENGTH];
+        Writer::framed(&mut buf, Self::TEMPLATE_ID, Self::BLOCK_LENGTH)
+            .u64(self.timestamp)
+            .u8(self.exchange_id as u8)
+            .u32(self.symbol_id)
+            .i64(self.mark_price)
+            .i64(self.index_price)
+            .i64(self.funding_rate)
+            .u64(self.next_funding_time);
+        buf
+    }
+
+    pub fn from_bytes(buf: &[u8]) -> Option<Self> {
+        let mut r = Reader::framed(buf, Self::TEMPLATE_ID)?;
+        Some(Self {
+            timestamp: r.u64(),
+            exchange_id: ExchangeID::from_u8(r.u8())?,
+            symbol_id: r.u32(),
+            mark_price: r.i64(),
+            index_price: r.i64(),
+            funding_rate: r.i64(),
+            next_funding_time: r.u64(),
+        })
+    }
+}
+
+/// Position closed by the venue's liquidation engine. `side` is the side of
+/// the liquidated position, as in `LiquidationLevel`: `Buy` when a long was
+/// sold out, `Sell` when a short was bought back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ForcedLiquidation {
+    pub timestamp: u64,
+    pub exchange_id: ExchangeID,
+    pub symbol_id: u32,
+    pub side: Side,
+    pub price: i64,
+    pub quantity: u64,
+}
+
+impl ForcedLiquidation {
+    pub const TEMPLATE_ID: u16 = 14;
+    pub const BLOCK_LENGTH: usize = 30;
+    pub const ENCODED_LENGTH: usize = MessageHeader::ENCODED_LENGTH + Self::BLOCK_LENGTH;
+
+    pub fn to_bytes(&self) -> [u8; Self::ENCODED_LENGTH] {
+        let mut buf = [0u8; Self::ENCODED_LENGTH];
+        Writer::framed(&mut buf, Self::TEMPLATE_ID, Self::BLOCK_LENGTH)
+            .u64(self.timestamp)
+            .u8(self.exchange_id as u8)
+            .u32(self.symbol_id)
+            .u8(self.side as u8)
+            .i64(self.price)
+            .u64(self.quantity);
+        buf
+    }
+
+    pub fn from_bytes(buf: &[u8]) -> Option<Self> {
+        let mut r = Reader::framed(buf, Self::TEMPLATE_ID)?;
+        Some(Self {
+            timestamp: r.u64(),
+            exchange_id: ExchangeID::from_u8(r.u8())?,
+            symbol_id: r.u32(),
+            side: Side::from_u8(r.u8())?,
+            price: r.i64(),
+            quantity: r.u64(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_derivatives_round_trip() {
+        let open_interest =
+            OpenInterest { timestamp: 1_000, exchange_id: ExchangeID::Bybit, symbol_id: 1, open_interest: 6_874_476_100_000 };
+        assert_eq!(OpenInterest::from_bytes(&open_interest.to_bytes()), Some(open_interest));
+
+        let mark = MarkPriceUpdate {
+            timestamp: 2_000,
+            exchange_id: ExchangeID::Binance,
+            symbol_id: 1,
+            mark_price: 1_179_415_000_000,
+            index_price: 1_178_462_659_091,
+            funding_rate: -21_200,
+            next_funding_time: 28_800_000,
+        };
+        let bytes = mark.to_bytes();
+        assert_eq!(MessageHeader::read(&bytes).map(|h| h.frame_length()), Some(MarkPriceUpdate::ENCODED_LENGTH));
+        assert_eq!(MarkPriceUpdate::from_bytes(&bytes), Some(mark));
+        assert_eq!(OpenInterest::from_bytes(&bytes), None);
+
+        let liquidation = ForcedLiquidation {
+            timestamp: 3_000,
+            exchange_id: ExchangeID::Binance,
+            symbol_id: 1,
+            side: Side::Buy,
+            price: 991_000_000_000,
+            quantity: 1_400_000,
+        };
+        assert_eq!(ForcedLiquidation::from_bytes(&liquidation.to_bytes()), Some(liquidation));
+    }
+}
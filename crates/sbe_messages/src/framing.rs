@@ -0,0 +1,413 @@
+//! SBE framing: the message header and the framed encodings of the order
+//! path messages, as sent over the order entry session.
+
+use crate::{
+    ExchangeID, ExecutionReport, MarketDataUpdate, OrdStatus, OrdType, OrderCancel, OrderEntry, Side, TimeInForce,
+    Trade,
+};
+
+/// SBE message header. Messages added after `MarketDataUpdate` are framed
+/// with it. Market data updates broadcast to the frontend are still sent
+/// bare, and are always exactly `MarketDataUpdate::ENCODED_LENGTH` bytes,
+/// which no framed message is; `read` refuses such a buffer, so a bare
+/// update is never taken for a header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MessageHeader {
+    pub block_length: u16,
+    pub template_id: u16,
+    pub schema_id: u16,
+    pub version: u16,
+}
+
+impl MessageHeader {
+    pub const ENCODED_LENGTH: usize = 8;
+    pub const SCHEMA_ID: u16 = 1;
+    pub const SCHEMA_VERSION: u16 = 1;
+
+    pub fn new(template_id: u16, block_length: usize) -> Self {
+        Self {
+            block_length: block_length as u16,
+            template_id,
+            schema_id: Self::SCHEMA_ID,
+            version: Self::SCHEMA_VERSION,
+        }
+    }
+
+    pub fn write(&self, buf: &mut [u8]) {
+        buf[0..2].copy_from_slice(&self.block_length.to_le_bytes());
+        buf[2..4].copy_from_slice(&self.template_id.to_le_bytes());
+        buf[4..6].copy_from_slice(&self.schema_id.to_le_bytes());
+        buf[6..8].copy_from_slice(&self.version.to_le_bytes());
+    }
+
+    /// Header at the start of `buf`, if it is from our schema and the whole
+    /// message is present. A buffer of exactly `MarketDataUpdate::ENCODED_LENGTH`
+    /// bytes is a bare update and has no header.
+    pub fn read(buf: &[u8]) -> Option<Self> {
+        if buf.len() < Self::ENCODED_LENGTH || buf.len() == MarketDataUpdate::ENCODED_LENGTH {
+            return None;
+        }
+        let header = Self {
+            block_length: u16::from_le_bytes([buf[0], buf[1]]),
+            template_id: u16::from_le_bytes([buf[2], buf[3]]),
+            schema_id: u16::from_le_bytes([buf[4], buf[5]]),
+            version: u16::from_le_bytes([buf[6], buf[7]]),
+        };
+        let complete = buf.len() >= header.frame_length();
+        (header.schema_id == Self::SCHEMA_ID && complete).then_some(header)
+    }
+
+    /// Header plus body
+    pub fn frame_length(&self) -> usize {
+        Self::ENCODED_LENGTH + self.block_length as usize
+    }
+}
+
+/// Sequential little-endian field writer
+pub(crate) struct Writer<'a> {
+    buf: &'a mut [u8],
+    pos: usize,
+}
+
+impl<'a> Writer<'a> {
+    /// Write the header for `template_id` and position after it
+    pub(crate) fn framed(buf: &'a mut [u8], template_id: u16, block_length: usize) -> Self {
+        MessageHeader::new(template_id, block_length).write(buf);
+        Self { buf, pos: MessageHeader::ENCODED_LENGTH }
+    }
+
+    fn put<const N: usize>(&mut self, bytes: [u8; N]) -> &mut Self {
+        self.buf[self.pos..self.pos + N].copy_from_slice(&bytes);
+        self.pos += N;
+        self
+    }
+
+    pub(crate) fn u8(&mut self, value: u8) -> &mut Self {
+        self.put([value])
+    }
+
+    pub(crate) fn u32(&mut self, value: u32) -> &mut Self {
+        self.put(value.to_le_bytes())
+    }
+
+    pub(crate) fn u64(&mut self, value: u64) -> &mut Self {
+        self.put(value.to_le_bytes())
+    }
+
+    pub(crate) fn i64(&mut self, value: i64) -> &mut Self {
+        self.put(value.to_le_bytes())
+    }
+}
+
+/// Sequential little-endian field reader over a complete frame
+pub(crate) struct Reader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    /// Reader over the body of `buf`, if it is a complete `template_id` frame
+    pub(crate) fn framed(buf: &'a [u8], template_id: u16) -> Option<Self> {
+        let header = MessageHeader::read(buf)?;
+        (header.template_id == template_id).then_some(Self { buf, pos: MessageHeader::ENCODED_LENGTH })
+    }
+
+    fn take<const N: usize>(&mut self) -> [u8; N] {
+        let mut bytes = [0u8; N];
+        bytes.copy_from_slice(&self.buf[self.pos..self.pos + N]);
+        self.pos += N;
+        bytes
+    }
+
+    pub(crate) fn u8(&mut self) -> u8 {
+        self.take::<1>()[0]
+    }
+
+    pub(crate) fn u32(&mut self) -> u32 {
+        u32::from_le_bytes(self.take())
+    }
+
+    pub(crate) fn u64(&mut self) -> u64 {
+        u64::from_le_bytes(self.take())
+    }
+
+    pub(crate) fn i64(&mut self) -> i64 {
+        i64::from_le_bytes(self.take())
+    }
+}
+
+impl Side {
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            1 => Some(Side::Buy),
+            2 => Some(Side::Sell),
+            _ => None,
+        }
+    }
+}
+
+impl ExchangeID {
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            1 => Some(ExchangeID::Binance),
+            2 => Some(ExchangeID::Bybit),
+            3 => Some(ExchangeID::Coinbase),
+            _ => None,
+        }
+    }
+}
+
+impl OrdType {
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            1 => Some(OrdType::Limit),
+            2 => Some(OrdType::Market),
+            _ => None,
+        }
+    }
+}
+
+impl TimeInForce {
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            1 => Some(TimeInForce::Gtc),
+            2 => Some(TimeInForce::Ioc),
+            _ => None,
+        }
+    }
+}
+
+impl OrdStatus {
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            1 => Some(OrdStatus::New),
+            2 => Some(OrdStatus::PartiallyFilled),
+            3 => Some(OrdStatus::Filled),
+            4 => Some(OrdStatus::Canceled),
+            5 => Some(OrdStatus::Rejected),
+            _ => None,
+        }
+    }
+}
+
+impl MarketDataUpdate {
+    pub const TEMPLATE_ID: u16 = 1;
+    pub const FRAME_LENGTH: usize = MessageHeader::ENCODED_LENGTH + Self::ENCODED_LENGTH;
+
+    /// Framed encoding, for order entry sessions that mix market data with
+    /// other messages. The frontend broadcast uses the bare `to_bytes`.
+    pub fn to_frame(&self) -> [u8; Self::FRAME_LENGTH] {
+        let mut buf = [0u8; Self::FRAME_LENGTH];
+        MessageHeader::new(Self::TEMPLATE_ID, Self::ENCODED_LENGTH).write(&mut buf);
+        buf[MessageHeader::ENCODED_LENGTH..].copy_from_slice(&self.to_bytes());
+        buf
+    }
+
+    pub fn from_frame(buf: &[u8]) -> Option<Self> {
+        let mut r = Reader::framed(buf, Self::TEMPLATE_ID)?;
+        Some(Self {
+            timestamp: r.u64(),
+            exchange_id: ExchangeID::from_u8(r.u8())?,
+            symbol_id: r.u32(),
+            side: Side::from_u8(r.u8())?,
+            price: r.i64(),
+            quantity: r.u64(),
+            is_snapshot: r.u8(),
+        })
+    }
+}
+
+impl OrderEntry {
+    pub const TEMPLATE_ID: u16 = 2;
+    pub const BLOCK_LENGTH: usize = 40;
+    pub const ENCODED_LENGTH: usize = MessageHeader::ENCODED_LENGTH + Self::BLOCK_LENGTH;
+
+    pub fn to_bytes(&self) -> [u8; Self::ENCODED_LENGTH] {
+        let mut buf = [0u8; Self::ENCODED_LENGTH];
+        Writer::framed(&mut buf, Self::TEMPLATE_ID, Self::BLOCK_LENGTH)
+            .u64(self.timestamp)
+            .u64(self.cl_ord_id)
+            .u8(self.exchange_id as u8)
+            .u32(self.symbol_id)
+            .u8(self.side as u8)
+            .i64(self.price)
+            .u64(self.quantity)
+            .u8(self.order_type as u8)
+            .u8(self.time_in_force as u8);
+        buf
+    }
+
+    pub fn from_bytes(buf: &[u8]) -> Option<Self> {
+        let mut r = Reader::framed(buf, Self::TEMPLATE_ID)?;
+        Some(Self {
+            timestamp: r.u64(),
+            cl_ord_id: r.u64(),
+            exchange_id: ExchangeID::from_u8(r.u8())?,
+            symbol_id: r.u32(),
+            side: Side::from_u8(r.u8())?,
+            price: r.i64(),
+            quantity: r.u64(),
+            order_type: OrdType::from_u8(r.u8())?,
+            time_in_force: TimeInForce::from_u8(r.u8())?,
+        })
+    }
+}
+
+impl ExecutionReport {
+    pub const TEMPLATE_ID: u16 = 3;
+    pub const BLOCK_LENGTH: usize = 63;
+    pub const ENCODED_LENGTH: usize = MessageHeader::ENCODED_LENGTH + Self::BLOCK_LENGTH;
+
+    pub fn to_bytes(&self) -> [u8; Self::ENCODED_LENGTH] {
+        let mut buf = [0u8; Self::ENCODED_LENGTH];
+        Writer::framed(&mut buf, Self::TEMPLATE_ID, Self::BLOCK_LENGTH)
+            .u64(self.timestamp)
+            .u64(self.cl_ord_id)
+            .u64(self.exec_id)
+            .u8(self.exchange_id as u8)
+            .u32(self.symbol_id)
+            .u8(self.side as u8)
+            .u64(self.filled_quantity)
+            .i64(self.filled_price)
+            .u64(self.leaves_quantity)
+            .i64(self.fee)
+            .u8(self.status as u8);
+        buf
+    }
+
+    pub fn from_bytes(buf: &[u8]) -> Option<Self> {
+        let mut r = Reader::framed(buf, Self::TEMPLATE_ID)?;
+        Some(Self {
+            timestamp: r.u64(),
+            cl_ord_id: r.u64(),
+            exec_id: r.u64(),
+            exchange_id: ExchangeID::from_u8(r.u8())?,
+            symbol_id: r.u32(),
+            side: Side::from_u8(r.u8())?,
+            filled_quantity: r.u64(),
+            filled_price: r.i64(),
+            leaves_quantity: r.u64(),
+            fee: r.i64(),
+            status: OrdStatus::from_u8(r.u8())?,
+        })
+    }
+}
+
+impl Trade {
+    pub const TEMPLATE_ID: u16 = 4;
+    pub const BLOCK_LENGTH: usize = 38;
+    pub const ENCODED_LENGTH: usize = MessageHeader::ENCODED_LENGTH + Self::BLOCK_LENGTH;
+
+    pub fn to_bytes(&self) -> [u8; Self::ENCODED_LENGTH] {
+        let mut buf = [0u8; Self::ENCODED_LENGTH];
+        Writer::framed(&mut buf, Self::TEMPLATE_ID, Self::BLOCK_LENGTH)
+            .u64(self.timestamp)
+            .u8(self.exchange_id as u8)
+            .u32(self.symbol_id)
+            .u64(self.trade_id)
+            .u8(self.aggressor_side as u8)
+            .i64(self.price)
+            .u64(self.quantity);
+        buf
+    }
+
+    pub fn from_bytes(buf: &[u8]) -> Option<Self> {
+        let mut r = Reader::framed(buf, Self::TEMPLATE_ID)?;
+        Some(Self {
+            timestamp: r.u64(),
+            exchange_id: ExchangeID::from_u8(r.u8())?,
+            symbol_id: r.u32(),
+            trade_id: r.u64(),
+            aggressor_side: Side::from_u8(r.u8())?,
+            price: r.i64(),
+            quantity: r.u64(),
+        })
+    }
+}
+
+impl OrderCancel {
+    pub const TEMPLATE_ID: u16 = 5;
+    pub const BLOCK_LENGTH: usize = 22;
+    pub const ENCODED_LENGTH: usize = MessageHeader::ENCODED_LENGTH + Self::BLOCK_LENGTH;
+
+    pub fn to_bytes(&self) -> [u8; Self::ENCODED_LENGTH] {
+        let mut buf = [0u8; Self::ENCODED_LENGTH];
+        Writer::framed(&mut buf, Self::TEMPLATE_ID, Self::BLOCK_LENGTH)
+            .u64(self.timestamp)
+            .u64(self.cl_ord_id)
+            .u8(self.exchange_id as u8)
+            .u32(self.symbol_id)
+            .u8(self.side as u8);
+        buf
+    }
+
+    pub fn from_bytes(buf: &[u8]) -> Option<Self> {
+        let mut r = Reader::framed(buf, Self::TEMPLATE_ID)?;
+        Some(Self {
+            timestamp: r.u64(),
+            cl_ord_id: r.u64(),
+            exchange_id: ExchangeID::from_u8(r.u8())?,
+            symbol_id: r.u32(),
+            side: Side::from_u8(r.u8())?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_order_path_round_trip() {
+        let entry = OrderEntry {
+            timestamp: 1,
+            cl_ord_id: 2,
+            exchange_id: ExchangeID::Bybit,
+            symbol_id: 3,
+            side: Side::Sell,
+            price: -4,
+            quantity: 5,
+            order_type: OrdType::Limit,
+            time_in_force: TimeInForce::Ioc,
+        };
+        assert_eq!(OrderEntry::from_bytes(&entry.to_bytes()), Some(entry));
+
+        let report = ExecutionReport {
+            timestamp: 1,
+            cl_ord_id: 2,
+            exec_id: 3,
+            exchange_id: ExchangeID::Coinbase,
+            symbol_id: 4,
+            side: Side::Buy,
+            filled_quantity: 5,
+            filled_price: 6,
+            leaves_quantity: 7,
+            fee: -8,
+            status: OrdStatus::PartiallyFilled,
+        };
+        assert_eq!(ExecutionReport::from_bytes(&report.to_bytes()), Some(report));
+
+        let cancel =
+            OrderCancel { timestamp: 1, cl_ord_id: 2, exchange_id: ExchangeID::Binance, symbol_id: 3, side: Side::Sell };
+        assert_eq!(OrderCancel::from_bytes(&cancel.to_bytes()), Some(cancel));
+        // Truncated frames and other templates are refused
+        assert_eq!(OrderCancel::from_bytes(&cancel.to_bytes()[..20]), None);
+        assert_eq!(OrderEntry::from_bytes(&cancel.to_bytes()), None);
+    }
+
+    #[test]
+    fn test_market_data_frame() {
+        let update = MarketDataUpdate {
+            timestamp: 1,
+            exchange_id: ExchangeID::Binance,
+            symbol_id: 2,
+            side: Side::Buy,
+            price: 3,
+            quantity: 4,
+            is_snapshot: 1,
+        };
+        let frame = update.to_frame();
+        assert_eq!(MessageHeader::read(&frame).map(|h| h.frame_length()), Some(MarketDataUpdate::FRAME_LENGTH));
+        assert_eq!(MarketDataUpdate::from_frame(&frame), Some(update));
+    }
+}
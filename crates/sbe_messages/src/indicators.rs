@@ -0,0 +1,616 @@
+//! Analytics messages the gateway publishes to the frontend, all framed.
+
+use crate::framing::{Reader, Writer};
+use crate::{ExchangeID, MessageHeader, Side};
+
+/// Cumulative volume delta of one venue, or across venues when
+/// `exchange_id` is `None` (encoded as 0), with the current bar as a candle.
+/// Values are signed quantities (1e8): aggressive buys minus sells since the
+/// session start.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CvdUpdate {
+    pub timestamp: u64,
+    pub exchange_id: Option<ExchangeID>,
+    pub symbol_id: u32,
+    pub bar_start: u64,
+    pub open: i64,
+    pub high: i64,
+    pub low: i64,
+    /// The CVD after the latest trade
+    pub close: i64,
+    pub buy_volume: u64,
+    pub sell_volume: u64,
+    /// The bar is complete and will not change again
+    pub closed: bool,
+}
+
+impl CvdUpdate {
+    pub const TEMPLATE_ID: u16 = 7;
+    pub const BLOCK_LENGTH: usize = 70;
+    pub const ENCODED_LENGTH: usize = MessageHeader::ENCODED_LENGTH + Self::BLOCK_LENGTH;
+
+    pub fn to_bytes(&self) -> [u8; Self::ENCODED_LENGTH] {
+        let mut buf = [0u8; Self::ENCODED_LENGTH];
+        Writer::framed(&mut buf, Self::TEMPLATE_ID, Self::BLOCK_LENGTH)
+            .u64(self.timestamp)
+            .u8(self.exchange_id.map_or(0, |id| id as u8))
+            .u32(self.symbol_id)
+            .u64(self.bar_start)
+            .i64(self.open)
+            .i64(self.high)
+            .i64(self.low)
+            .i64(self.close)
+            .u64(self.buy_volume)
+            .u64(self.sell_volume)
+            .u8(self.closed as u8);
+        buf
+    }
+
+    pub fn from_bytes(buf: &[u8]) -> Option<Self> {
+        let mut r = Reader::framed(buf, Self::TEMPLATE_ID)?;
+        Some(Self {
+            timestamp: r.u64(),
+            exchange_id: ExchangeID::from_u8(r.u8()),
+            symbol_id: r.u32(),
+            bar_start: r.u64(),
+            open: r.i64(),
+            high: r.i64(),
+            low: r.i64(),
+            close: r.i64(),
+            buy_volume: r.u64(),
+            sell_volume: r.u64(),
+            closed: r.u8() != 0,
+        })
+    }
+}
+
+/// How price and CVD disagreed between two swing points
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum DivergenceKind {
+    /// Price made a new extreme that CVD did not confirm
+    Regular = 1,
+    /// CVD made a new extreme that price did not follow
+    Hidden = 2,
+    /// Price held the level while aggressive flow pushed CVD through it
+    Absorption = 3,
+}
+
+impl DivergenceKind {
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            1 => Some(DivergenceKind::Regular),
+            2 => Some(DivergenceKind::Hidden),
+            3 => Some(DivergenceKind::Absorption),
+            _ => None,
+        }
+    }
+
+    /// Chart annotation: `R` for reversal setups, `C` for continuation
+    pub fn marker(self) -> char {
+        match self {
+            DivergenceKind::Regular | DivergenceKind::Absorption => 'R',
+            DivergenceKind::Hidden => 'C',
+        }
+    }
+}
+
+/// A price/CVD divergence confirmed at a swing point. `direction` is the
+/// expected move: `Buy` for bullish, `Sell` for bearish.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DivergenceSignal {
+    pub timestamp: u64,
+    pub exchange_id: Option<ExchangeID>,
+    pub symbol_id: u32,
+    pub kind: DivergenceKind,
+    pub direction: Side,
+    /// Start of the bar of the swing that completed the divergence
+    pub pivot_time: u64,
+    pub pivot_price: i64,
+    pub pivot_cvd: i64,
+    /// The earlier swing it is compared with
+    pub prev_pivot_time: u64,
+    pub prev_pivot_price: i64,
+    pub prev_pivot_cvd: i64,
+    /// 0 to 100, from the pivot bar's volume relative to recent bars
+    pub confidence: u8,
+}
+
+impl DivergenceSignal {
+    pub const TEMPLATE_ID: u16 = 8;
+    pub const BLOCK_LENGTH: usize = 64;
+    pub const ENCODED_LENGTH: usize = MessageHeader::ENCODED_LENGTH + Self::BLOCK_LENGTH;
+
+    pub fn to_bytes(&self) -> [u8; Self::ENCODED_LENGTH] {
+        let mut buf = [0u8; Self::ENCODED_LENGTH];
+        Writer::framed(&mut buf, Self::TEMPLATE_ID, Self::BLOCK_LENGTH)
+            .u64(self.timestamp)
+            .u8(self.exchange_id.map_or(0, |id| id as u8))
+            .u32(self.symbol_id)
+            .u8(self.kind as u8)
+            .u8(self.direction as u8)
+            .u64(self.pivot_time)
+            .i64(self.pivot_price)
+            .i64(self.pivot_cvd)
+            .u64(self.prev_pivot_time)
+            .i64(self.prev_pivot_price)
+            .i64(self.prev_pivot_cvd)
+            .u8(self.confidence);
+        buf
+    }
+
+    pub fn from_bytes(buf: &[u8]) -> Option<Self> {
+        let mut r = Reader::framed(buf, Self::TEMPLATE_ID)?;
+        Some(Self {
+            timestamp: r.u64(),
+            exchange_id: ExchangeID::from_u8(r.u8()),
+            symbol_id: r.u32(),
+            kind: DivergenceKind::from_u8(r.u8())?,
+            direction: Side::from_u8(r.u8())?,
+            pivot_time: r.u64(),
+            pivot_price: r.i64(),
+            pivot_cvd: r.i64(),
+            prev_pivot_time: r.u64(),
+            prev_pivot_price: r.i64(),
+            prev_pivot_cvd: r.i64(),
+            confidence: r.u8(),
+        })
+    }
+}
+
+/// Which indicator an `IndicatorUpdate` carries
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum IndicatorKind {
+    /// Order flow imbalance at the touch over `window`, a signed quantity (1e8)
+    Ofi = 1,
+    /// Normalized order book imbalance in `[-1, 1]` (1e8), time-weighted over `window`
+    Nobi = 2,
+    /// Cumulative volume delta since the session start, a signed quantity (1e8)
+    Cvd = 3,
+    /// Best ask minus best bid in basis points of the mid (1e8)
+    Spread = 4,
+}
+
+impl IndicatorKind {
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            1 => Some(IndicatorKind::Ofi),
+            2 => Some(IndicatorKind::Nobi),
+            3 => Some(IndicatorKind::Cvd),
+            4 => Some(IndicatorKind::Spread),
+            _ => None,
+        }
+    }
+}
+
+/// Latest value of a scalar indicator for one venue, or across venues when
+/// `exchange_id` is `None` (encoded as 0). `window` is the trailing span the
+/// value covers, in the units of `timestamp`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IndicatorUpdate {
+    pub timestamp: u64,
+    pub exchange_id: Option<ExchangeID>,
+    pub symbol_id: u32,
+    pub kind: IndicatorKind,
+    pub window: u64,
+    /// Fixed point (1e8); the unit depends on `kind`
+    pub value: i64,
+}
+
+impl IndicatorUpdate {
+    pub const TEMPLATE_ID: u16 = 9;
+    pub const BLOCK_LENGTH: usize = 30;
+    pub const ENCODED_LENGTH: usize = MessageHeader::ENCODED_LENGTH + Self::BLOCK_LENGTH;
+
+    pub fn to_bytes(&self) -> [u8; Self::ENCODED_LENGTH] {
+        let mut buf = [0u8; Self::ENCODED_LENGTH];
+        Writer::framed(&mut buf, Self::TEMPLATE_ID, Self::BLOCK_LENGTH)
+            .u64(self.timestamp)
+            .u8(self.exchange_id.map_or(0, |id| id as u8))
+            .u32(self.symbol_id)
+            .u8(self.kind as u8)
+            .u64(self.window)
+            .i64(self.value);
+        buf
+    }
+
+    pub fn from_bytes(buf: &[u8]) -> Option<Self> {
+        let mut r = Reader::framed(buf, Self::TEMPLATE_ID)?;
+        Some(Self {
+            timestamp: r.u64(),
+            exchange_id: ExchangeID::from_u8(r.u8()),
+            symbol_id: r.u32(),
+            kind: IndicatorKind::from_u8(r.u8())?,
+            window: r.u64(),
+            value: r.i64(),
+        })
+    }
+}
+
+/// An alert rule fired on an indicator of one venue, or across venues when
+/// `exchange_id` is `None` (encoded as 0). `direction` is the pressure the
+/// alert points to: `Buy` for rising or bullish, `Sell` for falling.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Alert {
+    pub timestamp: u64,
+    pub exchange_id: Option<ExchangeID>,
+    pub symbol_id: u32,
+    pub rule_id: u32,
+    pub indicator: IndicatorKind,
+    pub direction: Side,
+    /// Indicator value that fired the rule (1e8, unit of `indicator`)
+    pub value: i64,
+    /// Its rate of change per second at that point (1e8)
+    pub rate: i64,
+}
+
+impl Alert {
+    pub const TEMPLATE_ID: u16 = 10;
+    pub const BLOCK_LENGTH: usize = 35;
+    pub const ENCODED_LENGTH: usize = MessageHeader::ENCODED_LENGTH + Self::BLOCK_LENGTH;
+
+    /// Rule id of the PRD's "Aggressive Flow" alert: NOBI through +/-0.5 with
+    /// an acceleration above 0.1/s
+    pub const AGGRESSIVE_FLOW: u32 = 1;
+
+    pub fn to_bytes(&self) -> [u8; Self::ENCODED_LENGTH] {
+        let mut buf = [0u8; Self::ENCODED_LENGTH];
+        Writer::framed(&mut buf, Self::TEMPLATE_ID, Self::BLOCK_LENGTH)
+            .u64(self.timestamp)
+            .u8(self.exchange_id.map_or(0, |id| id as u8))
+            .u32(self.symbol_id)
+            .u32(self.rule_id)
+            .u8(self.indicator as u8)
+            .u8(self.direction as u8)
+            .i64(self.value)
+            .i64(self.rate);
+        buf
+    }
+
+    pub fn from_bytes(buf: &[u8]) -> Option<Self> {
+        let mut r = Reader::framed(buf, Self::TEMPLATE_ID)?;
+        Some(Self {
+            timestamp: r.u64(),
+            exchange_id: ExchangeID::from_u8(r.u8()),
+            symbol_id: r.u32(),
+            rule_id: r.u32(),
+            indicator: IndicatorKind::from_u8(r.u8())?,
+            direction: Side::from_u8(r.u8())?,
+            value: r.i64(),
+            rate: r.i64(),
+        })
+    }
+}
+
+/// Estimated size of leveraged positions that would be liquidated in one
+/// price bucket, `[price, price + bucket_size)`. `side` is the side of the
+/// positions: `Buy` for longs, liquidated as price falls into the bucket,
+/// `Sell` for shorts. An intensity of 0 clears the bucket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LiquidationLevel {
+    pub timestamp: u64,
+    pub exchange_id: Option<ExchangeID>,
+    pub symbol_id: u32,
+    pub side: Side,
+    pub price: i64,
+    pub bucket_size: i64,
+    /// Quantity (1e8)
+    pub intensity: u64,
+}
+
+impl LiquidationLevel {
+    pub const TEMPLATE_ID: u16 = 11;
+    pub const BLOCK_LENGTH: usize = 38;
+    pub const ENCODED_LENGTH: usize = MessageHeader::ENCODED_LENGTH + Self::BLOCK_LENGTH;
+
+    pub fn to_bytes(&self) -> [u8; Self::ENCODED_LENGTH] {
+        let mut buf = [0u8; Self::ENCODED_LENGTH];
+        Writer::framed(&mut buf, Self::TEMPLATE_ID, Self::BLOCK_LENGTH)
+            .u64(self.timestamp)
+            .u8(self.exchange_id.map_or(0, |id| id as u8))
+            .u32(self.symbol_id)
+            .u8(self.side as u8)
+            .i64(self.price)
+            .i64(self.bucket_size)
+            .u64(self.intensity);
+        buf
+    }
+
+    pub fn from_bytes(buf: &[u8]) -> Option<Self> {
+        let mut r = Reader::framed(buf, Self::TEMPLATE_ID)?;
+        Some(Self {
+            timestamp: r.u64(),
+            exchange_id: ExchangeID::from_u8(r.u8()),
+            symbol_id: r.u32(),
+            side: Side::from_u8(r.u8())?,
+            price: r.i64(),
+            bucket_size: r.i64(),
+            intensity: r.u64(),
+        })
+    }
+}
+
+/// Time span a volume profile covers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum ProfileSpan {
+    /// Since the start of the current session
+    Session = 1,
+    /// A trailing window ending now
+    Rolling = 2,
+    /// A fixed range, e.g. the part of the chart on screen
+    VisibleRange = 3,
+}
+
+impl ProfileSpan {
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            1 => Some(ProfileSpan::Session),
+            2 => Some(ProfileSpan::Rolling),
+            3 => Some(ProfileSpan::VisibleRange),
+            _ => None,
+        }
+    }
+}
+
+/// Volume node a profile bucket sits on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[repr(u8)]
+pub enum VolumeNode {
+    #[default]
+    None = 0,
+    /// High-volume node: a local peak of the profile, where price was accepted
+    High = 1,
+    /// Low-volume node: a local trough, where price moved through quickly
+    Low = 2,
+}
+
+impl VolumeNode {
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(VolumeNode::None),
+            1 => Some(VolumeNode::High),
+            2 => Some(VolumeNode::Low),
+            _ => None,
+        }
+    }
+}
+
+/// Traded volume in one price bucket of a volume profile, for one venue or
+/// across venues when `exchange_id` is `None` (encoded as 0). Only changed
+/// buckets are sent; a volume of 0 clears the bucket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VolumeProfileLevel {
+    pub timestamp: u64,
+    pub exchange_id: Option<ExchangeID>,
+    pub symbol_id: u32,
+    pub span: ProfileSpan,
+    /// Lower bound of the bucket
+    pub price: i64,
+    pub bucket_size: i64,
+    /// Quantity (1e8)
+    pub volume: u64,
+    pub node: VolumeNode,
+}
+
+impl VolumeProfileLevel {
+    pub const TEMPLATE_ID: u16 = 15;
+    pub const BLOCK_LENGTH: usize = 39;
+    pub const ENCODED_LENGTH: usize = MessageHeader::ENCODED_LENGTH + Self::BLOCK_LENGTH;
+
+    pub fn to_bytes(&self) -> [u8; Self::ENCODED_LENGTH] {
+        let mut buf = [0u8; Self::ENCODED_LENGTH];
+        Writer::framed(&mut buf, Self::TEMPLATE_ID, Self::BLOCK_LENGTH)
+            .u64(self.timestamp)
+            .u8(self.exchange_id.map_or(0, |id| id as u8))
+            .u32(self.symbol_id)
+            .u8(self.span as u8)
+            .i64(self.price)
+            .i64(self.bucket_size)
+            .u64(self.volume)
+            .u8(self.node as u8);
+        buf
+    }
+
+    pub fn from_bytes(buf: &[u8]) -> Option<Self> {
+        let mut r = Reader::framed(buf, Self::TEMPLATE_ID)?;
+        Some(Self {
+            timestamp: r.u64(),
+            exchange_id: ExchangeID::from_u8(r.u8()),
+            symbol_id: r.u32(),
+            span: ProfileSpan::from_u8(r.u8())?,
+            price: r.i64(),
+            bucket_size: r.i64(),
+            volume: r.u64(),
+            node: VolumeNode::from_u8(r.u8())?,
+        })
+    }
+}
+
+/// Point of control and value area of a volume profile over `[start, end)`.
+/// `poc` is the lower bound of the busiest bucket; the value area runs from
+/// the lower bound of its lowest bucket to the upper bound of its highest.
+/// An empty profile has a `total_volume` of 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VolumeProfileSummary {
+    pub timestamp: u64,
+    pub exchange_id: Option<ExchangeID>,
+    pub symbol_id: u32,
+    pub span: ProfileSpan,
+    pub start: u64,
+    pub end: u64,
+    pub poc: i64,
+    pub value_area_low: i64,
+    pub value_area_high: i64,
+    /// Quantity (1e8)
+    pub total_volume: u64,
+}
+
+impl VolumeProfileSummary {
+    pub const TEMPLATE_ID: u16 = 16;
+    pub const BLOCK_LENGTH: usize = 62;
+    pub const ENCODED_LENGTH: usize = MessageHeader::ENCODED_LENGTH + Self::BLOCK_LENGTH;
+
+    pub fn to_bytes(&self) -> [u8; Self::ENCODED_LENGTH] {
+        let mut buf = [0u8; Self::ENCODED_LENGTH];
+        Writer::framed(&mut buf, Self::TEMPLATE_ID, Self::BLOCK_LENGTH)
+            .u64(self.timestamp)
+            .u8(self.exchange_id.map_or(0, |id| id as u8))
+            .u32(self.symbol_id)
+            .u8(self.span as u8)
+            .u64(self.start)
+            .u64(self.end)
+            .i64(self.poc)
+            .i64(self.value_area_low)
+            .i64(self.value_area_high)
+            .u64(self.total_volume);
+        buf
+    }
+
+    pub fn from_bytes(buf: &[u8]) -> Option<Self> {
+        let mut r = Reader::framed(buf, Self::TEMPLATE_ID)?;
+        Some(Self {
+            timestamp: r.u64(),
+            exchange_id: ExchangeID::from_u8(r.u8()),
+            symbol_id: r.u32(),
+            span: ProfileSpan::from_u8(r.u8())?,
+            start: r.u64(),
+            end: r.u64(),
+            poc: r.i64(),
+            value_area_low: r.i64(),
+            value_area_high: r.i64(),
+            total_volume: r.u64(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cvd_update_round_trip() {
+        let update = CvdUpdate {
+            timestamp: 61_000,
+            exchange_id: Some(ExchangeID::Bybit),
+            symbol_id: 1,
+            bar_start: 60_000,
+            open: -5,
+            high: 12,
+            low: -9,
+            close: 3,
+            buy_volume: 20,
+            sell_volume: 12,
+            closed: true,
+        };
+        let bytes = update.to_bytes();
+        assert_eq!(MessageHeader::read(&bytes).map(|h| h.frame_length()), Some(CvdUpdate::ENCODED_LENGTH));
+        assert_eq!(CvdUpdate::from_bytes(&bytes), Some(update));
+    }
+
+    #[test]
+    fn test_divergence_signal_round_trip() {
+        let signal = DivergenceSignal {
+            timestamp: 600,
+            exchange_id: None,
+            symbol_id: 1,
+            kind: DivergenceKind::Hidden,
+            direction: Side::Buy,
+            pivot_time: 400,
+            pivot_price: 101,
+            pivot_cvd: -30,
+            prev_pivot_time: 100,
+            prev_pivot_price: 99,
+            prev_pivot_cvd: -10,
+            confidence: 72,
+        };
+        let bytes = signal.to_bytes();
+        assert_eq!(MessageHeader::read(&bytes).map(|h| h.frame_length()), Some(DivergenceSignal::ENCODED_LENGTH));
+        assert_eq!(DivergenceSignal::from_bytes(&bytes), Some(signal));
+        assert_eq!(signal.kind.marker(), 'C');
+    }
+
+    #[test]
+    fn test_indicator_update_round_trip() {
+        let update = IndicatorUpdate {
+            timestamp: 1_000,
+            exchange_id: None,
+            symbol_id: 1,
+            kind: IndicatorKind::Ofi,
+            window: 10_000,
+            value: -250_000_000,
+        };
+        let bytes = update.to_bytes();
+        assert_eq!(MessageHeader::read(&bytes).map(|h| h.frame_length()), Some(IndicatorUpdate::ENCODED_LENGTH));
+        assert_eq!(IndicatorUpdate::from_bytes(&bytes), Some(update));
+        assert_eq!(CvdUpdate::from_bytes(&bytes), None);
+    }
+
+    #[test]
+    fn test_alert_round_trip() {
+        let alert = Alert {
+            timestamp: 2_000,
+            exchange_id: None,
+            symbol_id: 1,
+            rule_id: Alert::AGGRESSIVE_FLOW,
+            indicator: IndicatorKind::Nobi,
+            direction: Side::Sell,
+            value: -52_000_000,
+            rate: -15_000_000,
+        };
+        let bytes = alert.to_bytes();
+        assert_eq!(MessageHeader::read(&bytes).map(|h| h.frame_length()), Some(Alert::ENCODED_LENGTH));
+        assert_eq!(Alert::from_bytes(&bytes), Some(alert));
+    }
+
+    #[test]
+    fn test_liquidation_level_round_trip() {
+        let level = LiquidationLevel {
+            timestamp: 60_000,
+            exchange_id: Some(ExchangeID::Binance),
+            symbol_id: 1,
+            side: Side::Sell,
+            price: 6_600_000_000_000,
+            bucket_size: 2_500_000_000,
+            intensity: 12_500_000,
+        };
+        let bytes = level.to_bytes();
+        assert_eq!(MessageHeader::read(&bytes).map(|h| h.frame_length()), Some(LiquidationLevel::ENCODED_LENGTH));
+        assert_eq!(LiquidationLevel::from_bytes(&bytes), Some(level));
+    }
+
+    #[test]
+    fn test_volume_profile_round_trip() {
+        let level = VolumeProfileLevel {
+            timestamp: 1_000,
+            exchange_id: None,
+            symbol_id: 1,
+            span: ProfileSpan::Rolling,
+            price: 6_500_000_000_000,
+            bucket_size: 2_500_000_000,
+            volume: 340_000_000,
+            node: VolumeNode::High,
+        };
+        let bytes = level.to_bytes();
+        assert_eq!(MessageHeader::read(&bytes).map(|h| h.frame_length()), Some(VolumeProfileLevel::ENCODED_LENGTH));
+        assert_eq!(VolumeProfileLevel::from_bytes(&bytes), Some(level));
+
+        let summary = VolumeProfileSummary {
+            timestamp: 1_000,
+            exchange_id: Some(ExchangeID::Coinbase),
+            symbol_id: 1,
+            span: ProfileSpan::Session,
+            start: 0,
+            end: 1_000,
+            poc: 6_500_000_000_000,
+            value_area_low: 6_490_000_000_000,
+            value_area_high: 6_512_500_000_000,
+            total_volume: 1_200_000_000,
+        };
+        let bytes = summary.to_bytes();
+        assert_eq!(MessageHeader::read(&bytes).map(|h| h.frame_length()), Some(VolumeProfileSummary::ENCODED_LENGTH));
+        assert_eq!(VolumeProfileSummary::from_bytes(&bytes), Some(summary));
+        assert_eq!(VolumeProfileLevel::from_bytes(&bytes), None);
+    }
+}
@@ -6,6 +6,18 @@
 // For the purpose of this scaffolding, we will define the structs manually to ensure compilation
 // of dependent crates, as we cannot run the actual SBE generator in this environment.
 
+mod derivatives;
+mod framing;
+mod indicators;
+
+pub use derivatives::{ForcedLiquidation, MarkPriceUpdate, OpenInterest};
+pub use framing::MessageHeader;
+pub use indicators::{
+    Alert, CvdUpdate, DivergenceKind, DivergenceSignal, IndicatorKind, IndicatorUpdate, LiquidationLevel, ProfileSpan,
+    VolumeNode, VolumeProfileLevel, VolumeProfileSummary,
+};
+use framing::{Reader, Writer};
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
 pub enum Side {
@@ -21,7 +33,37 @@ pub enum ExchangeID {
     Coinbase = 3,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum OrdType {
+    Limit = 1,
+    Market = 2,
+}
+
+/// How long an order may rest. IOC orders cancel whatever they cannot fill on arrival.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum TimeInForce {
+    Gtc = 1,
+    Ioc = 2,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum OrdStatus {
+    New = 1,
+    PartiallyFilled = 2,
+    Filled = 3,
+    Canceled = 4,
+    Rejected = 5,
+}
+
+/// One price level change. It has two encodings: `to_bytes` is bare, with no
+/// header, and is what the gateway broadcasts to frontend clients; `to_frame`
+/// carries the header (template 1) and is used on order entry sessions such
+/// as the mock exchange's, where book updates share the stream with trades
+/// and execution reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct MarketDataUpdate {
     pub timestamp: u64,
     pub exchange_id: ExchangeID,
@@ -33,8 +75,10 @@ pub struct MarketDataUpdate {
 }
 
 impl MarketDataUpdate {
-    pub fn to_bytes(&self) -> [u8; 31] {
-        let mut buf = [0u8; 31];
+    pub const ENCODED_LENGTH: usize = 31;
+
+    pub fn to_bytes(&self) -> [u8; Self::ENCODED_LENGTH] {
+        let mut buf = [0u8; Self::ENCODED_LENGTH];
         buf[0..8].copy_from_slice(&self.timestamp.to_le_bytes());
         buf[8] = self.exchange_id as u8;
         buf[9..13].copy_from_slice(&self.symbol_id.to_le_bytes());
@@ -46,7 +90,7 @@ impl MarketDataUpdate {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct OrderEntry {
     pub timestamp: u64,
     pub cl_ord_id: u64,
@@ -55,15 +99,141 @@ pub struct OrderEntry {
     pub side: Side,
     pub price: i64,
     pub quantity: u64,
-    pub order_type: u8,
+    pub order_type: OrdType,
+    pub time_in_force: TimeInForce,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct ExecutionReport {
     pub timestamp: u64,
     pub cl_ord_id: u64,
     pub exec_id: u64,
+    pub exchange_id: ExchangeID,
+    pub symbol_id: u32,
+    pub side: Side,
+    /// Quantity filled by this execution (0 for non-fill reports)
     pub filled_quantity: u64,
     pub filled_price: i64,
-    pub status: u8,
+    /// Quantity still open on the book after this execution
+    pub leaves_quantity: u64,
+    /// Fee charged for this execution in quote units (1e8), negative for rebates
+    pub fee: i64,
+    pub status: OrdStatus,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Trade {
+    pub timestamp: u64,
+    pub exchange_id: ExchangeID,
+    pub symbol_id: u32,
+    pub trade_id: u64,
+    /// Side of the aggressor (taker) of the trade
+    pub aggressor_side: Side,
+    pub price: i64,
+    pub quantity: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OrderCancel {
+    pub timestamp: u64,
+    pub cl_ord_id: u64,
+    pub exchange_id: ExchangeID,
+    pub symbol_id: u32,
+    /// Side of the order to cancel, echoed on a cancel reject
+    pub side: Side,
+}
+
+/// Position and PnL of one venue/symbol, or of a symbol netted across venues
+/// when `exchange_id` is `None` (encoded as 0). Money fields are in quote
+/// units (1e8).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PnlSnapshot {
+    pub timestamp: u64,
+    pub exchange_id: Option<ExchangeID>,
+    pub symbol_id: u32,
+    /// Signed position, negative when short
+    pub position: i64,
+    pub avg_entry_price: i64,
+    pub mark_price: i64,
+    pub realized_pnl: i64,
+    pub unrealized_pnl: i64,
+    /// Fees paid, negative for net rebates
+    pub fees: i64,
+    /// Funding received, negative when paid
+    pub funding: i64,
+}
+
+impl PnlSnapshot {
+    pub const TEMPLATE_ID: u16 = 6;
+    pub const BLOCK_LENGTH: usize = 69;
+    pub const ENCODED_LENGTH: usize = MessageHeader::ENCODED_LENGTH + Self::BLOCK_LENGTH;
+
+    pub fn to_bytes(&self) -> [u8; Self::ENCODED_LENGTH] {
+        let mut buf = [0u8; Self::ENCODED_LENGTH];
+        Writer::framed(&mut buf, Self::TEMPLATE_ID, Self::BLOCK_LENGTH)
+            .u64(self.timestamp)
+            .u8(self.exchange_id.map_or(0, |id| id as u8))
+            .u32(self.symbol_id)
+            .i64(self.position)
+            .i64(self.avg_entry_price)
+            .i64(self.mark_price)
+            .i64(self.realized_pnl)
+            .i64(self.unrealized_pnl)
+            .i64(self.fees)
+            .i64(self.funding);
+        buf
+    }
+
+    pub fn from_bytes(buf: &[u8]) -> Option<Self> {
+        let mut r = Reader::framed(buf, Self::TEMPLATE_ID)?;
+        Some(Self {
+            timestamp: r.u64(),
+            exchange_id: ExchangeID::from_u8(r.u8()),
+            symbol_id: r.u32(),
+            position: r.i64(),
+            avg_entry_price: r.i64(),
+            mark_price: r.i64(),
+            realized_pnl: r.i64(),
+            unrealized_pnl: r.i64(),
+            fees: r.i64(),
+            funding: r.i64(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pnl_snapshot_round_trip() {
+        let snapshot = PnlSnapshot {
+            timestamp: 42,
+            exchange_id: None,
+            symbol_id: 7,
+            position: -150,
+            avg_entry_price: 10_000,
+            mark_price: 9_990,
+            realized_pnl: 25,
+            unrealized_pnl: 15,
+            fees: -3,
+            funding: 4,
+        };
+        let bytes = snapshot.to_bytes();
+        assert_eq!(MessageHeader::read(&bytes).map(|h| h.template_id), Some(PnlSnapshot::TEMPLATE_ID));
+        assert_eq!(PnlSnapshot::from_bytes(&bytes), Some(snapshot));
+        assert_eq!(ExecutionReport::from_bytes(&bytes), None);
+
+        // Bare market data updates carry no header
+        let update = MarketDataUpdate {
+            timestamp: 1,
+            exchange_id: ExchangeID::Binance,
+            symbol_id: 1,
+            side: Side::Buy,
+            price: 1,
+            quantity: 1,
+            is_snapshot: 0,
+        };
+        assert_eq!(MessageHeader::read(&update.to_bytes()), None);
+    }
 }
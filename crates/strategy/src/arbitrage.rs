@@ -0,0 +1,426 @@
+use log::{info, warn};
+use vibe_hft_core::{Instrument, Price, Quantity};
+use vibe_hft_market_data::GlobalOrderBook;
+use vibe_hft_sbe_messages::{ExchangeID, ExecutionReport, MarketDataUpdate, Side};
+
+use crate::context::{FeedStatus, StrategyContext};
+use crate::Strategy;
+
+const FIXED_POINT: f64 = 100_000_000.0;
+
+/// Venues held side by side in `GlobalOrderBook`
+pub const VENUES: [ExchangeID; 3] = [ExchangeID::Binance, ExchangeID::Bybit, ExchangeID::Coinbase];
+
+/// Taker fee per venue (bps)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct VenueFees {
+    pub binance: f64,
+    pub bybit: f64,
+    pub coinbase: f64,
+}
+
+impl VenueFees {
+    pub fn get(&self, exchange_id: ExchangeID) -> f64 {
+        match exchange_id {
+            ExchangeID::Binance => self.binance,
+            ExchangeID::Bybit => self.bybit,
+            ExchangeID::Coinbase => self.coinbase,
+        }
+    }
+}
+
+/// What to do with the excess when only part of a pair fills
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LegRecovery {
+    /// Complete the trade at market on the venue whose leg came up short
+    Hedge,
+    /// Reverse the excess at market on the venue where it was filled
+    Unwind,
+}
+
+/// Buying on one venue and selling on another, sized by the depth on both legs
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Opportunity {
+    pub buy_venue: ExchangeID,
+    pub sell_venue: ExchangeID,
+    /// Worst ask taken on the buy venue, used as the IOC limit
+    pub buy_price: Price,
+    /// Worst bid hit on the sell venue, used as the IOC limit
+    pub sell_price: Price,
+    pub quantity: Quantity,
+    /// Edge at the touch after both taker fees, relative to the ask
+    pub edge_bps: f64,
+    /// Profit in quote currency after fees if every level is taken as seen
+    pub expected_profit: f64,
+    /// The touch prices were equal rather than crossed
+    pub locked: bool,
+}
+
+fn edge_bps(ask: Price, bid: Price, buy_fee: f64, sell_fee: f64) -> f64 {
+    let ask = ask as f64;
+    let net = bid as f64 * (1.0 - sell_fee / 10000.0) - ask * (1.0 + buy_fee / 10000.0);
+    net / ask * 10000.0
+}
+
+/// Walk the asks of `buy_venue` against the bids of `sell_venue` while every
+/// matched level clears `min_edge_bps` after fees, up to `max_quantity`.
+pub fn find_opportunity(
+    books: &GlobalOrderBook,
+    buy_venue: ExchangeID,
+    sell_venue: ExchangeID,
+    fees: &VenueFees,
+    min_edge_bps: f64,
+    max_quantity: Quantity,
+) -> Option<Opportunity> {
+    let (buy_fee, sell_fee) = (fees.get(buy_venue), fees.get(sell_venue));
+    let asks = books.book(buy_venue);
+    let bids = books.book(sell_venue);
+    let mut ask = asks.next_level(Side::Sell, None)?;
+    let mut bid = bids.next_level(Side::Buy, None)?;
+
+    let touch_edge = edge_bps(ask.price, bid.price, buy_fee, sell_fee);
+    if touch_edge < min_edge_bps {
+        return None;
+    }
+    let mut opportunity = Opportunity {
+        buy_venue,
+        sell_venue,
+        buy_price: ask.price,
+        sell_price: bid.price,
+        quantity: 0,
+        edge_bps: touch_edge,
+        expected_profit: 0.0,
+        locked: ask.price == bid.price,
+    };
+
+    let (mut ask_left, mut bid_left) = (ask.quantity, bid.quantity);
+    loop {
+        let quantity = ask_left.min(bid_left).min(max_quantity - opportunity.quantity);
+        let net = edge_bps(ask.price, bid.price, buy_fee, sell_fee) / 10000.0 * ask.price as f64 / FIXED_POINT;
+        opportunity.quantity += quantity;
+        opportunity.buy_price = ask.price;
+        opportunity.sell_price = bid.price;
+        opportunity.expected_profit += net * quantity as f64 / FIXED_POINT;
+        ask_left -= quantity;
+        bid_left -= quantity;
+
+        if opportunity.quantity >= max_quantity {
+            break;
+        }
+        if ask_left == 0 {
+            let Some(level) = asks.next_level(Side::Sell, Some(ask.price)) else { break };
+            (ask, ask_left) = (level, level.quantity);
+        }
+        if bid_left == 0 {
+            let Some(level) = bids.next_level(Side::Buy, Some(bid.price)) else { break };
+            (bid, bid_left) = (level, level.quantity);
+        }
+        if edge_bps(ask.price, bid.price, buy_fee, sell_fee) < min_edge_bps {
+            break;
+        }
+    }
+    Some(opportunity)
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ArbitrageStats {
+    /// Distinct opportunities seen, traded or not
+    pub opportunities: u64,
+    pub locked: u64,
+    pub best_edge_bps: f64,
+    /// Sum of the expected profit of every opportunity seen
+    pub expected_profit: f64,
+    pub pairs_sent: u64,
+    /// Pairs that filled unevenly and needed a recovery order
+    pub legs_recovered: u64,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct CrossVenueArbitrageConfig {
+    /// Traded under the same symbol id on every venue
+    pub instrument: Instrument,
+    pub taker_fees_bps: VenueFees,
+    /// Edge after fees a level pair must clear to be taken
+    pub min_edge_bps: f64,
+    /// Largest pair (1e8)
+    pub max_quantity: Quantity,
+    /// Opportunities smaller than this after lot rounding are only logged
+    pub min_quantity: Quantity,
+    /// When false, opportunities are detected and logged but never traded
+    pub trading_enabled: bool,
+    pub leg_recovery: LegRecovery,
+    /// Cancel whatever is still working this long after a pair was sent (ns)
+    pub leg_timeout: u64,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Leg {
+    venue: ExchangeID,
+    cl_ord_id: u64,
+    filled: Quantity,
+    done: bool,
+}
+
+/// A pair of IOC orders in flight, and the recovery order if one was needed
+#[derive(Debug, Clone, Copy)]
+struct Pair {
+    buy: Leg,
+    sell: Leg,
+    recovery: Option<Leg>,
+}
+
+/// Takes crossed (or, with rebates, locked) markets between venues with a
+/// pair of IOC orders.
+///
+/// Only one pair is in flight at a time. If the legs fill unevenly the excess
+/// is hedged or unwound at market before the next pair is allowed.
+pub struct CrossVenueArbitrage {
+    config: CrossVenueArbitrageConfig,
+    stats: ArbitrageStats,
+    /// Venues whose feed is down are left out of detection
+    stale: [bool; 3],
+    last_seen: Option<(ExchangeID, ExchangeID, Price, Price)>,
+    pair: Option<Pair>,
+    position: i64,
+}
+
+fn venue_index(exchange_id: ExchangeID) -> usize {
+    exchange_id as usize - 1
+}
+
+impl CrossVenueArbitrage {
+    pub fn new(config: CrossVenueArbitrageConfig) -> Self {
+        Self {
+            config,
+            stats: ArbitrageStats::default(),
+            stale: [false; 3],
+            last_seen: None,
+            pair: None,
+            position: 0,
+        }
+    }
+
+    pub fn stats(&self) -> &ArbitrageStats {
+        &self.stats
+    }
+
+    /// Net filled inventory across all venues (1e8)
+    pub fn position(&self) -> i64 {
+        self.position
+    }
+
+    /// Most profitable opportunity over every ordered pair of live venues
+    fn best_opportunity(&self, books: &GlobalOrderBook) -> Option<Opportunity> {
+        let config = &self.config;
+        let live = |venue: ExchangeID| !self.stale[venue_index(venue)];
+        let mut best: Option<Opportunity> = None;
+        for buy_venue in VENUES.into_iter().filter(|&v| live(v)) {
+            for sell_venue in VENUES.into_iter().filter(|&v| v != buy_venue && live(v)) {
+                let found = find_opportunity(
+                    books,
+                    buy_venue,
+                    sell_venue,
+                    &config.taker_fees_bps,
+                    config.min_edge_bps,
+                    config.max_quantity,
+                );
+                if let Some(found) = found {
+                    if best.is_none_or(|b| found.expected_profit > b.expected_profit) {
+                        best = Some(found);
+                    }
+                }
+            }
+        }
+        best
+    }
+
+    fn record(&mut self, opportunity: &Opportunity) {
+        let key = (opportunity.buy_venue, opportunity.sell_venue, opportunity.buy_price, opportunity.sell_price);
+        if self.last_seen == Some(key) {
+            return;
+        }
+        self.last_seen = Some(key);
+
+        let stats = &mut self.stats;
+        stats.opportunities += 1;
+        if opportunity.locked {
+            stats.locked += 1;
+        }
+        if stats.opportunities == 1 || opportunity.edge_bps > stats.best_edge_bps {
+            stats.best_edge_bps = opportunity.edge_bps;
+        }
+        stats.expected_profit += opportunity.expected_profit;
+        info!(
+            "Arbitrage {:?} -> {:?}: {} @ {} / {} edge {:.2} bps expected {:.4} (seen {}, traded {})",
+            opportunity.buy_venue,
+            opportunity.sell_venue,
+            opportunity.quantity,
+            opportunity.buy_price,
+            opportunity.sell_price,
+            opportunity.edge_bps,
+            opportunity.expected_profit,
+            stats.opportunities,
+            stats.pairs_sent,
+        );
+    }
+
+    fn send_pair(&mut self, ctx: &mut StrategyContext<'_>, opportunity: &Opportunity, quantity: Quantity) {
+        let symbol_id = self.config.instrument.symbol_id;
+        let buy_id = ctx.submit_ioc(opportunity.buy_venue, symbol_id, Side::Buy, opportunity.buy_price, quantity);
+        let sell_id = ctx.submit_ioc(opportunity.sell_venue, symbol_id, Side::Sell, opportunity.sell_price, quantity);
+        let leg = |venue, cl_ord_id| Leg { venue, cl_ord_id, filled: 0, done: false };
+        self.pair = Some(Pair {
+            buy: leg(opportunity.buy_venue, buy_id),
+            sell: leg(opportunity.sell_venue, sell_id),
+            recovery: None,
+        });
+        ctx.schedule_timer(ctx.now() + self.config.leg_timeout, buy_id);
+        self.stats.pairs_sent += 1;
+    }
+
+    /// Both legs are done: square up any difference between their fills
+    fn recover(&mut self, ctx: &mut StrategyContext<'_>, pair: &Pair) -> Option<Leg> {
+        let excess = pair.buy.filled as i64 - pair.sell.filled as i64;
+        if excess == 0 {
+            return None;
+        }
+        let (side, venue) = match (excess > 0, self.config.leg_recovery) {
+            (true, LegRecovery::Hedge) => (Side::Sell, pair.sell.venue),
+            (true, LegRecovery::Unwind) => (Side::Sell, pair.buy.venue),
+            (false, LegRecovery::Hedge) => (Side::Buy, pair.buy.venue),
+            (false, LegRecovery::Unwind) => (Side::Buy, pair.sell.venue),
+        };
+        let quantity = excess.unsigned_abs();
+        warn!(
+            "Arbitrage legs filled {} / {}: {:?} {} at market on {:?}",
+            pair.buy.filled, pair.sell.filled, side, quantity, venue
+        );
+        self.stats.legs_recovered += 1;
+        let cl_ord_id = ctx.submit_market(venue, self.config.instrument.symbol_id, side, quantity);
+        Some(Leg { venue, cl_ord_id, filled: 0, done: false })
+    }
+}
+
+impl Strategy for CrossVenueArbitrage {
+    fn on_book_update(&mut self, ctx: &mut StrategyContext<'_>, update: &MarketDataUpdate) {
+        if update.symbol_id != self.config.instrument.symbol_id {
+            return;
+        }
+        let Some(opportunity) = self.best_opportunity(ctx.books()) else {
+            self.last_seen = None;
+            return;
+        };
+        self.record(&opportunity);
+
+        let quantity = self.config.instrument.floor_quantity(opportunity.quantity);
+        if self.config.trading_enabled && self.pair.is_none() && quantity > 0 && quantity >= self.config.min_quantity {
+            self.send_pair(ctx, &opportunity, quantity);
+        }
+    }
+
+    fn on_execution_report(&mut self, ctx: &mut StrategyContext<'_>, report: &ExecutionReport) {
+        match report.side {
+            Side::Buy => self.position += report.filled_quantity as i64,
+            Side::Sell => self.position -= report.filled_quantity as i64,
+        }
+
+        let Some(mut pair) = self.pair else { return };
+        let done = !ctx.orders().get(report.cl_ord_id).is_some_and(|o| o.is_live());
+        for leg in [&mut pair.buy, &mut pair.sell].into_iter().chain(pair.recovery.as_mut()) {
+            if leg.cl_ord_id == report.cl_ord_id {
+                leg.filled += report.filled_quantity;
+                leg.done = done;
+            }
+        }
+
+        self.pair = match pair.recovery {
+            Some(recovery) if recovery.done => {
+                if self.position != 0 {
+                    warn!("Arbitrage recovery left {} unhedged", self.position);
+                }
+                None
+            }
+            Some(_) => Some(pair),
+            None if pair.buy.done && pair.sell.done => {
+                pair.recovery = self.recover(ctx, &pair);
+                pair.recovery.map(|_| pair)
+            }
+            None => Some(pair),
+        };
+    }
+
+    fn on_timer(&mut self, ctx: &mut StrategyContext<'_>, timer_id: u64) {
+        let Some(pair) = self.pair else { return };
+        if pair.buy.cl_ord_id != timer_id {
+            return;
+        }
+        for leg in [pair.buy, pair.sell].into_iter().chain(pair.recovery) {
+            if !leg.done && ctx.orders().get(leg.cl_ord_id).is_some_and(|o| o.is_live()) {
+                warn!("Arbitrage leg {} still working on {:?}, canceling", leg.cl_ord_id, leg.venue);
+                let _ = ctx.cancel(leg.cl_ord_id);
+            }
+        }
+    }
+
+    fn on_feed_status(&mut self, _ctx: &mut StrategyContext<'_>, exchange_id: ExchangeID, status: FeedStatus) {
+        self.stale[venue_index(exchange_id)] = status != FeedStatus::Connected;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ONE: u64 = 100_000_000;
+
+    fn level(books: &mut GlobalOrderBook, exchange_id: ExchangeID, side: Side, price: f64, quantity: u64) {
+        books.on_update(&MarketDataUpdate {
+            timestamp: 1,
+            exchange_id,
+            symbol_id: 1,
+            side,
+            price: (price * FIXED_POINT) as i64,
+            quantity,
+            is_snapshot: 0,
+        });
+    }
+
+    #[test]
+    fn test_walks_depth_on_both_legs() {
+        let mut books = GlobalOrderBook::new();
+        level(&mut books, ExchangeID::Binance, Side::Sell, 100.0, ONE);
+        level(&mut books, ExchangeID::Binance, Side::Sell, 100.1, 2 * ONE);
+        level(&mut books, ExchangeID::Binance, Side::Sell, 100.5, 5 * ONE);
+        level(&mut books, ExchangeID::Bybit, Side::Buy, 100.4, 2 * ONE);
+        level(&mut books, ExchangeID::Bybit, Side::Buy, 100.3, 5 * ONE);
+        let fees = VenueFees { binance: 1.0, bybit: 1.0, coinbase: 1.0 };
+
+        let found = find_opportunity(&books, ExchangeID::Binance, ExchangeID::Bybit, &fees, 5.0, 10 * ONE).unwrap();
+        // 1 @ 100.0 and 2 @ 100.1 against the bids; 100.5 is through every bid
+        assert_eq!(found.quantity, 3 * ONE);
+        assert_eq!(found.buy_price, 10_010_000_000);
+        assert_eq!(found.sell_price, 10_030_000_000);
+        assert!(!found.locked);
+        assert!(found.expected_profit > 0.0);
+
+        // Capped by max_quantity
+        let capped = find_opportunity(&books, ExchangeID::Binance, ExchangeID::Bybit, &fees, 5.0, ONE / 2).unwrap();
+        assert_eq!(capped.quantity, ONE / 2);
+        assert_eq!(capped.buy_price, 10_000_000_000);
+
+        // The reverse direction is not crossed
+        assert!(find_opportunity(&books, ExchangeID::Bybit, ExchangeID::Binance, &fees, 5.0, ONE).is_none());
+    }
+
+    #[test]
+    fn test_fees_eat_thin_crosses() {
+        let mut books = GlobalOrderBook::new();
+        level(&mut books, ExchangeID::Binance, Side::Sell, 100.0, ONE);
+        level(&mut books, ExchangeID::Coinbase, Side::Buy, 100.05, ONE);
+
+        let cheap = VenueFees { binance: 1.0, bybit: 1.0, coinbase: 1.0 };
+        assert!(find_opportunity(&books, ExchangeID::Binance, ExchangeID::Coinbase, &cheap, 0.0, ONE).is_some());
+        let expensive = VenueFees { binance: 4.0, bybit: 4.0, coinbase: 4.0 };
+        assert!(find_opportunity(&books, ExchangeID::Binance, ExchangeID::Coinbase, &expensive, 0.0, ONE).is_none());
+    }
+}
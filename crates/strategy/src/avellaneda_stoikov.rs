@@ -0,0 +1,243 @@
+use vibe_hft_core::{Instrument, Quantity, Timestamp};
+use vibe_hft_sbe_messages::{ExchangeID, ExecutionReport, MarketDataUpdate, Side, Trade};
+
+use crate::context::{FeedStatus, StrategyContext};
+use crate::quoter::Quoter;
+use crate::Strategy;
+
+const FIXED_POINT: f64 = 100_000_000.0;
+const NANOS_PER_SEC: f64 = 1_000_000_000.0;
+
+/// Reservation price `r = s - q * gamma * sigma^2 * (T - t)`: the mid adjusted
+/// for the risk of carrying inventory `q` until the end of the horizon.
+pub fn reservation_price(mid: f64, inventory: f64, gamma: f64, variance: f64, time_left: f64) -> f64 {
+    mid - inventory * gamma * variance * time_left
+}
+
+/// Optimal total spread `gamma * sigma^2 * (T - t) + (2 / gamma) * ln(1 + gamma / k)`,
+/// where `k` is the decay of fill intensity with distance from mid.
+pub fn optimal_spread(gamma: f64, variance: f64, time_left: f64, intensity: f64) -> f64 {
+    gamma * variance * time_left + (2.0 / gamma) * (1.0 + gamma / intensity).ln()
+}
+
+/// Units: prices in quote currency, inventory in base units, time in seconds.
+#[derive(Debug, Clone, Copy)]
+pub struct AvellanedaStoikovConfig {
+    pub exchange_id: ExchangeID,
+    pub instrument: Instrument,
+    /// Risk aversion `gamma`; higher values skew harder against inventory
+    pub gamma: f64,
+    /// Session length (ns). `T - t` runs down to zero over each session and restarts.
+    pub horizon: u64,
+    /// Size of each quote (1e8), rounded down to the lot
+    pub order_size: Quantity,
+    /// Absolute inventory limit (1e8)
+    pub max_position: Quantity,
+    /// Weight of each new sample in the volatility and intensity averages
+    pub ewma_alpha: f64,
+    /// Mid variance per second used until the estimate is warm
+    pub initial_variance: f64,
+    /// Fill intensity decay `k` (per unit of price) used until the estimate is warm
+    pub initial_intensity: f64,
+    /// Samples an estimate needs before it replaces its initial value
+    pub warmup_samples: u32,
+    pub requote_threshold_ticks: i64,
+    pub max_quote_age: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Ewma {
+    value: f64,
+    samples: u32,
+}
+
+impl Ewma {
+    fn update(&mut self, sample: f64, alpha: f64) {
+        self.value = if self.samples == 0 { sample } else { self.value + alpha * (sample - self.value) };
+        self.samples += 1;
+    }
+}
+
+/// Inventory-aware market maker after Avellaneda & Stoikov (2008).
+///
+/// Volatility is estimated from mid-price changes on the book and order-arrival
+/// intensity from how far trade prints land from mid: under the model's
+/// exponential fill intensity `A * exp(-k * d)`, the mean distance is `1 / k`.
+pub struct AvellanedaStoikov {
+    config: AvellanedaStoikovConfig,
+    quoter: Quoter,
+    session_start: Option<Timestamp>,
+    /// Latest mid; only final for its timestamp once time moves on
+    mid: Option<(Timestamp, f64)>,
+    /// Last final mid that produced a variance sample
+    anchor_mid: Option<(Timestamp, f64)>,
+    variance: Ewma,
+    trade_distance: Ewma,
+    /// Reservation price and spread behind the current quotes
+    last_quote: Option<(f64, f64)>,
+}
+
+impl AvellanedaStoikov {
+    pub fn new(config: AvellanedaStoikovConfig) -> Self {
+        Self {
+            config,
+            quoter: Quoter::new(
+                config.exchange_id,
+                config.instrument,
+                config.requote_threshold_ticks,
+                config.max_quote_age,
+            ),
+            session_start: None,
+            mid: None,
+            anchor_mid: None,
+            variance: Ewma::default(),
+            trade_distance: Ewma::default(),
+            last_quote: None,
+        }
+    }
+
+    pub fn position(&self) -> i64 {
+        self.quoter.position()
+    }
+
+    /// Mid variance per second
+    pub fn variance(&self) -> f64 {
+        if self.variance.samples >= self.config.warmup_samples {
+            self.variance.value
+        } else {
+            self.config.initial_variance
+        }
+    }
+
+    /// Fill intensity decay `k`
+    pub fn intensity(&self) -> f64 {
+        if self.trade_distance.samples >= self.config.warmup_samples && self.trade_distance.value > 0.0 {
+            1.0 / self.trade_distance.value
+        } else {
+            self.config.initial_intensity
+        }
+    }
+
+    /// `(reservation price, spread)` in quote currency behind the current quotes
+    pub fn last_quote(&self) -> Option<(f64, f64)> {
+        self.last_quote
+    }
+
+    /// Seconds left in the current session
+    fn time_left(&mut self, now: Timestamp) -> f64 {
+        let start = *self.session_start.get_or_insert(now);
+        let horizon = self.config.horizon.max(1);
+        let elapsed = (now - start) % horizon;
+        (horizon - elapsed) as f64 / NANOS_PER_SEC
+    }
+
+    fn observe_mid(&mut self, now: Timestamp, mid: f64) {
+        // Several levels often change under one timestamp; only the mid they
+        // settle on counts, otherwise half-applied updates inflate the variance.
+        if let Some((at, settled)) = self.mid {
+            if now > at {
+                self.sample_variance(at, settled);
+            }
+        }
+        self.mid = Some((now, mid));
+    }
+
+    /// Variance per second from the change since the last sample. Unchanged
+    /// mids are skipped so deep-book churn does not drag the estimate to zero.
+    fn sample_variance(&mut self, at: Timestamp, mid: f64) {
+        match self.anchor_mid {
+            Some((anchor_at, anchor)) if mid != anchor && at > anchor_at => {
+                let dt = (at - anchor_at) as f64 / NANOS_PER_SEC;
+                self.variance.update((mid - anchor).powi(2) / dt, self.config.ewma_alpha);
+                self.anchor_mid = Some((at, mid));
+            }
+            Some(_) => {}
+            None => self.anchor_mid = Some((at, mid)),
+        }
+    }
+}
+
+impl Strategy for AvellanedaStoikov {
+    fn on_book_update(&mut self, ctx: &mut StrategyContext<'_>, update: &MarketDataUpdate) {
+        if !self.quoter.is_for(update.exchange_id, update.symbol_id) {
+            return;
+        }
+        let book = ctx.book(update.exchange_id);
+        let (Some(best_bid), Some(best_ask)) = (book.best_bid(), book.best_ask()) else {
+            return;
+        };
+
+        let now = ctx.now();
+        let mid = (best_bid.price as f64 + best_ask.price as f64) / 2.0 / FIXED_POINT;
+        self.observe_mid(now, mid);
+
+        let gamma = self.config.gamma;
+        let variance = self.variance();
+        let time_left = self.time_left(now);
+        let inventory = self.quoter.position() as f64 / FIXED_POINT;
+
+        let reservation = reservation_price(mid, inventory, gamma, variance, time_left);
+        let spread = optimal_spread(gamma, variance, time_left, self.intensity());
+        self.last_quote = Some((reservation, spread));
+
+        let (bid_price, ask_price) = self.quoter.round_quotes(
+            (reservation - spread / 2.0) * FIXED_POINT,
+            (reservation + spread / 2.0) * FIXED_POINT,
+            best_bid.price,
+            best_ask.price,
+        );
+        let bid_size = self.quoter.capacity(Side::Buy, self.config.order_size, self.config.max_position);
+        let ask_size = self.quoter.capacity(Side::Sell, self.config.order_size, self.config.max_position);
+        self.quoter.requote(ctx, Side::Buy, bid_price, bid_size);
+        self.quoter.requote(ctx, Side::Sell, ask_price, ask_size);
+    }
+
+    fn on_trade(&mut self, _ctx: &mut StrategyContext<'_>, trade: &Trade) {
+        if !self.quoter.is_for(trade.exchange_id, trade.symbol_id) {
+            return;
+        }
+        if let Some((_, mid)) = self.mid {
+            let distance = (trade.price as f64 / FIXED_POINT - mid).abs();
+            if distance > 0.0 {
+                self.trade_distance.update(distance, self.config.ewma_alpha);
+            }
+        }
+    }
+
+    fn on_execution_report(&mut self, ctx: &mut StrategyContext<'_>, report: &ExecutionReport) {
+        self.quoter.on_execution_report(ctx, report);
+    }
+
+    fn on_timer(&mut self, ctx: &mut StrategyContext<'_>, timer_id: u64) {
+        self.quoter.on_timer(ctx, timer_id);
+    }
+
+    fn on_feed_status(&mut self, ctx: &mut StrategyContext<'_>, exchange_id: ExchangeID, status: FeedStatus) {
+        if status != FeedStatus::Connected {
+            self.quoter.on_feed_lost(ctx, exchange_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reservation_price_skews_against_inventory() {
+        let (mid, gamma, variance, time_left) = (100.0, 0.1, 2.0, 10.0);
+        assert_eq!(reservation_price(mid, 0.0, gamma, variance, time_left), mid);
+        assert_eq!(reservation_price(mid, 1.0, gamma, variance, time_left), 98.0);
+        assert_eq!(reservation_price(mid, -1.0, gamma, variance, time_left), 102.0);
+        // Nothing left to carry at the end of the horizon
+        assert_eq!(reservation_price(mid, 1.0, gamma, variance, 0.0), mid);
+    }
+
+    #[test]
+    fn test_spread_widens_with_risk_and_thin_flow() {
+        let base = optimal_spread(0.1, 1.0, 1.0, 10.0);
+        assert!((base - (0.1 + 20.0 * 1.01f64.ln())).abs() < 1e-12);
+        assert!(optimal_spread(0.1, 4.0, 1.0, 10.0) > base);
+        assert!(optimal_spread(0.1, 1.0, 1.0, 2.0) > base);
+    }
+}
@@ -0,0 +1,234 @@
+use log::warn;
+use smallvec::SmallVec;
+use vibe_hft_core::{Price, Quantity, Timestamp};
+use vibe_hft_market_data::{GlobalOrderBook, OrderBook};
+use vibe_hft_oms::{OmsError, OrderManager, PositionKeeper};
+use vibe_hft_risk::{RiskEngine, RiskReject};
+use vibe_hft_sbe_messages::{
+    ExchangeID, ExecutionReport, OrdStatus, OrdType, OrderCancel, OrderEntry, Side, TimeInForce,
+};
+
+/// Something a strategy asked to be sent to a venue.
+#[derive(Debug, Clone, Copy)]
+pub enum OrderAction {
+    Submit(OrderEntry),
+    Cancel(OrderCancel),
+    /// Cancel/replace: the cancel of the original and the replacement, if any
+    /// quantity remains. Transports without native amend send them in order.
+    Amend { cancel: OrderCancel, replacement: Option<OrderEntry> },
+}
+
+/// Fixed inline buffer of actions produced during one callback.
+/// Sized so a normal quoting cycle never spills to the heap.
+#[derive(Debug, Default)]
+pub struct ActionBuffer {
+    actions: SmallVec<[OrderAction; 32]>,
+}
+
+impl ActionBuffer {
+    pub fn push(&mut self, action: OrderAction) {
+        self.actions.push(action);
+    }
+
+    pub fn len(&self) -> usize {
+        self.actions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.actions.is_empty()
+    }
+
+    pub fn drain(&mut self) -> smallvec::Drain<'_, [OrderAction; 32]> {
+        self.actions.drain(..)
+    }
+}
+
+/// Local rejects for orders the risk checks refused, delivered to the
+/// strategy as execution reports once the current callback returns
+pub(crate) type RejectQueue = SmallVec<[ExecutionReport; 8]>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timer {
+    pub at: Timestamp,
+    pub id: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeedStatus {
+    Connected,
+    Disconnected,
+    /// Connected but no update within the expected interval
+    Stale,
+}
+
+/// Everything a strategy can see and do from inside a callback.
+pub struct StrategyContext<'a> {
+    pub(crate) now: Timestamp,
+    pub(crate) books: &'a mut GlobalOrderBook,
+    pub(crate) oms: &'a mut OrderManager,
+    pub(crate) positions: &'a PositionKeeper,
+    pub(crate) actions: &'a mut ActionBuffer,
+    pub(crate) timers: &'a mut Vec<Timer>,
+    pub(crate) risk: &'a mut RiskEngine,
+    pub(crate) rejects: &'a mut RejectQueue,
+}
+
+impl<'a> StrategyContext<'a> {
+    /// Local time of the event being handled (ns)
+    pub fn now(&self) -> Timestamp {
+        self.now
+    }
+
+    pub fn book(&mut self, exchange_id: ExchangeID) -> &mut OrderBook {
+        self.books.book_mut(exchange_id)
+    }
+
+    pub fn books(&mut self) -> &mut GlobalOrderBook {
+        self.books
+    }
+
+    /// Our orders, including ones still in flight
+    pub fn orders(&self) -> &OrderManager {
+        self.oms
+    }
+
+    /// Positions and PnL across venues, from every fill the host has seen
+    pub fn positions(&self) -> &PositionKeeper {
+        self.positions
+    }
+
+    /// Send an order. `cl_ord_id` and `timestamp` are filled in; returns the assigned `cl_ord_id`.
+    ///
+    /// The order goes through the pre-trade risk checks first. A refused
+    /// order is never sent: it comes back as a `Rejected` execution report
+    /// right after the current callback, as a venue reject would.
+    pub fn submit(&mut self, mut entry: OrderEntry) -> u64 {
+        entry.timestamp = self.now;
+        let mid = self.mid(entry.exchange_id);
+        let check = self.risk.check_order(self.now, &entry, mid, self.oms);
+        let entry = self.oms.create(entry);
+        match check {
+            Ok(()) => {
+                self.risk.record_message(entry.exchange_id, self.now);
+                self.actions.push(OrderAction::Submit(entry));
+            }
+            Err(reason) => self.reject(&entry, reason),
+        }
+        entry.cl_ord_id
+    }
+
+    pub fn submit_limit(
+        &mut self,
+        exchange_id: ExchangeID,
+        symbol_id: u32,
+        side: Side,
+        price: Price,
+        quantity: Quantity,
+    ) -> u64 {
+        self.submit(OrderEntry {
+            timestamp: 0,
+            cl_ord_id: 0,
+            exchange_id,
+            symbol_id,
+            side,
+            price,
+            quantity,
+            order_type: OrdType::Limit,
+            time_in_force: TimeInForce::Gtc,
+        })
+    }
+
+    /// Limit order that takes what it can on arrival and cancels the rest
+    pub fn submit_ioc(
+        &mut self,
+        exchange_id: ExchangeID,
+        symbol_id: u32,
+        side: Side,
+        price: Price,
+        quantity: Quantity,
+    ) -> u64 {
+        self.submit(OrderEntry {
+            timestamp: 0,
+            cl_ord_id: 0,
+            exchange_id,
+            symbol_id,
+            side,
+            price,
+            quantity,
+            order_type: OrdType::Limit,
+            time_in_force: TimeInForce::Ioc,
+        })
+    }
+
+    pub fn submit_market(&mut self, exchange_id: ExchangeID, symbol_id: u32, side: Side, quantity: Quantity) -> u64 {
+        self.submit(OrderEntry {
+            timestamp: 0,
+            cl_ord_id: 0,
+            exchange_id,
+            symbol_id,
+            side,
+            price: 0,
+            quantity,
+            order_type: OrdType::Market,
+            time_in_force: TimeInForce::Ioc,
+        })
+    }
+
+    pub fn cancel(&mut self, cl_ord_id: u64) -> Result<(), OmsError> {
+        let cancel = self.oms.request_cancel(cl_ord_id, self.now)?;
+        self.risk.record_message(cancel.exchange_id, self.now);
+        self.actions.push(OrderAction::Cancel(cancel));
+        Ok(())
+    }
+
+    /// Move an order to a new price and total quantity. Returns the `cl_ord_id`
+    /// of the replacement, or `None` if the fills so far already cover `quantity`.
+    ///
+    /// The replacement is risk checked like a new order; if it is refused the
+    /// original is still canceled and the replacement is rejected locally.
+    pub fn amend(&mut self, cl_ord_id: u64, price: Price, quantity: Quantity) -> Result<Option<u64>, OmsError> {
+        let (cancel, mut replacement) = self.oms.request_replace(cl_ord_id, self.now, price, quantity)?;
+        self.risk.record_message(cancel.exchange_id, self.now);
+        let replacement_id = replacement.map(|r| r.cl_ord_id);
+        if let Some(entry) = replacement {
+            let mid = self.mid(entry.exchange_id);
+            match self.risk.check_order(self.now, &entry, mid, self.oms) {
+                Ok(()) => self.risk.record_message(entry.exchange_id, self.now),
+                Err(reason) => {
+                    self.reject(&entry, reason);
+                    replacement = None;
+                }
+            }
+        }
+        self.actions.push(OrderAction::Amend { cancel, replacement });
+        Ok(replacement_id)
+    }
+
+    /// Request an `on_timer(id)` callback at local time `at`
+    pub fn schedule_timer(&mut self, at: Timestamp, id: u64) {
+        self.timers.push(Timer { at, id });
+    }
+
+    fn mid(&mut self, exchange_id: ExchangeID) -> Option<Price> {
+        let book = self.books.book_mut(exchange_id);
+        let (bid, ask) = (book.best_bid()?, book.best_ask()?);
+        Some((bid.price + ask.price) / 2)
+    }
+
+    fn reject(&mut self, entry: &OrderEntry, reason: RiskReject) {
+        warn!("Order {} refused by risk checks: {}", entry.cl_ord_id, reason);
+        self.rejects.push(ExecutionReport {
+            timestamp: self.now,
+            cl_ord_id: entry.cl_ord_id,
+            exec_id: 0,
+            exchange_id: entry.exchange_id,
+            symbol_id: entry.symbol_id,
+            side: entry.side,
+            filled_quantity: 0,
+            filled_price: 0,
+            leaves_quantity: 0,
+            fee: 0,
+            status: OrdStatus::Rejected,
+        });
+    }
+}
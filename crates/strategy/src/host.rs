@@ -0,0 +1,482 @@
+use log::{info, warn};
+use smallvec::SmallVec;
+use vibe_hft_core::Timestamp;
+use vibe_hft_market_data::GlobalOrderBook;
+use vibe_hft_oms::{OmsError, OrderManager, PositionKeeper};
+use vibe_hft_risk::{KillReason, RiskEngine, RiskLimits};
+use vibe_hft_sbe_messages::{
+    ExchangeID, ExecutionReport, MarketDataUpdate, OrdType, OrderEntry, Side, TimeInForce, Trade,
+};
+
+use crate::context::{ActionBuffer, FeedStatus, OrderAction, RejectQueue, StrategyContext, Timer};
+use crate::Strategy;
+
+/// Owns a strategy together with the state it trades against: the books,
+/// the order manager and the pending actions and timers.
+///
+/// The live gateway and the backtest runner both feed events in through the
+/// `on_*` methods and forward whatever comes out of `drain_actions`, so a
+/// strategy runs unchanged in either.
+///
+/// Every order the strategy sends passes the host's pre-trade risk checks
+/// before it reaches the action buffer. When the kill switch trips the host
+/// cancels every resting order, keeps cancelling whatever is still resting
+/// after each event, and flattens positions once if the breakers ask for it.
+pub struct StrategyHost<S: Strategy> {
+    strategy: S,
+    books: GlobalOrderBook,
+    oms: OrderManager,
+    positions: PositionKeeper,
+    actions: ActionBuffer,
+    timers: Vec<Timer>,
+    risk: RiskEngine,
+    rejects: RejectQueue,
+    delivering: bool,
+    /// The current trip has been logged and, if configured, flattened
+    halt_handled: bool,
+}
+
+impl<S: Strategy> StrategyHost<S> {
+    /// Host without risk limits
+    pub fn new(strategy: S) -> Self {
+        Self::with_risk(strategy, RiskLimits::default())
+    }
+
+    pub fn with_risk(strategy: S, limits: RiskLimits) -> Self {
+        Self {
+            strategy,
+            books: GlobalOrderBook::new(),
+            oms: OrderManager::new(),
+            positions: PositionKeeper::new(),
+            actions: ActionBuffer::default(),
+            timers: Vec::with_capacity(16),
+            risk: RiskEngine::new(limits),
+            rejects: RejectQueue::new(),
+            delivering: false,
+            halt_handled: false,
+        }
+    }
+
+    pub fn strategy(&self) -> &S {
+        &self.strategy
+    }
+
+    pub fn strategy_mut(&mut self) -> &mut S {
+        &mut self.strategy
+    }
+
+    pub fn books(&mut self) -> &mut GlobalOrderBook {
+        &mut self.books
+    }
+
+    pub fn orders(&self) -> &OrderManager {
+        &self.oms
+    }
+
+    /// Positions and PnL from every fill, marked to mid
+    pub fn positions(&self) -> &PositionKeeper {
+        &self.positions
+    }
+
+    /// For booking funding payments on perpetuals
+    pub fn positions_mut(&mut self) -> &mut PositionKeeper {
+        &mut self.positions
+    }
+
+    pub fn risk(&self) -> &RiskEngine {
+        &self.risk
+    }
+
+    pub fn risk_mut(&mut self) -> &mut RiskEngine {
+        &mut self.risk
+    }
+
+    pub fn on_market_data(&mut self, now: Timestamp, update: &MarketDataUpdate) {
+        self.books.on_update(update);
+        self.risk.kill_switch_mut().on_book_update(update.exchange_id, now);
+        let book = self.books.book_mut(update.exchange_id);
+        if let (Some(bid), Some(ask)) = (book.best_bid(), book.best_ask()) {
+            let mid = (bid.price + ask.price) / 2;
+            self.positions.mark(update.exchange_id, update.symbol_id, mid);
+            self.risk.mark(now, update.exchange_id, update.symbol_id, mid);
+        }
+        self.dispatch(now, |strategy, ctx| strategy.on_book_update(ctx, update));
+    }
+
+    pub fn on_trade(&mut self, now: Timestamp, trade: &Trade) {
+        self.dispatch(now, |strategy, ctx| strategy.on_trade(ctx, trade));
+    }
+
+    /// Reconcile a report with the OMS first. Only reports it accepts reach
+    /// the positions, the risk engine and the strategy, so a replayed or
+    /// stale report is never counted twice. A leaves mismatch is accepted:
+    /// the OMS has applied the report and adopted the venue's quantity.
+    pub fn on_execution_report(&mut self, now: Timestamp, report: &ExecutionReport) {
+        let done = match self.oms.on_execution_report(report) {
+            Ok(state) => state.is_terminal(),
+            Err(e @ OmsError::LeavesMismatch { .. }) => {
+                warn!("Execution report reconciliation: {}", e);
+                false
+            }
+            Err(e) => {
+                warn!("Execution report ignored: {}", e);
+                return;
+            }
+        };
+        self.positions.on_execution_report(report);
+        self.risk.on_execution_report(now, report);
+        self.dispatch(now, |strategy, ctx| strategy.on_execution_report(ctx, report));
+
+        // The strategy has seen the final report; recycle the slot
+        if done {
+            self.oms.purge_completed();
+        }
+    }
+
+    pub fn on_feed_status(&mut self, now: Timestamp, exchange_id: ExchangeID, status: FeedStatus) {
+        if status != FeedStatus::Connected && self.risk.kill_switch().breakers().halt_on_feed_loss {
+            self.risk.halt(now, KillReason::FeedLoss(exchange_id));
+        }
+        self.dispatch(now, |strategy, ctx| strategy.on_feed_status(ctx, exchange_id, status));
+    }
+
+    /// Operator kill switch: block new orders and cancel every resting one.
+    /// Returns `false` if trading was already halted.
+    pub fn kill(&mut self, now: Timestamp) -> bool {
+        let tripped = self.risk.halt(now, KillReason::Operator);
+        self.enforce_halt(now);
+        tripped
+    }
+
+    /// Resume trading after a halt. Nothing re-arms the kill switch on its own.
+    pub fn rearm(&mut self) -> bool {
+        let rearmed = self.risk.rearm();
+        if rearmed {
+            info!("Kill switch re-armed");
+        }
+        self.halt_handled = false;
+        rearmed
+    }
+
+    /// Run the time-based breakers without an event, e.g. from a watchdog
+    /// timer while the feeds are quiet
+    pub fn check_breakers(&mut self, now: Timestamp) {
+        self.risk.check_books(now, &self.oms);
+        self.enforce_halt(now);
+    }
+
+    /// Earliest pending timer, if any
+    pub fn next_timer(&self) -> Option<Timestamp> {
+        self.timers.iter().map(|t| t.at).min()
+    }
+
+    /// Fire every timer due at or before `now`, earliest first
+    pub fn fire_timers(&mut self, now: Timestamp) {
+        while let Some(i) = self.due_timer(now) {
+            let timer = self.timers.swap_remove(i);
+            self.dispatch(now, |strategy, ctx| strategy.on_timer(ctx, timer.id));
+        }
+    }
+
+    fn due_timer(&self, now: Timestamp) -> Option<usize> {
+        self.timers
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| t.at <= now)
+            .min_by_key(|(_, t)| t.at)
+            .map(|(i, _)| i)
+    }
+
+    /// Actions produced since the last drain, in the order they were requested
+    pub fn drain_actions(&mut self) -> impl Iterator<Item = OrderAction> + '_ {
+        self.actions.drain()
+    }
+
+    fn dispatch(&mut self, now: Timestamp, callback: impl FnOnce(&mut S, &mut StrategyContext<'_>)) {
+        self.risk.check_books(now, &self.oms);
+        let mut ctx = StrategyContext {
+            now,
+            books: &mut self.books,
+            oms: &mut self.oms,
+            positions: &self.positions,
+            actions: &mut self.actions,
+            timers: &mut self.timers,
+            risk: &mut self.risk,
+            rejects: &mut self.rejects,
+        };
+        callback(&mut self.strategy, &mut ctx);
+        if !self.rejects.is_empty() {
+            self.deliver_rejects(now);
+        }
+        self.enforce_halt(now);
+    }
+
+    fn enforce_halt(&mut self, now: Timestamp) {
+        let Some((reason, _)) = self.risk.kill_switch().tripped() else { return };
+        if !self.halt_handled {
+            self.halt_handled = true;
+            warn!("Kill switch tripped: {}; cancelling all orders", reason);
+            if self.risk.kill_switch().breakers().flatten {
+                self.flatten(now);
+            }
+        }
+
+        // IOC and market orders, the flattening ones included, end on their own
+        let resting: SmallVec<[u64; 32]> = self
+            .oms
+            .live_orders()
+            .filter(|o| !o.cancel_pending && o.time_in_force == TimeInForce::Gtc)
+            .map(|o| o.cl_ord_id)
+            .collect();
+        for cl_ord_id in resting {
+            if let Ok(cancel) = self.oms.request_cancel(cl_ord_id, now) {
+                self.risk.record_message(cancel.exchange_id, now);
+                self.actions.push(OrderAction::Cancel(cancel));
+            }
+        }
+    }
+
+    /// Close every position at market, bypassing the (halted) risk checks
+    fn flatten(&mut self, now: Timestamp) {
+        let positions: SmallVec<[(ExchangeID, u32, i64); 8]> = self.risk.positions().collect();
+        for (exchange_id, symbol_id, position) in positions {
+            let side = if position > 0 { Side::Sell } else { Side::Buy };
+            warn!("Flattening {} on {:?} symbol {}", position, exchange_id, symbol_id);
+            let entry = self.oms.create(OrderEntry {
+                timestamp: now,
+                cl_ord_id: 0,
+                exchange_id,
+                symbol_id,
+                side,
+                price: 0,
+                quantity: position.unsigned_abs(),
+                order_type: OrdType::Market,
+                time_in_force: TimeInForce::Ioc,
+            });
+            self.risk.record_message(exchange_id, now);
+            self.actions.push(OrderAction::Submit(entry));
+        }
+    }
+
+    /// Hand risk rejects back to the strategy. Orders it sends in response
+    /// that are refused again wait for the next event, so a strategy that
+    /// retries on reject cannot spin.
+    fn deliver_rejects(&mut self, now: Timestamp) {
+        if self.delivering {
+            return;
+        }
+        self.delivering = true;
+        let pending = core::mem::take(&mut self.rejects);
+        for report in &pending {
+            self.on_execution_report(now, report);
+        }
+        self.delivering = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vibe_hft_risk::CircuitBreakers;
+    use vibe_hft_sbe_messages::{OrdStatus, OrderCancel};
+
+    /// Bids one tick under the best bid on every book update and arms a timer
+    #[derive(Default)]
+    struct Probe {
+        timers_fired: Vec<u64>,
+        reports: usize,
+    }
+
+    impl Strategy for Probe {
+        fn on_book_update(&mut self, ctx: &mut StrategyContext<'_>, update: &MarketDataUpdate) {
+            if let Some(bid) = ctx.book(update.exchange_id).best_bid() {
+                ctx.submit_limit(update.exchange_id, update.symbol_id, Side::Buy, bid.price - 1, 1);
+            }
+            ctx.schedule_timer(ctx.now() + 10, 7);
+        }
+
+        fn on_execution_report(&mut self, _ctx: &mut StrategyContext<'_>, _report: &ExecutionReport) {
+            self.reports += 1;
+        }
+
+        fn on_timer(&mut self, _ctx: &mut StrategyContext<'_>, timer_id: u64) {
+            self.timers_fired.push(timer_id);
+        }
+    }
+
+    #[test]
+    fn test_host_routes_actions_and_timers() {
+        let mut host = StrategyHost::new(Probe::default());
+        host.on_market_data(
+            100,
+            &MarketDataUpdate {
+                timestamp: 90,
+                exchange_id: ExchangeID::Bybit,
+                symbol_id: 1,
+                side: Side::Buy,
+                price: 500,
+                quantity: 3,
+                is_snapshot: 0,
+            },
+        );
+
+        let actions: Vec<_> = host.drain_actions().collect();
+        let OrderAction::Submit(entry) = actions[0] else {
+            panic!("expected a submit, got {:?}", actions[0]);
+        };
+        assert_eq!((entry.cl_ord_id, entry.price, entry.timestamp), (1, 499, 100));
+        assert_eq!(host.orders().live_orders().count(), 1);
+
+        host.on_execution_report(
+            105,
+            &ExecutionReport {
+                timestamp: 104,
+                cl_ord_id: 1,
+                exec_id: 1,
+                exchange_id: ExchangeID::Bybit,
+                symbol_id: 1,
+                side: Side::Buy,
+                filled_quantity: 0,
+                filled_price: 0,
+                leaves_quantity: 1,
+                fee: 0,
+                status: OrdStatus::New,
+            },
+        );
+        assert_eq!(host.strategy().reports, 1);
+
+        assert_eq!(host.next_timer(), Some(110));
+        host.fire_timers(109);
+        assert!(host.strategy().timers_fired.is_empty());
+        host.fire_timers(110);
+        assert_eq!(host.strategy().timers_fired, [7]);
+        assert_eq!(host.next_timer(), None);
+    }
+
+    #[test]
+    fn test_risk_reject_is_reported_locally() {
+        let limits = RiskLimits { max_open_orders: 1, ..RiskLimits::default() };
+        let mut host = StrategyHost::with_risk(Probe::default(), limits);
+        let update = MarketDataUpdate {
+            timestamp: 90,
+            exchange_id: ExchangeID::Bybit,
+            symbol_id: 1,
+            side: Side::Buy,
+            price: 500,
+            quantity: 3,
+            is_snapshot: 0,
+        };
+        host.on_market_data(100, &update);
+        host.on_market_data(101, &update);
+
+        // Only the first bid is sent; the second comes back rejected
+        let actions: Vec<_> = host.drain_actions().collect();
+        assert_eq!(actions.len(), 1);
+        assert_eq!(host.strategy().reports, 1);
+        assert_eq!(host.risk().rejected(), 1);
+        // The rejected order was purged once the strategy saw it
+        assert!(host.orders().get(2).is_none());
+        assert_eq!(host.orders().live_orders().count(), 1);
+    }
+
+    #[test]
+    fn test_replayed_fill_is_counted_once() {
+        let mut host = StrategyHost::new(Probe::default());
+        host.on_market_data(100, &bybit_bid(500));
+        host.drain_actions().for_each(drop);
+        let fill = ExecutionReport {
+            timestamp: 104,
+            cl_ord_id: 1,
+            exec_id: 1,
+            exchange_id: ExchangeID::Bybit,
+            symbol_id: 1,
+            side: Side::Buy,
+            filled_quantity: 1,
+            filled_price: 499,
+            leaves_quantity: 0,
+            fee: 3,
+            status: OrdStatus::Filled,
+        };
+        host.on_execution_report(105, &fill);
+        host.on_execution_report(106, &fill);
+
+        assert_eq!(host.strategy().reports, 1);
+        assert_eq!(host.risk().position(ExchangeID::Bybit, 1), 1);
+        let position = host.positions().get(ExchangeID::Bybit, 1).unwrap();
+        assert_eq!((position.quantity, position.fees), (1, 3));
+    }
+
+    fn bybit_bid(price: i64) -> MarketDataUpdate {
+        MarketDataUpdate {
+            timestamp: 90,
+            exchange_id: ExchangeID::Bybit,
+            symbol_id: 1,
+            side: Side::Buy,
+            price,
+            quantity: 3,
+            is_snapshot: 0,
+        }
+    }
+
+    #[test]
+    fn test_kill_cancels_blocks_and_needs_rearm() {
+        let mut host = StrategyHost::new(Probe::default());
+        host.on_market_data(100, &bybit_bid(500));
+        host.drain_actions().for_each(drop);
+
+        assert!(host.kill(110));
+        assert!(!host.kill(111));
+        let actions: Vec<_> = host.drain_actions().collect();
+        assert!(matches!(actions[..], [OrderAction::Cancel(OrderCancel { cl_ord_id: 1, .. })]));
+
+        // Halted: the strategy's next bid never leaves
+        host.on_market_data(120, &bybit_bid(501));
+        assert_eq!(host.drain_actions().count(), 0);
+        assert_eq!(host.strategy().reports, 1);
+
+        assert!(host.rearm());
+        host.on_market_data(130, &bybit_bid(502));
+        assert!(matches!(host.drain_actions().next(), Some(OrderAction::Submit(_))));
+    }
+
+    #[test]
+    fn test_reject_burst_trips_and_flattens() {
+        let limits = RiskLimits {
+            breakers: CircuitBreakers { max_rejects: 2, flatten: true, ..CircuitBreakers::default() },
+            ..RiskLimits::default()
+        };
+        let mut host = StrategyHost::with_risk(Probe::default(), limits);
+        host.on_market_data(100, &bybit_bid(500));
+        host.drain_actions().for_each(drop);
+        let report = ExecutionReport {
+            timestamp: 104,
+            cl_ord_id: 1,
+            exec_id: 1,
+            exchange_id: ExchangeID::Bybit,
+            symbol_id: 1,
+            side: Side::Buy,
+            filled_quantity: 1,
+            filled_price: 499,
+            leaves_quantity: 0,
+            fee: 0,
+            status: OrdStatus::Filled,
+        };
+        host.on_execution_report(105, &report);
+
+        host.on_market_data(110, &bybit_bid(500));
+        host.drain_actions().for_each(drop);
+        host.on_execution_report(115, &ExecutionReport { cl_ord_id: 2, filled_quantity: 0, status: OrdStatus::Rejected, ..report });
+        assert!(!host.risk().kill_switch().is_tripped());
+        host.on_market_data(120, &bybit_bid(500));
+        host.drain_actions().for_each(drop);
+        host.on_execution_report(125, &ExecutionReport { cl_ord_id: 3, filled_quantity: 0, status: OrdStatus::Rejected, ..report });
+
+        let tripped = host.risk().kill_switch().tripped();
+        assert_eq!(tripped, Some((KillReason::RejectBurst { rejects: 2 }, 125)));
+        let actions: Vec<_> = host.drain_actions().collect();
+        let [OrderAction::Submit(flatten)] = actions[..] else {
+            panic!("expected only the flattening order, got {:?}", actions);
+        };
+        assert_eq!((flatten.side, flatten.quantity, flatten.order_type), (Side::Sell, 1, OrdType::Market));
+    }
+}
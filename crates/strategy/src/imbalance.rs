@@ -0,0 +1,245 @@
+use vibe_hft_core::{Instrument, Quantity};
+use vibe_hft_market_data::nobi::{DepthBand, VolumeShare, EQUAL_WEIGHTS};
+use vibe_hft_market_data::ofi::OFICalculator;
+use vibe_hft_sbe_messages::{ExchangeID, ExecutionReport, MarketDataUpdate, Side, Trade};
+
+use crate::context::{FeedStatus, StrategyContext};
+use crate::quoter::Quoter;
+use crate::Strategy;
+
+/// How OFI and NOBI turn into quote adjustments.
+///
+/// Both indicators are blended into one signal in `[-1, 1]`, positive when
+/// buyers dominate. The signal then shifts the quote midpoint, tilts sizes
+/// towards the side the flow favours, and past the thresholds widens the
+/// spread or pulls the side that would be run over.
+#[derive(Debug, Clone, Copy)]
+pub struct SignalMapping {
+    /// OFI (1e8) at which the OFI component saturates at +/-1
+    pub ofi_scale: Quantity,
+    pub ofi_weight: f64,
+    pub nobi_weight: f64,
+    /// Midpoint shift at a full-strength signal
+    pub skew_bps: f64,
+    /// Fraction by which sizes grow on the favoured side and shrink on the other
+    pub size_skew: f64,
+    /// Signal strength from which the spread is multiplied by `widen_factor`
+    pub widen_threshold: f64,
+    pub widen_factor: f64,
+    /// Signal strength from which the side against the flow is pulled
+    pub pull_threshold: f64,
+}
+
+/// Adjustments derived from one signal value
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QuoteSkew {
+    pub mid_offset_bps: f64,
+    pub spread_multiplier: f64,
+    /// Multipliers on the configured order size; zero pulls the side
+    pub bid_size: f64,
+    pub ask_size: f64,
+}
+
+impl SignalMapping {
+    pub fn signal(&self, ofi: f64, nobi: f64) -> f64 {
+        let scale = self.ofi_scale.max(1) as f64;
+        let ofi = (ofi / scale).clamp(-1.0, 1.0);
+        (self.ofi_weight * ofi + self.nobi_weight * nobi).clamp(-1.0, 1.0)
+    }
+
+    pub fn skew(&self, signal: f64) -> QuoteSkew {
+        let strength = signal.abs();
+        let spread_multiplier = if strength >= self.widen_threshold { self.widen_factor } else { 1.0 };
+
+        let mut bid_size = (1.0 + self.size_skew * signal).max(0.0);
+        let mut ask_size = (1.0 - self.size_skew * signal).max(0.0);
+        if strength >= self.pull_threshold {
+            // Buyers in control: stop selling to them, and vice versa
+            if signal > 0.0 {
+                ask_size = 0.0;
+            } else {
+                bid_size = 0.0;
+            }
+        }
+
+        QuoteSkew {
+            mid_offset_bps: self.skew_bps * signal,
+            spread_multiplier,
+            bid_size,
+            ask_size,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ImbalanceMarketMakerConfig {
+    pub exchange_id: ExchangeID,
+    pub instrument: Instrument,
+    /// Full quoted spread around the skewed midpoint before widening
+    pub spread_bps: f64,
+    /// Base size of each quote (1e8), rounded down to the lot after skewing
+    pub order_size: Quantity,
+    /// Time span of best-level changes the OFI sums over, in feed timestamp units
+    pub ofi_window: u64,
+    /// Depth of each venue's book the NOBI counts
+    pub nobi_band: DepthBand,
+    /// Span of the trades behind the NOBI venue weights, e.g. 24h
+    pub volume_window: u64,
+    pub mapping: SignalMapping,
+    pub requote_threshold_ticks: i64,
+    pub max_quote_age: u64,
+    pub max_position: Quantity,
+}
+
+/// Market maker that leans its quotes on order-flow imbalance.
+///
+/// OFI comes from best-level transitions on the quoted venue; NOBI is the
+/// depth imbalance of the global book, each venue weighted by its share of
+/// the traded volume.
+pub struct ImbalanceMarketMaker {
+    config: ImbalanceMarketMakerConfig,
+    quoter: Quoter,
+    ofi: OFICalculator,
+    volumes: VolumeShare,
+    /// Best bid and ask last fed to the OFI, to skip updates behind the touch
+    last_touch: Option<(i64, u64, i64, u64)>,
+    signal: f64,
+}
+
+impl ImbalanceMarketMaker {
+    pub fn new(config: ImbalanceMarketMakerConfig) -> Self {
+        Self {
+            config,
+            quoter: Quoter::new(
+                config.exchange_id,
+                config.instrument,
+                config.requote_threshold_ticks,
+                config.max_quote_age,
+            ),
+            ofi: OFICalculator::new(config.ofi_window),
+            volumes: VolumeShare::new(config.volume_window),
+            last_touch: None,
+            signal: 0.0,
+        }
+    }
+
+    pub fn position(&self) -> i64 {
+        self.quoter.position()
+    }
+
+    /// Blended imbalance signal behind the current quotes, in `[-1, 1]`
+    pub fn signal(&self) -> f64 {
+        self.signal
+    }
+
+    fn sized(&self, side: Side, factor: f64) -> Quantity {
+        let size = (self.config.order_size as f64 * factor) as Quantity;
+        self.quoter.capacity(side, size, self.config.max_position)
+    }
+}
+
+impl Strategy for ImbalanceMarketMaker {
+    fn on_book_update(&mut self, ctx: &mut StrategyContext<'_>, update: &MarketDataUpdate) {
+        self.volumes.advance(update.timestamp);
+        if !self.quoter.is_for(update.exchange_id, update.symbol_id) {
+            return;
+        }
+        let book = ctx.book(update.exchange_id);
+        let (Some(best_bid), Some(best_ask)) = (book.best_bid(), book.best_ask()) else {
+            return;
+        };
+
+        let touch = (best_bid.price, best_bid.quantity, best_ask.price, best_ask.quantity);
+        if self.last_touch != Some(touch) {
+            self.last_touch = Some(touch);
+            self.ofi.update(update.timestamp, touch.0, touch.1, touch.2, touch.3);
+        }
+
+        let weights = self.volumes.weights().unwrap_or(EQUAL_WEIGHTS);
+        let nobi = ctx.books().calculate_nobi(self.config.nobi_band, &weights);
+        let mapping = self.config.mapping;
+        self.signal = mapping.signal(self.ofi.get_ofi(), nobi);
+        let skew = mapping.skew(self.signal);
+
+        let mid_price = (best_bid.price as f64 + best_ask.price as f64) / 2.0;
+        let fair = mid_price * (1.0 + skew.mid_offset_bps / 10000.0);
+        let half_spread = mid_price * (self.config.spread_bps * skew.spread_multiplier / 10000.0) / 2.0;
+
+        let (bid_price, ask_price) =
+            self.quoter.round_quotes(fair - half_spread, fair + half_spread, best_bid.price, best_ask.price);
+        let bid_size = self.sized(Side::Buy, skew.bid_size);
+        let ask_size = self.sized(Side::Sell, skew.ask_size);
+        self.quoter.requote(ctx, Side::Buy, bid_price, bid_size);
+        self.quoter.requote(ctx, Side::Sell, ask_price, ask_size);
+    }
+
+    fn on_trade(&mut self, _ctx: &mut StrategyContext<'_>, trade: &Trade) {
+        if trade.symbol_id == self.config.instrument.symbol_id {
+            self.volumes.on_trade(trade);
+        }
+    }
+
+    fn on_execution_report(&mut self, ctx: &mut StrategyContext<'_>, report: &ExecutionReport) {
+        self.quoter.on_execution_report(ctx, report);
+    }
+
+    fn on_timer(&mut self, ctx: &mut StrategyContext<'_>, timer_id: u64) {
+        self.quoter.on_timer(ctx, timer_id);
+    }
+
+    fn on_feed_status(&mut self, ctx: &mut StrategyContext<'_>, exchange_id: ExchangeID, status: FeedStatus) {
+        if status != FeedStatus::Connected {
+            self.quoter.on_feed_lost(ctx, exchange_id);
+            if exchange_id == self.config.exchange_id {
+                // Transitions across a gap are not real flow
+                self.ofi.reset();
+                self.last_touch = None;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mapping() -> SignalMapping {
+        SignalMapping {
+            ofi_scale: 100_000_000,
+            ofi_weight: 0.5,
+            nobi_weight: 0.5,
+            skew_bps: 4.0,
+            size_skew: 0.5,
+            widen_threshold: 0.5,
+            widen_factor: 2.0,
+            pull_threshold: 0.8,
+        }
+    }
+
+    #[test]
+    fn test_signal_blends_and_saturates() {
+        let mapping = mapping();
+        assert_eq!(mapping.signal(0.0, 0.0), 0.0);
+        assert_eq!(mapping.signal(50_000_000.0, 0.0), 0.25);
+        assert_eq!(mapping.signal(-1e12, -0.2), -0.6);
+        assert_eq!(mapping.signal(1e12, 1.0), 1.0);
+    }
+
+    #[test]
+    fn test_skew_widens_then_pulls_against_flow() {
+        let mapping = mapping();
+
+        let mild = mapping.skew(0.2);
+        assert_eq!(mild.mid_offset_bps, 0.8);
+        assert_eq!(mild.spread_multiplier, 1.0);
+        assert_eq!((mild.bid_size, mild.ask_size), (1.1, 0.9));
+
+        let strong = mapping.skew(-0.6);
+        assert_eq!(strong.spread_multiplier, 2.0);
+        assert!(strong.bid_size > 0.0 && strong.ask_size > 1.0);
+
+        let extreme = mapping.skew(0.9);
+        assert_eq!(extreme.ask_size, 0.0);
+        assert!(extreme.bid_size > 1.0);
+    }
+}
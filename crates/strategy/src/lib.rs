@@ -1,43 +1,38 @@
-use vibe_hft_core::{Order, OrderSide};
-use vibe_hft_market_data::OrderBook;
-use log::info;
-
+use vibe_hft_sbe_messages::{ExchangeID, ExecutionReport, MarketDataUpdate, Trade};
+
+pub mod arbitrage;
+pub mod avellaneda_stoikov;
+pub mod context;
+pub mod host;
+pub mod imbalance;
+pub mod market_maker;
+pub mod quoter;
+
+pub use arbitrage::{
+    find_opportunity, ArbitrageStats, CrossVenueArbitrage, CrossVenueArbitrageConfig, LegRecovery, Opportunity, VenueFees,
+};
+pub use avellaneda_stoikov::{AvellanedaStoikov, AvellanedaStoikovConfig};
+pub use context::{ActionBuffer, FeedStatus, OrderAction, StrategyContext, Timer};
+pub use host::StrategyHost;
+pub use imbalance::{ImbalanceMarketMaker, ImbalanceMarketMakerConfig, QuoteSkew, SignalMapping};
+pub use market_maker::{SimpleMarketMaker, SimpleMarketMakerConfig};
+pub use quoter::Quoter;
+
+/// Event callbacks for a trading strategy.
+///
+/// Callbacks never return orders directly: they act through the
+/// `StrategyContext`, which records actions in a pre-allocated buffer that the
+/// host drains after the callback. Only `on_book_update` is mandatory.
 pub trait Strategy {
-    fn on_market_data(&mut self, order_book: &mut OrderBook) -> Option<Vec<Order>>;
-}
-
-pub struct SimpleMarketMaker {
-    spread_bps: f64,
-    order_size: f64,
-}
-
-impl SimpleMarketMaker {
-    pub fn new(spread_bps: f64, order_size: f64) -> Self {
-        Self {
-            spread_bps,
-            order_size,
-        }
-    }
-}
-
-impl Strategy for SimpleMarketMaker {
-    fn on_market_data(&mut self, order_book: &mut OrderBook) -> Option<Vec<Order>> {
-        let best_bid = order_book.best_bid()?;
-        let best_ask = order_book.best_ask()?;
+    /// A level changed on `update.exchange_id`; the context's books already include it
+    fn on_book_update(&mut self, ctx: &mut StrategyContext<'_>, update: &MarketDataUpdate);
 
-        let mid_price = (best_bid.price as f64 + best_ask.price as f64) / 2.0;
-        let half_spread = mid_price * (self.spread_bps / 10000.0) / 2.0;
+    fn on_trade(&mut self, _ctx: &mut StrategyContext<'_>, _trade: &Trade) {}
 
-        let bid_price = mid_price - half_spread;
-        let ask_price = mid_price + half_spread;
+    /// One of our orders changed; the context's order manager already includes it
+    fn on_execution_report(&mut self, _ctx: &mut StrategyContext<'_>, _report: &ExecutionReport) {}
 
-        // In a real HFT system, we would manage order state, cancellations, etc.
-        // Here we just generate "ideal" quotes for demonstration.
-        
-        info!("Strategy Signal: Quote Bid {:.2} @ {:.4} | Ask {:.2} @ {:.4}", 
-            bid_price, self.order_size, ask_price, self.order_size);
+    fn on_timer(&mut self, _ctx: &mut StrategyContext<'_>, _timer_id: u64) {}
 
-        // Returning None as we are just logging signals for now
-        None 
-    }
+    fn on_feed_status(&mut self, _ctx: &mut StrategyContext<'_>, _exchange_id: ExchangeID, _status: FeedStatus) {}
 }
@@ -0,0 +1,88 @@
+use vibe_hft_core::{Instrument, Quantity};
+use vibe_hft_sbe_messages::{ExchangeID, ExecutionReport, MarketDataUpdate, Side};
+
+use crate::context::{FeedStatus, StrategyContext};
+use crate::quoter::Quoter;
+use crate::Strategy;
+
+#[derive(Debug, Clone, Copy)]
+pub struct SimpleMarketMakerConfig {
+    pub exchange_id: ExchangeID,
+    pub instrument: Instrument,
+    /// Full quoted spread around mid
+    pub spread_bps: f64,
+    /// Size of each quote (1e8), rounded down to the lot
+    pub order_size: Quantity,
+    /// Leave a quote alone until its target moves at least this many ticks
+    pub requote_threshold_ticks: i64,
+    /// Cancel a quote that has rested this long without being refreshed (ns)
+    pub max_quote_age: u64,
+    /// Absolute inventory limit (1e8); the side that would grow it is pulled at the limit
+    pub max_position: Quantity,
+}
+
+/// Quotes a symmetric spread around mid on one venue.
+pub struct SimpleMarketMaker {
+    config: SimpleMarketMakerConfig,
+    quoter: Quoter,
+}
+
+impl SimpleMarketMaker {
+    pub fn new(config: SimpleMarketMakerConfig) -> Self {
+        Self {
+            config,
+            quoter: Quoter::new(
+                config.exchange_id,
+                config.instrument,
+                config.requote_threshold_ticks,
+                config.max_quote_age,
+            ),
+        }
+    }
+
+    /// Net filled inventory (1e8), positive when long
+    pub fn position(&self) -> i64 {
+        self.quoter.position()
+    }
+}
+
+impl Strategy for SimpleMarketMaker {
+    fn on_book_update(&mut self, ctx: &mut StrategyContext<'_>, update: &MarketDataUpdate) {
+        if !self.quoter.is_for(update.exchange_id, update.symbol_id) {
+            return;
+        }
+        let book = ctx.book(update.exchange_id);
+        let (Some(best_bid), Some(best_ask)) = (book.best_bid(), book.best_ask()) else {
+            return;
+        };
+
+        let mid_price = (best_bid.price as f64 + best_ask.price as f64) / 2.0;
+        let half_spread = mid_price * (self.config.spread_bps / 10000.0) / 2.0;
+
+        let (bid_price, ask_price) = self.quoter.round_quotes(
+            mid_price - half_spread,
+            mid_price + half_spread,
+            best_bid.price,
+            best_ask.price,
+        );
+
+        let bid_size = self.quoter.capacity(Side::Buy, self.config.order_size, self.config.max_position);
+        let ask_size = self.quoter.capacity(Side::Sell, self.config.order_size, self.config.max_position);
+        self.quoter.requote(ctx, Side::Buy, bid_price, bid_size);
+        self.quoter.requote(ctx, Side::Sell, ask_price, ask_size);
+    }
+
+    fn on_execution_report(&mut self, ctx: &mut StrategyContext<'_>, report: &ExecutionReport) {
+        self.quoter.on_execution_report(ctx, report);
+    }
+
+    fn on_timer(&mut self, ctx: &mut StrategyContext<'_>, timer_id: u64) {
+        self.quoter.on_timer(ctx, timer_id);
+    }
+
+    fn on_feed_status(&mut self, ctx: &mut StrategyContext<'_>, exchange_id: ExchangeID, status: FeedStatus) {
+        if status != FeedStatus::Connected {
+            self.quoter.on_feed_lost(ctx, exchange_id);
+        }
+    }
+}
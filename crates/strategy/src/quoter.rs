@@ -0,0 +1,159 @@
+use log::{debug, info};
+use vibe_hft_core::{Instrument, Price, Quantity, Timestamp};
+use vibe_hft_sbe_messages::{ExchangeID, ExecutionReport, Side};
+
+use crate::context::StrategyContext;
+
+#[derive(Debug, Clone, Copy)]
+struct Quote {
+    cl_ord_id: u64,
+    price: Price,
+    placed_at: Timestamp,
+}
+
+/// Order handling shared by the market-making strategies: one working quote
+/// per side on a single instrument, inventory tracking from fills, and
+/// threshold-based requoting.
+///
+/// Quotes are only moved when the target drifts past the requote threshold,
+/// so small mid flickers do not burn order-rate limits. A timer per quote
+/// cancels it once it is older than `max_quote_age`, which also covers a feed
+/// that has gone quiet.
+#[derive(Debug)]
+pub struct Quoter {
+    exchange_id: ExchangeID,
+    instrument: Instrument,
+    requote_threshold_ticks: i64,
+    max_quote_age: u64,
+    position: i64,
+    bid: Option<Quote>,
+    ask: Option<Quote>,
+}
+
+impl Quoter {
+    pub fn new(exchange_id: ExchangeID, instrument: Instrument, requote_threshold_ticks: i64, max_quote_age: u64) -> Self {
+        Self {
+            exchange_id,
+            instrument,
+            requote_threshold_ticks,
+            max_quote_age,
+            position: 0,
+            bid: None,
+            ask: None,
+        }
+    }
+
+    pub fn instrument(&self) -> &Instrument {
+        &self.instrument
+    }
+
+    /// Net filled inventory (1e8), positive when long
+    pub fn position(&self) -> i64 {
+        self.position
+    }
+
+    /// True if the venue and symbol are the instrument we quote
+    pub fn is_for(&self, exchange_id: ExchangeID, symbol_id: u32) -> bool {
+        exchange_id == self.exchange_id && symbol_id == self.instrument.symbol_id
+    }
+
+    /// Lot-rounded size we may quote on `side` without breaching `max_position`
+    pub fn capacity(&self, side: Side, order_size: Quantity, max_position: Quantity) -> Quantity {
+        let max = max_position as i64;
+        let room = match side {
+            Side::Buy => max - self.position,
+            Side::Sell => max + self.position,
+        };
+        let size = (room.max(0) as Quantity).min(order_size);
+        self.instrument.floor_quantity(size)
+    }
+
+    /// Bid and ask rounded outwards to the tick grid, never crossing the touch
+    pub fn round_quotes(&self, bid: f64, ask: f64, best_bid: Price, best_ask: Price) -> (Price, Price) {
+        let tick = self.instrument.tick_size;
+        let bid = self.instrument.floor_price(bid as Price).min(best_ask - tick);
+        let ask = self.instrument.ceil_price(ask as Price).max(best_bid + tick);
+        (bid, ask)
+    }
+
+    fn slot(&mut self, side: Side) -> &mut Option<Quote> {
+        match side {
+            Side::Buy => &mut self.bid,
+            Side::Sell => &mut self.ask,
+        }
+    }
+
+    /// Place, move or pull the quote on one side so it rests at `target` for `size`.
+    /// A `size` of zero pulls the quote.
+    pub fn requote(&mut self, ctx: &mut StrategyContext<'_>, side: Side, target: Price, size: Quantity) {
+        let threshold = self.requote_threshold_ticks * self.instrument.tick_size;
+        let now = ctx.now();
+
+        let current = *self.slot(side);
+        let working = current.and_then(|q| ctx.orders().get(q.cl_ord_id).map(|o| (q, *o)));
+
+        match working {
+            // Cancel in flight: wait for the venue before doing anything else
+            Some((_, order)) if order.cancel_pending => {}
+            Some((quote, _)) if size == 0 => {
+                let _ = ctx.cancel(quote.cl_ord_id);
+            }
+            Some((quote, order)) => {
+                if (target - quote.price).abs() < threshold {
+                    return;
+                }
+                debug!("Requote {:?} {} -> {}", side, quote.price, target);
+                if let Ok(Some(cl_ord_id)) = ctx.amend(quote.cl_ord_id, target, order.filled_quantity + size) {
+                    *self.slot(side) = Some(Quote { cl_ord_id, price: target, placed_at: now });
+                    ctx.schedule_timer(now + self.max_quote_age, cl_ord_id);
+                }
+            }
+            None if size > 0 => {
+                info!("Quote {:?} {} @ {}", side, size, target);
+                let cl_ord_id = ctx.submit_limit(self.exchange_id, self.instrument.symbol_id, side, target, size);
+                *self.slot(side) = Some(Quote { cl_ord_id, price: target, placed_at: now });
+                ctx.schedule_timer(now + self.max_quote_age, cl_ord_id);
+            }
+            None => *self.slot(side) = None,
+        }
+    }
+
+    pub fn cancel_all(&mut self, ctx: &mut StrategyContext<'_>) {
+        for quote in [self.bid, self.ask].into_iter().flatten() {
+            let _ = ctx.cancel(quote.cl_ord_id);
+        }
+    }
+
+    /// Track inventory and forget quotes the venue is done with
+    pub fn on_execution_report(&mut self, ctx: &mut StrategyContext<'_>, report: &ExecutionReport) {
+        if report.filled_quantity > 0 {
+            match report.side {
+                Side::Buy => self.position += report.filled_quantity as i64,
+                Side::Sell => self.position -= report.filled_quantity as i64,
+            }
+        }
+
+        let live = ctx.orders().get(report.cl_ord_id).is_some_and(|o| o.is_live());
+        for slot in [&mut self.bid, &mut self.ask] {
+            if slot.is_some_and(|q| q.cl_ord_id == report.cl_ord_id) && !live {
+                *slot = None;
+            }
+        }
+    }
+
+    /// Cancel the quote guarded by this timer if it was not refreshed since
+    pub fn on_timer(&mut self, ctx: &mut StrategyContext<'_>, timer_id: u64) {
+        for quote in [self.bid, self.ask].into_iter().flatten() {
+            if quote.cl_ord_id == timer_id && ctx.now() >= quote.placed_at + self.max_quote_age {
+                debug!("Cancel stale quote {}", quote.cl_ord_id);
+                let _ = ctx.cancel(quote.cl_ord_id);
+            }
+        }
+    }
+
+    pub fn on_feed_lost(&mut self, ctx: &mut StrategyContext<'_>, exchange_id: ExchangeID) {
+        if exchange_id == self.exchange_id {
+            self.cancel_all(ctx);
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use vibe_hft_backtest::testing::{self, book, print, CENT, ONE, SEC};
+use vibe_hft_backtest::{LatencyConfig, TapeEvent};
+use vibe_hft_core::{Instrument, InstrumentType};
+use vibe_hft_sbe_messages::{ExchangeID, Side};
+use vibe_hft_strategy::{AvellanedaStoikov, AvellanedaStoikovConfig, StrategyHost};
+
+const MS: u64 = 1_000_000;
+
+fn config() -> AvellanedaStoikovConfig {
+    AvellanedaStoikovConfig {
+        exchange_id: ExchangeID::Binance,
+        instrument: Instrument {
+            symbol_id: 1,
+            instrument_type: InstrumentType::Spot,
+            tick_size: CENT,
+            lot_size: 1_000,
+        },
+        gamma: 0.1,
+        horizon: 60 * SEC,
+        order_size: ONE,
+        max_position: 5 * ONE,
+        ewma_alpha: 0.5,
+        initial_variance: 0.01,
+        initial_intensity: 10.0,
+        warmup_samples: u32::MAX,
+        requote_threshold_ticks: 2,
+        max_quote_age: 3_600 * SEC,
+    }
+}
+
+fn backtest(config: AvellanedaStoikovConfig, tape: Vec<TapeEvent>) -> StrategyHost<AvellanedaStoikov> {
+    testing::backtest(AvellanedaStoikov::new(config), tape, LatencyConfig::constant(100))
+}
+
+fn working_price(host: &StrategyHost<AvellanedaStoikov>, side: Side) -> f64 {
+    let order = host.orders().live_orders().find(|o| o.side == side).expect("no working quote");
+    order.price as f64 / ONE as f64
+}
+
+#[test]
+fn test_flat_inventory_quotes_symmetric_around_mid() {
+    let host = backtest(config(), vec![book(MS, Side::Buy, 10_000, ONE), book(2 * MS, Side::Sell, 10_002, ONE)]);
+
+    let (reservation, spread) = host.strategy().last_quote().unwrap();
+    assert!((reservation - 100.01).abs() < 1e-9);
+    assert!(spread > 0.25 && spread < 0.27);
+    assert_eq!(working_price(&host, Side::Buy), 99.88);
+    assert_eq!(working_price(&host, Side::Sell), 100.14);
+}
+
+#[test]
+fn test_long_inventory_skews_quotes_down() {
+    let host = backtest(
+        config(),
+        vec![
+            book(MS, Side::Buy, 10_000, ONE),
+            book(2 * MS, Side::Sell, 10_002, ONE),
+            // Sweeps through our bid: we are now long one unit
+            print(3 * MS, Side::Sell, 9_980, 2 * ONE),
+            book(4 * MS, Side::Buy, 9_000, ONE),
+        ],
+    );
+    assert_eq!(host.strategy().position(), ONE as i64);
+
+    let mid = 100.01;
+    let (reservation, _) = host.strategy().last_quote().unwrap();
+    assert!(reservation < mid);
+
+    // Eager to sell, reluctant to buy more
+    let bid_distance = mid - working_price(&host, Side::Buy);
+    let ask_distance = working_price(&host, Side::Sell) - mid;
+    assert!(ask_distance < bid_distance);
+}
+
+#[test]
+fn test_estimates_volatility_and_intensity_from_flow() {
+    let config = AvellanedaStoikovConfig { warmup_samples: 3, ..config() };
+    let mut tape = vec![book(SEC, Side::Buy, 10_000, ONE), book(SEC, Side::Sell, 10_002, ONE)];
+    // Mid steps up one cent per second, trades print 5 cents from mid
+    for i in 1..=4 {
+        let t = SEC + i as u64 * SEC;
+        tape.push(book(t, Side::Sell, 10_002 + i, ONE));
+        tape.push(book(t, Side::Sell, 10_001 + i, 0));
+        tape.push(book(t, Side::Buy, 10_000 + i, ONE));
+        tape.push(book(t, Side::Buy, 9_999 + i, 0));
+        tape.push(print(t + MS, Side::Buy, 10_006 + i, 1_000));
+    }
+    let host = backtest(config, tape);
+
+    assert!((host.strategy().variance() - 1e-4).abs() < 1e-6);
+    assert!((host.strategy().intensity() - 20.0).abs() < 1e-6);
+}
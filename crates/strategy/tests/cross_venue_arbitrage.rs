@@ -0,0 +1,79 @@
+use vibe_hft_backtest::testing::{self, book_on, CENT, ONE};
+use vibe_hft_backtest::{ExchangeConfig, LatencyConfig, TapeEvent};
+use vibe_hft_core::{Instrument, InstrumentType};
+use vibe_hft_sbe_messages::{ExchangeID, Side};
+use vibe_hft_strategy::{CrossVenueArbitrage, CrossVenueArbitrageConfig, LegRecovery, StrategyHost, VenueFees};
+
+fn config(trading_enabled: bool, leg_recovery: LegRecovery) -> CrossVenueArbitrageConfig {
+    CrossVenueArbitrageConfig {
+        instrument: Instrument {
+            symbol_id: 1,
+            instrument_type: InstrumentType::Spot,
+            tick_size: CENT,
+            lot_size: 1_000,
+        },
+        // Matches the simulated venues' default taker fee
+        taker_fees_bps: VenueFees { binance: 5.0, bybit: 5.0, coinbase: 5.0 },
+        min_edge_bps: 5.0,
+        max_quantity: 3 * ONE,
+        min_quantity: ONE / 100,
+        trading_enabled,
+        leg_recovery,
+        leg_timeout: 1_000_000,
+    }
+}
+
+/// Binance 99.90 / 100.00 against Bybit 100.20 / 100.30: buy Binance, sell Bybit
+fn crossed_books() -> Vec<TapeEvent> {
+    vec![
+        book_on(1_000, ExchangeID::Binance, Side::Buy, 9_990, 5 * ONE),
+        book_on(1_001, ExchangeID::Binance, Side::Sell, 10_000, ONE),
+        book_on(1_002, ExchangeID::Bybit, Side::Sell, 10_030, 2 * ONE),
+        book_on(1_003, ExchangeID::Bybit, Side::Buy, 10_020, 2 * ONE),
+    ]
+}
+
+fn backtest(config: CrossVenueArbitrageConfig, tape: Vec<TapeEvent>) -> StrategyHost<CrossVenueArbitrage> {
+    let venues = [ExchangeID::Binance, ExchangeID::Bybit].map(|venue| (venue, ExchangeConfig::default()));
+    testing::backtest_venues(CrossVenueArbitrage::new(config), tape, venues, LatencyConfig::constant(10))
+}
+
+#[test]
+fn test_detects_and_logs_without_trading() {
+    let mut tape = crossed_books();
+    // Deep-book churn behind the same cross is not a new opportunity
+    tape.push(book_on(1_004, ExchangeID::Bybit, Side::Sell, 10_050, ONE));
+    let host = backtest(config(false, LegRecovery::Hedge), tape);
+
+    let stats = host.strategy().stats();
+    assert_eq!(stats.opportunities, 1);
+    assert_eq!(stats.pairs_sent, 0);
+    // 20 cents on 100.00 less 10 bps of fees, on the single lot Binance offers
+    assert!((stats.best_edge_bps - 9.99).abs() < 0.01);
+    assert!((stats.expected_profit - 0.0999).abs() < 1e-3);
+    assert_eq!(host.orders().live_orders().count(), 0);
+}
+
+#[test]
+fn test_pair_fills_flat() {
+    let host = backtest(config(true, LegRecovery::Hedge), crossed_books());
+
+    let stats = host.strategy().stats();
+    assert_eq!(stats.pairs_sent, 1);
+    assert_eq!(stats.legs_recovered, 0);
+    assert_eq!(host.strategy().position(), 0);
+}
+
+#[test]
+fn test_missed_leg_is_unwound() {
+    let mut tape = crossed_books();
+    // The Bybit bid is gone before our sell arrives
+    tape.push(book_on(1_020, ExchangeID::Bybit, Side::Buy, 10_020, 0));
+    let host = backtest(config(true, LegRecovery::Unwind), tape);
+
+    let stats = host.strategy().stats();
+    assert_eq!(stats.pairs_sent, 1);
+    assert_eq!(stats.legs_recovered, 1);
+    // Bought on Binance, sold back into the Binance bid
+    assert_eq!(host.strategy().position(), 0);
+}
@@ -0,0 +1,89 @@
+use vibe_hft_backtest::testing::{self, book, CENT, ONE};
+use vibe_hft_backtest::{LatencyConfig, TapeEvent};
+use vibe_hft_core::{Instrument, InstrumentType};
+use vibe_hft_market_data::nobi::DepthBand;
+use vibe_hft_sbe_messages::{ExchangeID, Side};
+use vibe_hft_strategy::{ImbalanceMarketMaker, ImbalanceMarketMakerConfig, SignalMapping, StrategyHost};
+
+fn config(ofi_weight: f64, nobi_weight: f64) -> ImbalanceMarketMakerConfig {
+    ImbalanceMarketMakerConfig {
+        exchange_id: ExchangeID::Binance,
+        instrument: Instrument {
+            symbol_id: 1,
+            instrument_type: InstrumentType::Spot,
+            tick_size: CENT,
+            lot_size: 1_000,
+        },
+        spread_bps: 10.0,
+        order_size: ONE / 2,
+        ofi_window: 1_000_000_000,
+        nobi_band: DepthBand::Levels(10),
+        volume_window: 86_400_000_000_000,
+        mapping: SignalMapping {
+            ofi_scale: ONE,
+            ofi_weight,
+            nobi_weight,
+            skew_bps: 4.0,
+            size_skew: 0.5,
+            widen_threshold: 0.5,
+            widen_factor: 1.0,
+            pull_threshold: 0.8,
+        },
+        requote_threshold_ticks: 1,
+        max_quote_age: 1_000_000_000_000,
+        max_position: 10 * ONE,
+    }
+}
+
+fn backtest(config: ImbalanceMarketMakerConfig, tape: Vec<TapeEvent>) -> StrategyHost<ImbalanceMarketMaker> {
+    testing::backtest(ImbalanceMarketMaker::new(config), tape, LatencyConfig::constant(10))
+}
+
+/// (side, price in cents, quantity) of every working order
+fn working(host: &StrategyHost<ImbalanceMarketMaker>) -> Vec<(Side, i64, u64)> {
+    host.orders().live_orders().map(|o| (o.side, o.price / CENT, o.quantity)).collect()
+}
+
+#[test]
+fn test_balanced_flow_quotes_symmetric() {
+    let host = backtest(
+        config(1.0, 0.0),
+        vec![book(1_000, Side::Buy, 10_000, ONE), book(2_000, Side::Sell, 10_002, ONE)],
+    );
+
+    assert_eq!(host.strategy().signal(), 0.0);
+    assert_eq!(working(&host), [(Side::Buy, 9_995, ONE / 2), (Side::Sell, 10_007, ONE / 2)]);
+}
+
+#[test]
+fn test_buy_pressure_lifts_bid_and_pulls_ask() {
+    let host = backtest(
+        config(1.0, 0.0),
+        vec![
+            book(1_000, Side::Buy, 10_000, ONE),
+            book(2_000, Side::Sell, 10_002, ONE),
+            // A large bid steps in ahead of the touch
+            book(3_000, Side::Buy, 10_001, 5 * ONE),
+        ],
+    );
+
+    assert_eq!(host.strategy().signal(), 1.0);
+    // Mid 100.015 skewed up 4 bps, less the 5 bps half spread; bid size tilted up 50%
+    assert_eq!(working(&host), [(Side::Buy, 10_000, 3 * ONE / 4)]);
+}
+
+#[test]
+fn test_ask_heavy_book_pulls_bid() {
+    let host = backtest(
+        config(0.0, 1.0),
+        vec![book(1_000, Side::Buy, 10_000, ONE), book(2_000, Side::Sell, 10_002, 20 * ONE)],
+    );
+
+    assert!(host.strategy().signal() < -0.8);
+    let orders = working(&host);
+    assert_eq!(orders.len(), 1);
+    assert_eq!(orders[0].0, Side::Sell);
+    // Leaning with the sellers: more size, quoted below the plain 100.07
+    assert!(orders[0].1 < 10_007);
+    assert!(orders[0].2 > ONE / 2);
+}
@@ -0,0 +1,111 @@
+use vibe_hft_backtest::testing::{self, book, print, CENT};
+use vibe_hft_backtest::{LatencyConfig, TapeEvent};
+use vibe_hft_core::{Instrument, InstrumentType};
+use vibe_hft_sbe_messages::{ExchangeID, Side};
+use vibe_hft_strategy::{SimpleMarketMaker, SimpleMarketMakerConfig, StrategyHost};
+
+fn config() -> SimpleMarketMakerConfig {
+    SimpleMarketMakerConfig {
+        exchange_id: ExchangeID::Binance,
+        instrument: Instrument {
+            symbol_id: 1,
+            instrument_type: InstrumentType::Spot,
+            tick_size: CENT,
+            lot_size: 1_000,
+        },
+        spread_bps: 10.0,
+        order_size: 50_000_500, // not a whole number of lots
+        requote_threshold_ticks: 5,
+        max_quote_age: 1_000_000_000_000,
+        max_position: 100_000_000,
+    }
+}
+
+/// 100.00 / 100.02 market
+fn opening_book() -> Vec<TapeEvent> {
+    vec![
+        book(1_000, Side::Buy, 10_000, 100_000_000),
+        book(2_000, Side::Sell, 10_002, 100_000_000),
+    ]
+}
+
+fn backtest(config: SimpleMarketMakerConfig, tape: Vec<TapeEvent>) -> StrategyHost<SimpleMarketMaker> {
+    testing::backtest(SimpleMarketMaker::new(config), tape, LatencyConfig::constant(10))
+}
+
+/// (cl_ord_id, side, price in cents, quantity) of every working order
+fn working(host: &StrategyHost<SimpleMarketMaker>) -> Vec<(u64, Side, i64, u64)> {
+    host.orders()
+        .live_orders()
+        .map(|o| (o.cl_ord_id, o.side, o.price / CENT, o.quantity))
+        .collect()
+}
+
+#[test]
+fn test_quotes_are_tick_and_lot_aligned() {
+    let host = backtest(config(), opening_book());
+
+    // Mid 100.01 +/- 5 bps: the bid rounds down and the ask up to the cent
+    assert_eq!(
+        working(&host),
+        [(1, Side::Buy, 9_995, 50_000_000), (2, Side::Sell, 10_007, 50_000_000)]
+    );
+}
+
+#[test]
+fn test_requotes_only_past_threshold() {
+    // Mid drifts up 2 cents: targets move 2 ticks, under the 5-tick threshold
+    let mut small = opening_book();
+    small.extend([
+        book(3_000, Side::Sell, 10_004, 100_000_000),
+        book(4_000, Side::Sell, 10_002, 0),
+        book(5_000, Side::Buy, 10_002, 100_000_000),
+        book(6_000, Side::Buy, 10_000, 0),
+    ]);
+    let ids: Vec<_> = working(&backtest(config(), small)).iter().map(|o| o.0).collect();
+    assert_eq!(ids, [1, 2]);
+
+    // Mid jumps 10 cents: both sides are moved to the new targets
+    let mut large = opening_book();
+    large.extend([
+        book(3_000, Side::Sell, 10_012, 100_000_000),
+        book(4_000, Side::Sell, 10_002, 0),
+        book(5_000, Side::Buy, 10_010, 100_000_000),
+        book(6_000, Side::Buy, 10_000, 0),
+    ]);
+    let orders = working(&backtest(config(), large));
+    assert_eq!(orders.len(), 2);
+    assert!(orders.iter().all(|o| o.0 > 2));
+    assert!(orders.iter().any(|o| (o.1, o.2, o.3) == (Side::Buy, 10_005, 50_000_000)));
+    assert!(orders.iter().any(|o| (o.1, o.2, o.3) == (Side::Sell, 10_017, 50_000_000)));
+}
+
+#[test]
+fn test_stops_bidding_at_max_position() {
+    let config = SimpleMarketMakerConfig { max_position: 50_000_000, ..config() };
+    let mut tape = opening_book();
+    tape.extend([
+        // Trades through our bid and fills it, reaching the limit
+        print(3_000, Side::Sell, 9_990, 100_000_000),
+        book(4_000, Side::Buy, 9_000, 100_000_000),
+    ]);
+    let host = backtest(config, tape);
+
+    assert_eq!(host.strategy().position(), 50_000_000);
+    let orders = working(&host);
+    assert!(!orders.is_empty());
+    assert!(orders.iter().all(|o| o.1 == Side::Sell));
+}
+
+#[test]
+fn test_cancels_stale_quotes() {
+    let config = SimpleMarketMakerConfig { max_quote_age: 5_000, ..config() };
+    let mut tape = opening_book();
+    // Nothing moves the touch for a while, then a deep level changes
+    tape.push(book(20_000, Side::Buy, 9_000, 100_000_000));
+    let host = backtest(config, tape);
+
+    // The original quotes timed out and were canceled; the late update requoted
+    let ids: Vec<_> = working(&host).iter().map(|o| o.0).collect();
+    assert_eq!(ids, [3, 4]);
+}
@@ -1,5 +1,10 @@
+use vibe_hft_sbe_messages::{
+    Alert, CvdUpdate, DivergenceKind, DivergenceSignal, ForcedLiquidation, IndicatorKind, IndicatorUpdate, LiquidationLevel,
+    MarkPriceUpdate, MessageHeader, OpenInterest, PnlSnapshot, ProfileSpan, Side, VolumeNode,
+    VolumeProfileLevel, VolumeProfileSummary,
+};
+use vibe_hft_core::to_millis;
 use wasm_bindgen::prelude::*;
-use vibe_hft_sbe_messages::{MarketDataUpdate, ExchangeID, Side};
 
 #[wasm_bindgen]
 extern "C" {
@@ -12,6 +17,9 @@ pub fn init_client() {
     log("WASM Client Initialized");
 }
 
+// Times are handed to JavaScript in epoch ms: a ns timestamp is past the
+// 2^53 a JS number holds exactly.
+
 #[derive(serde::Serialize)]
 pub struct DecodedUpdate {
     pub timestamp: u64,
@@ -31,7 +39,7 @@ pub fn decode_market_data(data: &[u8]) -> Result<JsValue, JsValue> {
     
     let mut ts_bytes = [0u8; 8];
     ts_bytes.copy_from_slice(&data[0..8]);
-    let timestamp = u64::from_le_bytes(ts_bytes);
+    let timestamp = to_millis(u64::from_le_bytes(ts_bytes));
 
     let side_byte = data[13];
     let side = match side_byte {
@@ -60,27 +68,401 @@ pub fn decode_market_data(data: &[u8]) -> Result<JsValue, JsValue> {
     Ok(serde_wasm_bindgen::to_value(&update)?)
 }
 
+/// Template id of a framed gateway message, or 0 for a bare market data update
+#[wasm_bindgen]
+pub fn template_id(data: &[u8]) -> u16 {
+    MessageHeader::read(data).map_or(0, |header| header.template_id)
+}
+
+#[derive(serde::Serialize)]
+pub struct DecodedPnl {
+    pub timestamp: u64,
+    /// 0 when netted across venues
+    pub exchange_id: u8,
+    pub symbol_id: u32,
+    pub position: f64,
+    pub avg_entry_price: f64,
+    pub mark_price: f64,
+    pub realized_pnl: f64,
+    pub unrealized_pnl: f64,
+    pub fees: f64,
+    pub funding: f64,
+}
+
+#[wasm_bindgen]
+pub fn decode_pnl_snapshot(data: &[u8]) -> Result<JsValue, JsValue> {
+    let snapshot = PnlSnapshot::from_bytes(data).ok_or_else(|| JsValue::from_str("Not a PnL snapshot"))?;
+    let scale = |raw: i64| raw as f64 / 100_000_000.0;
+
+    let decoded = DecodedPnl {
+        timestamp: to_millis(snapshot.timestamp),
+        exchange_id: snapshot.exchange_id.map_or(0, |id| id as u8),
+        symbol_id: snapshot.symbol_id,
+        position: scale(snapshot.position),
+        avg_entry_price: scale(snapshot.avg_entry_price),
+        mark_price: scale(snapshot.mark_price),
+        realized_pnl: scale(snapshot.realized_pnl),
+        unrealized_pnl: scale(snapshot.unrealized_pnl),
+        fees: scale(snapshot.fees),
+        funding: scale(snapshot.funding),
+    };
+
+    Ok(serde_wasm_bindgen::to_value(&decoded)?)
+}
+
+#[derive(serde::Serialize)]
+pub struct DecodedCvd {
+    pub timestamp: u64,
+    /// 0 when aggregated across venues
+    pub exchange_id: u8,
+    pub symbol_id: u32,
+    pub bar_start: u64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub buy_volume: f64,
+    pub sell_volume: f64,
+    pub closed: bool,
+}
+
+#[wasm_bindgen]
+pub fn decode_cvd_update(data: &[u8]) -> Result<JsValue, JsValue> {
+    let update = CvdUpdate::from_bytes(data).ok_or_else(|| JsValue::from_str("Not a CVD update"))?;
+    let scale = |raw: i64| raw as f64 / 100_000_000.0;
+
+    let decoded = DecodedCvd {
+        timestamp: to_millis(update.timestamp),
+        exchange_id: update.exchange_id.map_or(0, |id| id as u8),
+        symbol_id: update.symbol_id,
+        bar_start: to_millis(update.bar_start),
+        open: scale(update.open),
+        high: scale(update.high),
+        low: scale(update.low),
+        close: scale(update.close),
+        buy_volume: scale(update.buy_volume as i64),
+        sell_volume: scale(update.sell_volume as i64),
+        closed: update.closed,
+    };
+
+    Ok(serde_wasm_bindgen::to_value(&decoded)?)
+}
+
+#[derive(serde::Serialize)]
+pub struct DecodedDivergence {
+    pub timestamp: u64,
+    /// 0 when aggregated across venues
+    pub exchange_id: u8,
+    pub symbol_id: u32,
+    pub kind: String,
+    /// "R" (reversal) or "C" (continuation), as drawn on the chart
+    pub marker: String,
+    pub bullish: bool,
+    pub pivot_time: u64,
+    pub pivot_price: f64,
+    pub prev_pivot_time: u64,
+    pub prev_pivot_price: f64,
+    pub confidence: u8,
+}
+
+#[wasm_bindgen]
+pub fn decode_divergence_signal(data: &[u8]) -> Result<JsValue, JsValue> {
+    let signal = DivergenceSignal::from_bytes(data).ok_or_else(|| JsValue::from_str("Not a divergence signal"))?;
+    let scale = |raw: i64| raw as f64 / 100_000_000.0;
+    let kind = match signal.kind {
+        DivergenceKind::Regular => "Regular",
+        DivergenceKind::Hidden => "Hidden",
+        DivergenceKind::Absorption => "Absorption",
+    };
+
+    let decoded = DecodedDivergence {
+        timestamp: to_millis(signal.timestamp),
+        exchange_id: signal.exchange_id.map_or(0, |id| id as u8),
+        symbol_id: signal.symbol_id,
+        kind: kind.to_string(),
+        marker: signal.kind.marker().to_string(),
+        bullish: signal.direction == Side::Buy,
+        pivot_time: to_millis(signal.pivot_time),
+        pivot_price: scale(signal.pivot_price),
+        prev_pivot_time: to_millis(signal.prev_pivot_time),
+        prev_pivot_price: scale(signal.prev_pivot_price),
+        confidence: signal.confidence,
+    };
+
+    Ok(serde_wasm_bindgen::to_value(&decoded)?)
+}
+
+fn indicator_name(kind: IndicatorKind) -> &'static str {
+    match kind {
+        IndicatorKind::Ofi => "OFI",
+        IndicatorKind::Nobi => "NOBI",
+        IndicatorKind::Cvd => "CVD",
+        IndicatorKind::Spread => "Spread",
+    }
+}
+
+#[derive(serde::Serialize)]
+pub struct DecodedIndicator {
+    pub timestamp: u64,
+    /// 0 when aggregated across venues
+    pub exchange_id: u8,
+    pub symbol_id: u32,
+    pub kind: String,
+    pub window: u64,
+    pub value: f64,
+}
+
+#[wasm_bindgen]
+pub fn decode_indicator_update(data: &[u8]) -> Result<JsValue, JsValue> {
+    let update = IndicatorUpdate::from_bytes(data).ok_or_else(|| JsValue::from_str("Not an indicator update"))?;
+    let kind = indicator_name(update.kind);
+
+    let decoded = DecodedIndicator {
+        timestamp: to_millis(update.timestamp),
+        exchange_id: update.exchange_id.map_or(0, |id| id as u8),
+        symbol_id: update.symbol_id,
+        kind: kind.to_string(),
+        window: to_millis(update.window),
+        value: update.value as f64 / 100_000_000.0,
+    };
+
+    Ok(serde_wasm_bindgen::to_value(&decoded)?)
+}
+
+#[derive(serde::Serialize)]
+pub struct DecodedAlert {
+    pub timestamp: u64,
+    /// 0 when aggregated across venues
+    pub exchange_id: u8,
+    pub symbol_id: u32,
+    pub rule_id: u32,
+    /// "Aggressive Flow" for the PRD rule, otherwise "Rule <id>"
+    pub name: String,
+    pub indicator: String,
+    pub bullish: bool,
+    pub value: f64,
+    /// Per second
+    pub rate: f64,
+}
+
+#[wasm_bindgen]
+pub fn decode_alert(data: &[u8]) -> Result<JsValue, JsValue> {
+    let alert = Alert::from_bytes(data).ok_or_else(|| JsValue::from_str("Not an alert"))?;
+    let scale = |raw: i64| raw as f64 / 100_000_000.0;
+    let name = match alert.rule_id {
+        Alert::AGGRESSIVE_FLOW => "Aggressive Flow".to_string(),
+        id => format!("Rule {}", id),
+    };
+
+    let decoded = DecodedAlert {
+        timestamp: to_millis(alert.timestamp),
+        exchange_id: alert.exchange_id.map_or(0, |id| id as u8),
+        symbol_id: alert.symbol_id,
+        rule_id: alert.rule_id,
+        name,
+        indicator: indicator_name(alert.indicator).to_string(),
+        bullish: alert.direction == Side::Buy,
+        value: scale(alert.value),
+        rate: scale(alert.rate),
+    };
+
+    Ok(serde_wasm_bindgen::to_value(&decoded)?)
+}
+
+#[derive(serde::Serialize)]
+pub struct DecodedLiquidationLevel {
+    pub timestamp: u64,
+    /// 0 when aggregated across venues
+    pub exchange_id: u8,
+    pub symbol_id: u32,
+    /// Longs liquidated below the market, otherwise shorts above it
+    pub long: bool,
+    /// Lower bound of the bucket
+    pub price: f64,
+    pub bucket_size: f64,
+    /// Estimated size liquidated in the bucket; 0 clears it
+    pub intensity: f64,
+}
+
+#[wasm_bindgen]
+pub fn decode_liquidation_level(data: &[u8]) -> Result<JsValue, JsValue> {
+    let level = LiquidationLevel::from_bytes(data).ok_or_else(|| JsValue::from_str("Not a liquidation level"))?;
+
+    let decoded = DecodedLiquidationLevel {
+        timestamp: to_millis(level.timestamp),
+        exchange_id: level.exchange_id.map_or(0, |id| id as u8),
+        symbol_id: level.symbol_id,
+        long: level.side == Side::Buy,
+        price: level.price as f64 / 100_000_000.0,
+        bucket_size: level.bucket_size as f64 / 100_000_000.0,
+        intensity: level.intensity as f64 / 100_000_000.0,
+    };
+
+    Ok(serde_wasm_bindgen::to_value(&decoded)?)
+}
+
+#[derive(serde::Serialize)]
+pub struct DecodedOpenInterest {
+    pub timestamp: u64,
+    pub exchange_id: u8,
+    pub symbol_id: u32,
+    pub open_interest: f64,
+}
+
+#[wasm_bindgen]
+pub fn decode_open_interest(data: &[u8]) -> Result<JsValue, JsValue> {
+    let oi = OpenInterest::from_bytes(data).ok_or_else(|| JsValue::from_str("Not open interest"))?;
+
+    let decoded = DecodedOpenInterest {
+        timestamp: to_millis(oi.timestamp),
+        exchange_id: oi.exchange_id as u8,
+        symbol_id: oi.symbol_id,
+        open_interest: oi.open_interest as f64 / 100_000_000.0,
+    };
+
+    Ok(serde_wasm_bindgen::to_value(&decoded)?)
+}
+
+#[derive(serde::Serialize)]
+pub struct DecodedMarkPrice {
+    pub timestamp: u64,
+    pub exchange_id: u8,
+    pub symbol_id: u32,
+    pub mark_price: f64,
+    pub index_price: f64,
+    /// Per funding interval, e.g. 0.0001 for 0.01%
+    pub funding_rate: f64,
+    pub next_funding_time: u64,
+}
+
+#[wasm_bindgen]
+pub fn decode_mark_price(data: &[u8]) -> Result<JsValue, JsValue> {
+    let mark = MarkPriceUpdate::from_bytes(data).ok_or_else(|| JsValue::from_str("Not a mark price update"))?;
+    let scale = |raw: i64| raw as f64 / 100_000_000.0;
+
+    let decoded = DecodedMarkPrice {
+        timestamp: to_millis(mark.timestamp),
+        exchange_id: mark.exchange_id as u8,
+        symbol_id: mark.symbol_id,
+        mark_price: scale(mark.mark_price),
+        index_price: scale(mark.index_price),
+        funding_rate: scale(mark.funding_rate),
+        next_funding_time: to_millis(mark.next_funding_time),
+    };
+
+    Ok(serde_wasm_bindgen::to_value(&decoded)?)
+}
+
+#[derive(serde::Serialize)]
+pub struct DecodedForcedLiquidation {
+    pub timestamp: u64,
+    pub exchange_id: u8,
+    pub symbol_id: u32,
+    /// A long position was liquidated, otherwise a short
+    pub long: bool,
+    pub price: f64,
+    pub quantity: f64,
+}
+
 #[wasm_bindgen]
-pub fn calculate_ofi(bid_vol: f64, ask_vol: f64, prev_bid_vol: f64, prev_ask_vol: f64, bid_price: f64, ask_price: f64, prev_bid_price: f64, prev_ask_price: f64) -> f64 {
-    // OFI Calculation Logic
-    // e_n^b (Bid Event)
-    let e_b = if bid_price > prev_bid_price {
-        bid_vol
-    } else if bid_price < prev_bid_price {
-        -prev_bid_vol
-    } else {
-        bid_vol - prev_bid_vol
-    };
-
-    // e_n^a (Ask Event)
-    let e_a = if ask_price > prev_ask_price {
-        -prev_ask_vol
-    } else if ask_price < prev_ask_price {
-        ask_vol
-    } else {
-        ask_vol - prev_ask_vol
-    };
-
-    // OFI = e_b - e_a
-    e_b - e_a
+pub fn decode_forced_liquidation(data: &[u8]) -> Result<JsValue, JsValue> {
+    let liquidation = ForcedLiquidation::from_bytes(data).ok_or_else(|| JsValue::from_str("Not a liquidation"))?;
+
+    let decoded = DecodedForcedLiquidation {
+        timestamp: to_millis(liquidation.timestamp),
+        exchange_id: liquidation.exchange_id as u8,
+        symbol_id: liquidation.symbol_id,
+        long: liquidation.side == Side::Buy,
+        price: liquidation.price as f64 / 100_000_000.0,
+        quantity: liquidation.quantity as f64 / 100_000_000.0,
+    };
+
+    Ok(serde_wasm_bindgen::to_value(&decoded)?)
+}
+
+fn span_name(span: ProfileSpan) -> &'static str {
+    match span {
+        ProfileSpan::Session => "SESSION",
+        ProfileSpan::Rolling => "ROLLING",
+        ProfileSpan::VisibleRange => "VISIBLE_RANGE",
+    }
+}
+
+#[derive(serde::Serialize)]
+pub struct DecodedVolumeProfileLevel {
+    pub timestamp: u64,
+    /// 0 when aggregated across venues
+    pub exchange_id: u8,
+    pub symbol_id: u32,
+    /// "SESSION", "ROLLING" or "VISIBLE_RANGE"
+    pub span: String,
+    /// Lower bound of the bucket
+    pub price: f64,
+    pub bucket_size: f64,
+    /// 0 clears the bucket
+    pub volume: f64,
+    /// "HVN", "LVN" or "" for neither
+    pub node: String,
+}
+
+#[wasm_bindgen]
+pub fn decode_volume_profile_level(data: &[u8]) -> Result<JsValue, JsValue> {
+    let level = VolumeProfileLevel::from_bytes(data).ok_or_else(|| JsValue::from_str("Not a volume profile level"))?;
+    let node = match level.node {
+        VolumeNode::None => "",
+        VolumeNode::High => "HVN",
+        VolumeNode::Low => "LVN",
+    };
+
+    let decoded = DecodedVolumeProfileLevel {
+        timestamp: to_millis(level.timestamp),
+        exchange_id: level.exchange_id.map_or(0, |id| id as u8),
+        symbol_id: level.symbol_id,
+        span: span_name(level.span).to_string(),
+        price: level.price as f64 / 100_000_000.0,
+        bucket_size: level.bucket_size as f64 / 100_000_000.0,
+        volume: level.volume as f64 / 100_000_000.0,
+        node: node.to_string(),
+    };
+
+    Ok(serde_wasm_bindgen::to_value(&decoded)?)
+}
+
+#[derive(serde::Serialize)]
+pub struct DecodedVolumeProfileSummary {
+    pub timestamp: u64,
+    /// 0 when aggregated across venues
+    pub exchange_id: u8,
+    pub symbol_id: u32,
+    pub span: String,
+    pub start: u64,
+    pub end: u64,
+    /// Lower bound of the busiest bucket
+    pub poc: f64,
+    pub value_area_low: f64,
+    pub value_area_high: f64,
+    /// 0 for an empty profile
+    pub total_volume: f64,
+}
+
+#[wasm_bindgen]
+pub fn decode_volume_profile_summary(data: &[u8]) -> Result<JsValue, JsValue> {
+    let summary =
+        VolumeProfileSummary::from_bytes(data).ok_or_else(|| JsValue::from_str("Not a volume profile summary"))?;
+    let scale = |raw: i64| raw as f64 / 100_000_000.0;
+
+    let decoded = DecodedVolumeProfileSummary {
+        timestamp: to_millis(summary.timestamp),
+        exchange_id: summary.exchange_id.map_or(0, |id| id as u8),
+        symbol_id: summary.symbol_id,
+        span: span_name(summary.span).to_string(),
+        start: to_millis(summary.start),
+        end: to_millis(summary.end),
+        poc: scale(summary.poc),
+        value_area_low: scale(summary.value_area_low),
+        value_area_high: scale(summary.value_area_high),
+        total_volume: summary.total_volume as f64 / 100_000_000.0,
+    };
+
+    Ok(serde_wasm_bindgen::to_value(&decoded)?)
 }
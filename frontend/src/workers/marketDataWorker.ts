@@ -1,5 +1,5 @@
 console.log("Worker script started");
-import init, { decode_market_data, calculate_ofi } from "vibe-hft-wasm-client";
+import init, { decode_market_data, decode_pnl_snapshot, decode_cvd_update, decode_divergence_signal, decode_indicator_update, decode_alert, decode_liquidation_level, decode_open_interest, decode_mark_price, decode_forced_liquidation, decode_volume_profile_level, decode_volume_profile_summary, template_id } from "vibe-hft-wasm-client";
 console.log("WASM client imported");
 
 // Initialize WASM
@@ -23,11 +23,18 @@ const initializeWasm = async () => {
 
 initializeWasm();
 
-// State for OFI calculation
-let prevBidVol = 0;
-let prevAskVol = 0;
-let prevBidPrice = 0;
-let prevAskPrice = 0;
+// Template ids of framed gateway messages (see crates/sbe_messages/schema.xml)
+const PNL_SNAPSHOT_TEMPLATE_ID = 6;
+const CVD_UPDATE_TEMPLATE_ID = 7;
+const DIVERGENCE_SIGNAL_TEMPLATE_ID = 8;
+const INDICATOR_UPDATE_TEMPLATE_ID = 9;
+const ALERT_TEMPLATE_ID = 10;
+const LIQUIDATION_LEVEL_TEMPLATE_ID = 11;
+const OPEN_INTEREST_TEMPLATE_ID = 12;
+const MARK_PRICE_TEMPLATE_ID = 13;
+const FORCED_LIQUIDATION_TEMPLATE_ID = 14;
+const VOLUME_PROFILE_LEVEL_TEMPLATE_ID = 15;
+const VOLUME_PROFILE_SUMMARY_TEMPLATE_ID = 16;
 
 self.onmessage = async (e: MessageEvent) => {
     if (!isWasmInitialized) {
@@ -38,32 +45,70 @@ self.onmessage = async (e: MessageEvent) => {
 
     if (type === 'PROCESS_UPDATE') {
         try {
+            // Framed messages carry an SBE header; bare payloads are market data
+            const templateId = template_id(payload);
+            if (templateId === PNL_SNAPSHOT_TEMPLATE_ID) {
+                postMessage({ type: 'PNL_SNAPSHOT', payload: decode_pnl_snapshot(payload) });
+                return;
+            }
+            if (templateId === CVD_UPDATE_TEMPLATE_ID) {
+                postMessage({ type: 'CVD_UPDATE', payload: decode_cvd_update(payload) });
+                return;
+            }
+            if (templateId === DIVERGENCE_SIGNAL_TEMPLATE_ID) {
+                // Chart marker: payload.marker is "R" or "C", placed at pivot_time/pivot_price
+                postMessage({ type: 'DIVERGENCE', payload: decode_divergence_signal(payload) });
+                return;
+            }
+            if (templateId === INDICATOR_UPDATE_TEMPLATE_ID) {
+                // OFI and NOBI computed by the gateway, per venue (exchange_id 0 = global); payload.kind tells them apart
+                postMessage({ type: 'INDICATOR', payload: decode_indicator_update(payload) });
+                return;
+            }
+            if (templateId === ALERT_TEMPLATE_ID) {
+                // payload.name is e.g. "Aggressive Flow"; payload.bullish gives the direction
+                postMessage({ type: 'ALERT', payload: decode_alert(payload) });
+                return;
+            }
+            if (templateId === LIQUIDATION_LEVEL_TEMPLATE_ID) {
+                // One heatmap bucket: [price, price + bucket_size), replaces the previous intensity
+                postMessage({ type: 'LIQUIDATION_LEVEL', payload: decode_liquidation_level(payload) });
+                return;
+            }
+            // Perpetual feeds of Binance USDⓈ-M and Bybit linear
+            if (templateId === OPEN_INTEREST_TEMPLATE_ID) {
+                postMessage({ type: 'OPEN_INTEREST', payload: decode_open_interest(payload) });
+                return;
+            }
+            if (templateId === MARK_PRICE_TEMPLATE_ID) {
+                postMessage({ type: 'MARK_PRICE', payload: decode_mark_price(payload) });
+                return;
+            }
+            if (templateId === FORCED_LIQUIDATION_TEMPLATE_ID) {
+                // Venue-reported liquidation: payload.long tells which side was closed out
+                postMessage({ type: 'FORCED_LIQUIDATION', payload: decode_forced_liquidation(payload) });
+                return;
+            }
+            // Composite volume profiles, keyed by payload.span; levels replace the bucket at payload.price
+            if (templateId === VOLUME_PROFILE_LEVEL_TEMPLATE_ID) {
+                postMessage({ type: 'VOLUME_PROFILE_LEVEL', payload: decode_volume_profile_level(payload) });
+                return;
+            }
+            if (templateId === VOLUME_PROFILE_SUMMARY_TEMPLATE_ID) {
+                postMessage({ type: 'VOLUME_PROFILE_SUMMARY', payload: decode_volume_profile_summary(payload) });
+                return;
+            }
+            if (templateId !== 0) {
+                return;
+            }
+
             // Decode SBE data using WASM
             // payload is Uint8Array
             const decoded = decode_market_data(payload);
 
-            // Calculate OFI if it's a trade or relevant update
-            // For simplicity, we assume decoded has price/quantity/side
-            // In a real scenario, we'd maintain order book state here or in WASM
-
-            // Example OFI calc (simplified)
-            let ofi = 0;
-            if (decoded.side === 'Buy') {
-                ofi = calculate_ofi(decoded.quantity, 0, prevBidVol, prevAskVol, decoded.price, prevAskPrice, prevBidPrice, prevAskPrice);
-                prevBidVol = decoded.quantity;
-                prevBidPrice = decoded.price;
-            } else {
-                ofi = calculate_ofi(0, decoded.quantity, prevBidVol, prevAskVol, prevBidPrice, decoded.price, prevBidPrice, prevAskPrice);
-                prevAskVol = decoded.quantity;
-                prevAskPrice = decoded.price;
-            }
-
             postMessage({
                 type: 'MARKET_UPDATE',
-                payload: {
-                    ...decoded,
-                    ofi
-                }
+                payload: decoded
             });
 
         } catch (err) {
@@ -1,18 +1,39 @@
 use futures_util::{SinkExt, StreamExt};
 use tokio::net::{TcpListener, TcpStream};
 use tokio_tungstenite::{accept_async, connect_async, tungstenite::protocol::Message};
-use tokio::sync::broadcast;
+use tokio::sync::{broadcast, mpsc};
+use tokio::time::{interval, Duration, MissedTickBehavior};
 use url::Url;
-use serde::Deserialize;
-use vibe_hft_sbe_messages::{MarketDataUpdate, ExchangeID, Side};
-use vibe_hft_market_data::OrderBook;
-use vibe_hft_strategy::{Strategy, SimpleMarketMaker};
+use serde::{Deserialize, Deserializer};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::time::Instant;
+use vibe_hft_sbe_messages::{Alert, IndicatorKind, MarketDataUpdate, ExchangeID, Side, Trade};
+use vibe_hft_core::{from_millis, parse_fixed, Instrument, InstrumentType, Timestamp};
+use vibe_hft_market_data::alerts::{AlertEngine, AlertRule};
+use vibe_hft_market_data::cvd::{CvdBar, CvdConfig, CvdEngine};
+use vibe_hft_market_data::divergence::{DivergenceConfig, DivergenceDetector, PriceBar};
+use vibe_hft_market_data::liquidation::{LiquidationConfig, LiquidationEstimator};
+use vibe_hft_market_data::nobi::{DepthBand, NobiConfig, NobiEngine, EQUAL_WEIGHTS};
+use vibe_hft_market_data::ofi::{OfiConfig, OfiEngine};
+use vibe_hft_market_data::volume_profile::{ProfileUpdate, ProfileWindow, VolumeProfile, VolumeProfileConfig};
+use vibe_hft_market_connectors::binance::{parse_perp_event, perp_streams, OpenInterestClient};
+use vibe_hft_market_connectors::bybit::{subscribe_request, BybitPerpFeed, BYBIT_LINEAR_WS_URL};
+use vibe_hft_market_connectors::{PerpEvent, PerpSymbol};
+use vibe_hft_risk::{CircuitBreakers, RiskLimits};
+use vibe_hft_strategy::{FeedStatus, Strategy, StrategyContext, StrategyHost};
 
+const SECOND: Timestamp = 1_000_000_000;
+const DAY: Timestamp = 86_400 * SECOND;
+
+/// Diff depth event (`<symbol>@depth@100ms`): changed levels only, with
+/// quantity 0 for a level that is gone
 #[derive(Deserialize, Debug)]
 struct BinanceDepthUpdate {
-    #[serde(rename = "E")]
-    event_time: u64,
+    #[serde(rename = "E", deserialize_with = "exchange_time")]
+    event_time: Timestamp,
     #[serde(rename = "s")]
+    #[allow(dead_code)]
     symbol: String,
     #[serde(rename = "b")]
     bids: Vec<[String; 2]>,
@@ -20,6 +41,130 @@ struct BinanceDepthUpdate {
     asks: Vec<[String; 2]>,
 }
 
+/// Binance event times are epoch ms; everything past ingestion runs on ns
+fn exchange_time<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Timestamp, D::Error> {
+    u64::deserialize(deserializer).map(from_millis)
+}
+
+/// Envelope of the combined stream endpoint
+#[derive(Deserialize, Debug)]
+struct BinanceStreamMessage {
+    stream: String,
+    data: serde_json::Value,
+}
+
+#[derive(Deserialize, Debug)]
+struct BinanceAggTrade {
+    #[serde(rename = "E", deserialize_with = "exchange_time")]
+    event_time: Timestamp,
+    #[serde(rename = "a")]
+    trade_id: u64,
+    #[serde(rename = "p")]
+    price: String,
+    #[serde(rename = "q")]
+    quantity: String,
+    /// The buyer was the maker, so the aggressor sold
+    #[serde(rename = "m")]
+    buyer_is_maker: bool,
+}
+
+/// Stands in for a strategy until the gateway has an order path: the host
+/// still keeps the books, risk state and kill switch, but nothing quotes
+struct NoStrategy;
+
+impl Strategy for NoStrategy {
+    fn on_book_update(&mut self, _ctx: &mut StrategyContext<'_>, _update: &MarketDataUpdate) {}
+}
+
+/// Notices a silent feed on the local clock. The exchange clock only moves
+/// when messages arrive, so on its own a dead feed would never look stale.
+struct FeedWatchdog {
+    max_silence: Duration,
+    last_message: Instant,
+    stale: bool,
+}
+
+impl FeedWatchdog {
+    fn new(max_silence: Duration, now: Instant) -> Self {
+        Self { max_silence, last_message: now, stale: false }
+    }
+
+    /// Returns `true` if the feed was reported stale until this message
+    fn on_message(&mut self, now: Instant) -> bool {
+        self.last_message = now;
+        std::mem::replace(&mut self.stale, false)
+    }
+
+    /// Report the feed stale once it has been silent past the threshold,
+    /// and run the time-based breakers on the exchange clock carried
+    /// forward by the silence
+    fn check<S: Strategy>(&mut self, host: &mut StrategyHost<S>, exchange_now: Timestamp, now: Instant) {
+        let silence = now.saturating_duration_since(self.last_message);
+        let clock = exchange_now + silence.as_nanos() as Timestamp;
+        if silence >= self.max_silence && !self.stale {
+            self.stale = true;
+            eprintln!("Binance feed silent for {:?}", silence);
+            host.on_feed_status(clock, ExchangeID::Binance, FeedStatus::Stale);
+        }
+        host.check_breakers(clock);
+    }
+}
+
+/// Operator commands sent by frontend clients as JSON text frames,
+/// e.g. `{"type":"kill"}`
+#[derive(Deserialize, Debug, Clone, Copy)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ControlCommand {
+    /// Cancel everything and block new orders
+    Kill,
+    /// Resume trading after a kill
+    Rearm,
+    /// Chart range for the visible-range volume profile, exchange time (ms)
+    VisibleRange { from: u64, to: u64 },
+}
+
+/// Publishes fired alerts to the frontend and appends them as JSON lines to
+/// `$ALERT_LOG` (default `alerts.jsonl`), for evaluating the rules later
+struct AlertSink {
+    tx: broadcast::Sender<Vec<u8>>,
+    file: Option<File>,
+}
+
+impl AlertSink {
+    fn open(tx: broadcast::Sender<Vec<u8>>) -> Self {
+        let path = std::env::var("ALERT_LOG").unwrap_or_else(|_| "alerts.jsonl".to_string());
+        let file = match OpenOptions::new().create(true).append(true).open(&path) {
+            Ok(file) => Some(file),
+            Err(e) => {
+                eprintln!("Alert log {} unavailable: {}", path, e);
+                None
+            }
+        };
+        Self { tx, file }
+    }
+
+    fn publish(&mut self, alert: &Alert) {
+        println!("🚨 Alert {} on {:?} ({:?}): {:?} value {} rate {}/s", alert.rule_id, alert.indicator,
+            alert.exchange_id, alert.direction, alert.value as f64 / 1e8, alert.rate as f64 / 1e8);
+        let _ = self.tx.send(alert.to_bytes().to_vec());
+        if let Some(file) = self.file.as_mut() {
+            let record = serde_json::json!({
+                "timestamp": alert.timestamp,
+                "exchange_id": alert.exchange_id.map_or(0, |id| id as u8),
+                "symbol_id": alert.symbol_id,
+                "rule_id": alert.rule_id,
+                "indicator": alert.indicator as u8,
+                "direction": alert.direction as u8,
+                "value": alert.value,
+                "rate": alert.rate,
+            });
+            if let Err(e) = writeln!(file, "{}", record) {
+                eprintln!("Alert log write failed: {}", e);
+            }
+        }
+    }
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let addr = "127.0.0.1:8080";
@@ -29,10 +174,19 @@ async fn main() -> anyhow::Result<()> {
     // Channel to broadcast market data updates to all connected frontend clients
     let (tx, _) = broadcast::channel::<Vec<u8>>(10000);
 
+    // Control channel from frontend clients to the trading loop
+    let (control_tx, control_rx) = mpsc::unbounded_channel::<ControlCommand>();
+
+    // Perpetual feeds (open interest, mark/index, funding, liquidations) into the trading loop
+    let (perp_tx, perp_rx) = mpsc::unbounded_channel::<PerpEvent>();
+    tokio::spawn(run_binance_perp_client(perp_tx.clone()));
+    tokio::spawn(run_binance_open_interest_poll(perp_tx.clone()));
+    tokio::spawn(run_bybit_perp_client(perp_tx));
+
     // Spawn Binance WebSocket Client
     let tx_binance = tx.clone();
     tokio::spawn(async move {
-        if let Err(e) = run_binance_client(tx_binance).await {
+        if let Err(e) = run_binance_client(tx_binance, control_rx, perp_rx).await {
             eprintln!("Binance client error: {}", e);
         }
     });
@@ -40,14 +194,18 @@ async fn main() -> anyhow::Result<()> {
     // Accept incoming frontend connections
     while let Ok((stream, _)) = listener.accept().await {
         let rx = tx.subscribe();
-        tokio::spawn(accept_connection(stream, rx));
+        tokio::spawn(accept_connection(stream, rx, control_tx.clone()));
     }
 
     Ok(())
 }
 
-async fn run_binance_client(tx: broadcast::Sender<Vec<u8>>) -> anyhow::Result<()> {
-    let url = Url::parse("wss://stream.binance.com:9443/ws/btcusdt@depth20@100ms")?;
+async fn run_binance_client(
+    tx: broadcast::Sender<Vec<u8>>,
+    mut control: mpsc::UnboundedReceiver<ControlCommand>,
+    mut perp: mpsc::UnboundedReceiver<PerpEvent>,
+) -> anyhow::Result<()> {
+    let url = Url::parse("wss://stream.binance.com:9443/stream?streams=btcusdt@depth@100ms/btcusdt@aggTrade")?;
     println!("Connecting to Binance: {}", url);
 
     println!("Attempting handshake with Binance...");
@@ -56,72 +214,399 @@ async fn run_binance_client(tx: broadcast::Sender<Vec<u8>>) -> anyhow::Result<()
 
     let (_, mut read) = ws_stream.split();
 
-    // Initialize OrderBook and Strategy
-    let mut order_book = OrderBook::new();
-    let mut strategy = SimpleMarketMaker::new(10.0, 0.5); // 10 bps spread, 0.5 BTC size
+    // Trips on a lost feed, a stale book or a burst of rejects
+    let limits = RiskLimits {
+        breakers: CircuitBreakers {
+            halt_on_feed_loss: true,
+            max_rejects: 10,
+            reject_window: 1_000_000_000,
+            max_book_age: 5_000_000_000,
+            ..CircuitBreakers::default()
+        },
+        ..RiskLimits::default()
+    };
+
+    // The host owns the order books; no strategy quotes until orders can reach a venue
+    let mut host = StrategyHost::with_risk(NoStrategy, limits);
+    // Silence longer than the stale-book limit counts as a lost feed
+    let mut watchdog = FeedWatchdog::new(Duration::from_nanos(limits.breakers.max_book_age), Instant::now());
+    let mut watchdog_timer = interval(Duration::from_secs(1));
+
+    // CVD candles on exchange time: one-minute bars, sessions from UTC midnight
+    let cvd_config = CvdConfig {
+        bar_interval: 60 * SECOND,
+        session_length: DAY,
+        session_offset: 0,
+    };
+    let mut cvd = CvdEngine::new(1, cvd_config);
+    // Price candles of the same bars, checked against the global CVD once closed
+    let mut price_bar: Option<PriceBar> = None;
+    let divergence_config = DivergenceConfig { cvd: cvd_config, ..DivergenceConfig::default() };
+    let mut divergence = DivergenceDetector::new(None, 1, divergence_config);
+    // OFI from touch transitions over 1s, 10s and 60s of exchange time
+    let mut ofi = OfiEngine::new(1, OfiConfig { windows: [SECOND, 10 * SECOND, 60 * SECOND] });
+    // NOBI over the top 20 levels with 24h volume weights, sampled every 100ms
+    let mut nobi = NobiEngine::new(1, NobiConfig {
+        band: DepthBand::Levels(20),
+        volume_window: DAY,
+        interval: SECOND / 10,
+        smoothing: SECOND,
+        fallback_weights: EQUAL_WEIGHTS,
+    });
+    // Aggressive Flow on the global NOBI, both ways, at most once a minute each
+    let mut alerts = AlertEngine::new(1, SECOND);
+    alerts.add_rule(AlertRule::aggressive_flow(None, Side::Buy, 60 * SECOND));
+    alerts.add_rule(AlertRule::aggressive_flow(None, Side::Sell, 60 * SECOND));
+    let mut alert_sink = AlertSink::open(tx.clone());
+    // Liquidation heatmap on exchange time: 25 USDT buckets, 7-day half-life
+    // applied every minute. Binance perp open interest projected at the spot VWAP.
+    let mut liquidations = LiquidationEstimator::new(Some(ExchangeID::Binance), 1, LiquidationConfig {
+        half_life: 7 * DAY,
+        decay_interval: 60 * SECOND,
+        ..LiquidationConfig::default()
+    });
+    // Composite volume profiles across venues on exchange time: the UTC day,
+    // the last hour and the chart's visible range (all 24h kept until a client sets one)
+    let profile_config = VolumeProfileConfig {
+        slice: 30 * 60 * SECOND,
+        session_length: DAY,
+        interval: SECOND,
+        ..VolumeProfileConfig::default()
+    };
+    let mut profiles = [
+        VolumeProfile::new(None, 1, ProfileWindow::Session, profile_config),
+        VolumeProfile::new(None, 1, ProfileWindow::Rolling(3_600 * SECOND), profile_config),
+        VolumeProfile::new(None, 1, ProfileWindow::VisibleRange { from: 0, to: u64::MAX }, profile_config),
+    ];
+    // NOBI sampling and every expiry/decay timer, on a 100ms local tick
+    let mut indicator_timer = interval(Duration::from_millis(100));
+    indicator_timer.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+    // Latest exchange time across both streams, used as the clock; it never
+    // steps back when aggTrade and depth events interleave out of order
+    let mut now = 0;
+    let mut next_pnl_snapshot = 0;
+
+    loop {
+        let msg = tokio::select! {
+            msg = read.next() => match msg {
+                Some(msg) => {
+                    if watchdog.on_message(Instant::now()) {
+                        println!("Binance feed resumed");
+                        host.on_feed_status(now, ExchangeID::Binance, FeedStatus::Connected);
+                    }
+                    msg
+                }
+                None => break,
+            },
+            _ = watchdog_timer.tick() => {
+                watchdog.check(&mut host, now, Instant::now());
+                continue;
+            }
+            Some(command) = control.recv() => {
+                match command {
+                    ControlCommand::Kill => {
+                        if host.kill(now) {
+                            println!("🛑 Kill switch activated by operator");
+                        }
+                    }
+                    ControlCommand::Rearm => {
+                        if host.rearm() {
+                            println!("Kill switch re-armed by operator");
+                        }
+                    }
+                    ControlCommand::VisibleRange { from, to } => {
+                        let [_, _, visible] = &mut profiles;
+                        let window = ProfileWindow::VisibleRange { from: from_millis(from), to: from_millis(to) };
+                        visible.set_window(window);
+                    }
+                }
+                continue;
+            }
+            Some(event) = perp.recv() => {
+                let _ = tx.send(event.to_bytes());
+                if let PerpEvent::OpenInterest(oi) = event {
+                    if oi.exchange_id == ExchangeID::Binance {
+                        liquidations.on_open_interest(oi.timestamp, oi.open_interest, |level| {
+                            let _ = tx.send(level.to_bytes().to_vec());
+                        });
+                    }
+                }
+                continue;
+            }
+            _ = indicator_timer.tick() => {
+                // Samples on exchange time, so a silent feed publishes nothing new
+                nobi.on_timer(now, host.books(), |update| {
+                    let _ = tx.send(update.to_bytes().to_vec());
+                    alerts.on_indicator(update, |alert| alert_sink.publish(alert));
+                });
+                // OFI windows expire even while the touch does not move
+                ofi.on_timer(now, |update| {
+                    let _ = tx.send(update.to_bytes().to_vec());
+                    alerts.on_indicator(update, |alert| alert_sink.publish(alert));
+                });
+                liquidations.on_timer(now, |level| {
+                    let _ = tx.send(level.to_bytes().to_vec());
+                });
+
+                // Final candles of bars that ended without a trade
+                let mut closed = None;
+                cvd.on_timer(now, |update| {
+                    let _ = tx.send(update.to_bytes().to_vec());
+                    if update.exchange_id.is_none() {
+                        closed = Some(CvdBar::from(update));
+                    }
+                });
+                if let (Some(cvd_bar), Some(bar)) = (closed, price_bar.take()) {
+                    divergence.on_bar(&bar, &cvd_bar, |signal| {
+                        let _ = tx.send(signal.to_bytes().to_vec());
+                    });
+                }
+
+                // PnL snapshots for the frontend, once a second of exchange time
+                if now >= next_pnl_snapshot {
+                    next_pnl_snapshot = now + SECOND;
+                    for snapshot in host.positions().snapshots(now) {
+                        let _ = tx.send(snapshot.to_bytes().to_vec());
+                    }
+                }
+                for profile in profiles.iter_mut() {
+                    profile.on_timer(now, |update| {
+                        let _ = match update {
+                            ProfileUpdate::Level(level) => tx.send(level.to_bytes().to_vec()),
+                            ProfileUpdate::Summary(summary) => tx.send(summary.to_bytes().to_vec()),
+                        };
+                    });
+                }
+                continue;
+            }
+        };
 
-    while let Some(msg) = read.next().await {
         match msg {
             Ok(Message::Text(text)) => {
                 // println!("Received msg: {:.50}...", text); // Debug log
-                if let Ok(update) = serde_json::from_str::<BinanceDepthUpdate>(&text) {
-                    // Process Bids
-                    for bid in update.bids {
-                        let price = (bid[0].parse::<f64>().unwrap_or(0.0) * 100_000_000.0) as i64;
-                        let quantity = (bid[1].parse::<f64>().unwrap_or(0.0) * 100_000_000.0) as u64;
-                        
-                        let sbe_update = MarketDataUpdate {
-                            timestamp: update.event_time,
+                let Ok(message) = serde_json::from_str::<BinanceStreamMessage>(&text) else {
+                    continue;
+                };
+
+                if message.stream.ends_with("@aggTrade") {
+                    if let Ok(agg) = serde_json::from_value::<BinanceAggTrade>(message.data) {
+                        now = now.max(agg.event_time);
+                        let trade = Trade {
+                            timestamp: agg.event_time,
                             exchange_id: ExchangeID::Binance,
                             symbol_id: 1,
-                            side: Side::Buy,
-                            price,
-                            quantity,
-                            is_snapshot: 0,
+                            trade_id: agg.trade_id,
+                            aggressor_side: if agg.buyer_is_maker { Side::Sell } else { Side::Buy },
+                            price: (agg.price.parse::<f64>().unwrap_or(0.0) * 100_000_000.0) as i64,
+                            quantity: (agg.quantity.parse::<f64>().unwrap_or(0.0) * 100_000_000.0) as u64,
                         };
-                        
-                        // Update OrderBook and Run Strategy
-                        order_book.apply_update(&sbe_update);
-                        strategy.on_market_data(&mut order_book);
 
+                        host.on_trade(now, &trade);
+                        nobi.on_trade(&trade);
+                        profiles.iter_mut().for_each(|profile| profile.on_trade(&trade));
+                        liquidations.on_trade(&trade, |level| {
+                            let _ = tx.send(level.to_bytes().to_vec());
+                        });
+
+                        let mut closed = None;
+                        cvd.on_trade(&trade, |update| {
+                            let _ = tx.send(update.to_bytes().to_vec());
+                            alerts.on_cvd(update, |alert| alert_sink.publish(alert));
+                            if update.closed && update.exchange_id.is_none() {
+                                closed = Some(CvdBar::from(update));
+                            }
+                        });
+                        if let (Some(cvd_bar), Some(bar)) = (closed, price_bar.take()) {
+                            divergence.on_bar(&bar, &cvd_bar, |signal| {
+                                let _ = tx.send(signal.to_bytes().to_vec());
+                            });
+                        }
+
+                        let bar_start = trade.timestamp - trade.timestamp % cvd_config.bar_interval;
+                        match price_bar.as_mut() {
+                            Some(bar) if bar.start == bar_start => bar.add(&trade),
+                            _ => price_bar = Some(PriceBar::open_with(bar_start, &trade)),
+                        }
+                    }
+                    continue;
+                }
+
+                if let Some(updates) = parse_depth_update(message.data) {
+                    for sbe_update in &updates {
+                        now = now.max(sbe_update.timestamp);
+                        host.on_market_data(sbe_update.timestamp, sbe_update);
                         let _ = tx.send(sbe_update.to_bytes().to_vec());
                     }
 
-                    // Process Asks
-                    for ask in update.asks {
-                        let price = (ask[0].parse::<f64>().unwrap_or(0.0) * 100_000_000.0) as i64;
-                        let quantity = (ask[1].parse::<f64>().unwrap_or(0.0) * 100_000_000.0) as u64;
-                        
-                        let sbe_update = MarketDataUpdate {
-                            timestamp: update.event_time,
-                            exchange_id: ExchangeID::Binance,
-                            symbol_id: 1,
-                            side: Side::Sell,
-                            price,
-                            quantity,
-                            is_snapshot: 0,
-                        };
-                        
-                        // Update OrderBook and Run Strategy
-                        order_book.apply_update(&sbe_update);
-                        strategy.on_market_data(&mut order_book);
+                    // The touch after the whole depth message, so OFI only sees real transitions
+                    ofi.on_book(now, ExchangeID::Binance, host.books().book(ExchangeID::Binance), |update| {
+                        let _ = tx.send(update.to_bytes().to_vec());
+                        alerts.on_indicator(update, |alert| alert_sink.publish(alert));
+                    });
 
-                        let _ = tx.send(sbe_update.to_bytes().to_vec());
+                    // Spread of the quoted venue, for spread rules
+                    let book = host.books().book(ExchangeID::Binance);
+                    let touch = (book.next_level(Side::Buy, None), book.next_level(Side::Sell, None));
+                    if let (Some(bid), Some(ask)) = touch {
+                        let mid = (bid.price + ask.price) as f64 / 2.0;
+                        let bps = (ask.price - bid.price) as f64 / mid * 10_000.0;
+                        alerts.on_value(now, IndicatorKind::Spread, Some(ExchangeID::Binance), 0, bps, |alert| {
+                            alert_sink.publish(alert)
+                        });
                     }
                 }
             }
-            Ok(Message::Ping(ping)) => {
+            Ok(Message::Ping(_ping)) => {
                 // Handle ping if needed, tungstenite usually handles it
             }
-            Err(e) => eprintln!("Error reading from Binance: {}", e),
+            Err(e) => {
+                eprintln!("Error reading from Binance: {}", e);
+                host.on_feed_status(now, ExchangeID::Binance, FeedStatus::Disconnected);
+                // Touch changes across a gap are not flow
+                ofi.reset();
+                nobi.reset();
+            }
             _ => {}
         }
     }
 
+    host.on_feed_status(now, ExchangeID::Binance, FeedStatus::Disconnected);
     Ok(())
 }
 
-async fn accept_connection(stream: TcpStream, mut rx: broadcast::Receiver<Vec<u8>>) {
+/// Level updates of one diff depth event, bids then asks, or `None` if
+/// `data` is not a depth event
+fn parse_depth_update(data: serde_json::Value) -> Option<Vec<MarketDataUpdate>> {
+    let update = serde_json::from_value::<BinanceDepthUpdate>(data).ok()?;
+    let bids = update.bids.iter().map(|level| (Side::Buy, level));
+    let asks = update.asks.iter().map(|level| (Side::Sell, level));
+    // Exact decimal parsing: a float round trip can land a price off the tick
+    let updates = bids
+        .chain(asks)
+        .filter_map(|(side, [price, quantity])| {
+            Some(MarketDataUpdate {
+                timestamp: update.event_time,
+                exchange_id: ExchangeID::Binance,
+                symbol_id: 1,
+                side,
+                price: parse_fixed(price)?,
+                quantity: parse_fixed(quantity)? as u64,
+                is_snapshot: 0,
+            })
+        })
+        .collect();
+    Some(updates)
+}
+
+/// BTCUSDT perpetual on both derivatives venues, under the spot symbol id
+/// so its open interest lines up with the spot trades
+fn perp_symbols() -> Vec<PerpSymbol> {
+    vec![PerpSymbol::new("BTCUSDT", Instrument {
+        symbol_id: 1,
+        instrument_type: InstrumentType::Perpetual,
+        tick_size: 10_000_000, // 0.1 USDT
+        lot_size: 100_000,     // 0.001 BTC
+    })]
+}
+
+/// Binance USDⓈ-M mark price and liquidations from the stream. Reconnects
+/// after 5s on error.
+async fn run_binance_perp_client(tx: mpsc::UnboundedSender<PerpEvent>) {
+    let symbols = perp_symbols();
+    let url = format!("wss://fstream.binance.com{}", perp_streams(&symbols));
+    loop {
+        match connect_async(url.as_str()).await {
+            Ok((ws_stream, _)) => {
+                println!("✅ Connected to Binance USDⓈ-M: {}", url);
+                let (_, mut read) = ws_stream.split();
+                loop {
+                    match read.next().await {
+                        Some(Ok(Message::Text(text))) => parse_perp_event(&text, &symbols, |e| {
+                            let _ = tx.send(e);
+                        }),
+                        Some(Ok(_)) => {}
+                        Some(Err(e)) => {
+                            eprintln!("Error reading from Binance USDⓈ-M: {}", e);
+                            break;
+                        }
+                        None => break,
+                    }
+                }
+            }
+            Err(e) => eprintln!("Binance USDⓈ-M connect failed: {}", e),
+        }
+        tokio::time::sleep(Duration::from_secs(5)).await;
+    }
+}
+
+/// Binance USDⓈ-M open interest, which is only served over REST, polled
+/// every 5s. Its own task so a slow request never holds up the streams.
+async fn run_binance_open_interest_poll(tx: mpsc::UnboundedSender<PerpEvent>) {
+    let symbols = perp_symbols();
+    let client = OpenInterestClient::new("https://fapi.binance.com").expect("static REST url");
+    let mut poll = interval(Duration::from_secs(5));
+    poll.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    loop {
+        poll.tick().await;
+        for symbol in &symbols {
+            match client.fetch(symbol).await {
+                Ok(oi) => {
+                    let _ = tx.send(PerpEvent::OpenInterest(oi));
+                }
+                Err(e) => eprintln!("Binance open interest for {}: {}", symbol.name, e),
+            }
+        }
+    }
+}
+
+/// Bybit linear tickers and liquidations, with the 20s application ping the
+/// venue expects. Reconnects after 5s on error.
+async fn run_bybit_perp_client(tx: mpsc::UnboundedSender<PerpEvent>) {
+    let mut feed = BybitPerpFeed::new(perp_symbols());
+    loop {
+        match connect_async(BYBIT_LINEAR_WS_URL).await {
+            Ok((ws_stream, _)) => {
+                println!("✅ Connected to Bybit linear: {}", BYBIT_LINEAR_WS_URL);
+                let (mut write, mut read) = ws_stream.split();
+                feed.reset();
+                if let Err(e) = write.send(Message::Text(subscribe_request(feed.symbols()))).await {
+                    eprintln!("Bybit subscribe failed: {}", e);
+                } else {
+                    let mut ping = interval(Duration::from_secs(20));
+                    loop {
+                        tokio::select! {
+                            msg = read.next() => match msg {
+                                Some(Ok(Message::Text(text))) => feed.on_message(&text, |e| {
+                                    let _ = tx.send(e);
+                                }),
+                                Some(Ok(_)) => {}
+                                Some(Err(e)) => {
+                                    eprintln!("Error reading from Bybit linear: {}", e);
+                                    break;
+                                }
+                                None => break,
+                            },
+                            _ = ping.tick() => {
+                                if write.send(Message::Text(r#"{"op":"ping"}"#.to_string())).await.is_err() {
+                                    break;
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            Err(e) => eprintln!("Bybit linear connect failed: {}", e),
+        }
+        tokio::time::sleep(Duration::from_secs(5)).await;
+    }
+}
+
+async fn accept_connection(
+    stream: TcpStream,
+    mut rx: broadcast::Receiver<Vec<u8>>,
+    control: mpsc::UnboundedSender<ControlCommand>,
+) {
     let addr = stream.peer_addr().expect("connected streams should have a peer address");
     println!("New Frontend connection: {}", addr);
 
@@ -129,7 +614,22 @@ async fn accept_connection(stream: TcpStream, mut rx: broadcast::Receiver<Vec<u8
         .await
         .expect("Error during the websocket handshake occurred");
 
-    let (mut write, _) = ws_stream.split();
+    let (mut write, mut read) = ws_stream.split();
+
+    // Control messages from this client
+    tokio::spawn(async move {
+        while let Some(Ok(msg)) = read.next().await {
+            if let Message::Text(text) = msg {
+                match serde_json::from_str::<ControlCommand>(&text) {
+                    Ok(command) => {
+                        println!("Control command from {}: {:?}", addr, command);
+                        let _ = control.send(command);
+                    }
+                    Err(e) => eprintln!("Invalid control message from {}: {}", addr, e),
+                }
+            }
+        }
+    });
 
     loop {
         match rx.recv().await {
@@ -151,3 +651,53 @@ async fn accept_connection(stream: TcpStream, mut rx: broadcast::Receiver<Vec<u8
     }
     println!("Frontend connection closed: {}", addr);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vibe_hft_risk::KillReason;
+
+    /// Recorded frame of the combined stream
+    const DEPTH_FRAME: &str = r#"{"stream":"btcusdt@depth@100ms","data":{"e":"depthUpdate","E":1700000000123,"s":"BTCUSDT","U":40351170001,"u":40351170004,"b":[["37250.01000000","0.51200000"],["37249.50000000","0.00000000"]],"a":[["37250.02000000","1.20000000"]]}}"#;
+
+    #[test]
+    fn test_combined_stream_depth_frame() {
+        let message = serde_json::from_str::<BinanceStreamMessage>(DEPTH_FRAME).unwrap();
+        assert_eq!(message.stream, "btcusdt@depth@100ms");
+        let updates = parse_depth_update(message.data).unwrap();
+
+        let levels: Vec<_> = updates.iter().map(|u| (u.side, u.price, u.quantity)).collect();
+        assert_eq!(levels, [
+            (Side::Buy, 3_725_001_000_000, 51_200_000),
+            (Side::Buy, 3_724_950_000_000, 0),
+            (Side::Sell, 3_725_002_000_000, 120_000_000),
+        ]);
+        assert!(updates.iter().all(|u| (u.timestamp, u.exchange_id) == (1_700_000_000_123_000_000, ExchangeID::Binance)));
+
+        // Partial-book snapshots are a different shape
+        let snapshot = serde_json::json!({"lastUpdateId": 1, "bids": [["37250.01", "1.0"]], "asks": []});
+        assert!(parse_depth_update(snapshot).is_none());
+    }
+
+    #[test]
+    fn test_watchdog_halts_a_silent_feed() {
+        let limits = RiskLimits {
+            breakers: CircuitBreakers { halt_on_feed_loss: true, ..CircuitBreakers::default() },
+            ..RiskLimits::default()
+        };
+        let mut host = StrategyHost::with_risk(NoStrategy, limits);
+        let start = Instant::now();
+        let mut watchdog = FeedWatchdog::new(Duration::from_secs(5), start);
+
+        // No market events at all: only the local clock moves
+        watchdog.check(&mut host, 0, start + Duration::from_secs(4));
+        assert!(!host.risk().kill_switch().is_tripped());
+        watchdog.check(&mut host, 0, start + Duration::from_secs(5));
+        let (reason, at) = host.risk().kill_switch().tripped().unwrap();
+        assert_eq!((reason, at), (KillReason::FeedLoss(ExchangeID::Binance), 5 * SECOND));
+
+        // The next message clears the stale state
+        assert!(watchdog.on_message(start + Duration::from_secs(6)));
+        assert!(!watchdog.on_message(start + Duration::from_secs(7)));
+    }
+}